@@ -22,7 +22,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     let mut pin = Gpio::new()?.get(GPIO_LED)?.into_output();
 
     loop {
-        pin.toggle();
+        pin.toggle()?;
         thread::sleep(Duration::from_millis(500));
     }
 }
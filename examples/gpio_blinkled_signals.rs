@@ -35,12 +35,12 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     // Blink the LED until running is set to false.
     while running.load(Ordering::SeqCst) {
-        pin.toggle();
+        pin.toggle()?;
         thread::sleep(Duration::from_millis(500));
     }
 
     // After we're done blinking, turn the LED off.
-    pin.set_low();
+    pin.set_low()?;
 
     Ok(())
 
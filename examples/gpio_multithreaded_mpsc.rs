@@ -26,9 +26,9 @@ fn main() -> Result<(), Box<dyn Error>> {
         while let Some(count) = receiver.recv().unwrap() {
             println!("Blinking the LED {} times.", count);
             for _ in 0u8..count {
-                pin.set_high();
+                pin.set_high()?;
                 thread::sleep(Duration::from_millis(250));
-                pin.set_low();
+                pin.set_low()?;
                 thread::sleep(Duration::from_millis(250));
             }
         }
@@ -24,32 +24,34 @@ fn main() -> Result<(), Box<dyn Error>> {
         // Clone the Arc so it can be moved to the spawned thread.
         let output_pin_clone = Arc::clone(&output_pin);
 
-        threads.push(thread::spawn(move || {
+        threads.push(thread::spawn(move || -> Result<(), rppal::gpio::Error> {
             // Lock the Mutex on the spawned thread to get exclusive access to the OutputPin.
             let mut pin = output_pin_clone.lock().unwrap();
             println!("Blinking the LED from thread {}.", thread_id);
-            pin.set_high();
+            pin.set_high()?;
             thread::sleep(Duration::from_millis(250));
-            pin.set_low();
+            pin.set_low()?;
             thread::sleep(Duration::from_millis(250));
+
             // The MutexGuard is automatically dropped here.
+            Ok(())
         }));
     });
 
     // Lock the Mutex on the main thread to get exclusive access to the OutputPin.
     let mut pin = output_pin.lock().unwrap();
     println!("Blinking the LED from the main thread.");
-    pin.set_high();
+    pin.set_high()?;
     thread::sleep(Duration::from_millis(250));
-    pin.set_low();
+    pin.set_low()?;
     thread::sleep(Duration::from_millis(250));
     // Manually drop the MutexGuard so the Mutex doesn't stay locked indefinitely.
     drop(pin);
 
     // Wait until all threads have finished executing.
-    threads
-        .into_iter()
-        .for_each(|thread| thread.join().unwrap());
+    for thread in threads {
+        thread.join().unwrap()?;
+    }
 
     Ok(())
 }
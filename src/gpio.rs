@@ -4,6 +4,15 @@
 //! accessing the registers through either `/dev/gpiomem` or `/dev/mem`. GPIO interrupts
 //! are configured using the `gpiochip` character device.
 //!
+//! If the GPIO registers can't be memory-mapped, for instance inside a container that only
+//! has access to `/dev/gpiochipN`, [`Gpio::new`] falls back to controlling the pins through
+//! `gpiochip` line requests, which is considerably slower. A specific [`Backend`] can be
+//! selected with [`Gpio::with_backend`].
+//!
+//! Other GPIO controllers exposed through a `gpiochip` character device, such as I/O expanders
+//! or the GPIO peripheral on a different single-board computer, can be accessed with
+//! [`Gpio::with_chip`].
+//!
 //! ## Pins
 //!
 //! GPIO pins are retrieved from a [`Gpio`] instance by their BCM GPIO number by calling
@@ -18,24 +27,88 @@
 //! (or a derived [`InputPin`], [`OutputPin`] or [`IoPin`]) goes out of scope, it can be
 //! retrieved again through another [`Gpio::get`] call.
 //!
+//! [`Gpio::get`] only prevents a pin from being used more than once within the current
+//! process. Enable [`Gpio::set_line_ownership`] to also request the pin's line from the
+//! `gpiochip`, which prevents other processes and kernel drivers from using the pin at the
+//! same time.
+//!
+//! [`Gpio::line_info`] and [`Gpio::line_infos`] retrieve the kernel's [`LineInfo`] for a pin,
+//! which includes the line's name and consumer label, and whether it's currently in use by
+//! another process or a kernel driver.
+//!
+//! To detect when a line is requested, released or reconfigured, [`Gpio::watch_lines`] returns
+//! a [`LineWatcher`] that reports each [`LineChange`], either blocking or with a timeout.
+//! [`Gpio::watch_lines_async`] executes a callback on a separate thread instead.
+//!
+//! Alternatively, [`Gpio::get_physical`] retrieves a GPIO pin by its physical pin number
+//! on the GPIO header. The header's pinout for the current model is available through
+//! [`Gpio::header`].
+//!
+//! When a pin is configured for one of its alternate functions, [`Pin::function_name`] returns
+//! the name of the selected peripheral signal, such as `SPI0_MOSI` or `TXD1`. The underlying
+//! function tables can be queried directly through [`function_name`], and [`function_pins`]
+//! finds the pins and modes that provide a specific signal.
+//!
 //! By default, pins are reset to their original state when they go out of scope.
 //! Use [`InputPin::set_reset_on_drop(false)`], [`OutputPin::set_reset_on_drop(false)`]
 //! or [`IoPin::set_reset_on_drop(false)`], respectively, to disable this behavior.
 //! Note that `drop` methods aren't called when a process is abnormally terminated (for
 //! instance when a `SIGINT` signal isn't caught).
 //!
+//! Multiple [`OutputPin`]s can be grouped into an [`OutputPort`], which changes the output state
+//! of all pins simultaneously, rather than one pin at a time. Similarly, an [`InputPort`] reads
+//! the logic level of multiple [`InputPin`]s simultaneously.
+//!
 //! ## Interrupts
 //!
-//! [`InputPin`] supports both synchronous and asynchronous interrupt handlers.
+//! [`InputPin`] supports both synchronous and asynchronous interrupt handlers. The same
+//! interrupt API is available on [`IoPin`] while it's in input mode, and on [`OutputPin`]
+//! when it's configured as an open-drain or open-source output, which can be used to detect
+//! when an external device pulls the line.
 //!
 //! Synchronous (blocking) interrupt triggers are configured using [`InputPin::set_interrupt`].
 //! An interrupt trigger for a single pin can be polled with [`InputPin::poll_interrupt`],
 //! which blocks the current thread until a trigger event occurs, or until the timeout period
 //! elapses. [`Gpio::poll_interrupts`] should be used when multiple pins have been configured
 //! for synchronous interrupt triggers, and need to be polled simultaneously.
+//! [`Gpio::poll_interrupts_batch`] returns all trigger events that are waiting for the
+//! specified pins at once, which avoids losing edges on pins that trigger at a high rate.
 //!
 //! Asynchronous interrupt triggers are configured using [`InputPin::set_async_interrupt`]. The
 //! specified callback function will be executed on a separate thread when a trigger event occurs.
+//! To avoid spawning a thread for each pin, [`Gpio::set_interrupt_dispatcher`] enables a
+//! single shared dispatcher thread, which executes the callbacks for all pins.
+//!
+//! To integrate interrupts with an external event loop, such as `mio`, `calloop` or `glib`,
+//! [`InputPin::interrupt_fd`] returns an [`InterruptFd`], which provides access to the
+//! underlying event fd, and retrieves trigger events without blocking.
+//!
+//! When the `async` feature is enabled, [`InputPin::wait_for_edge`] asynchronously waits for
+//! a single trigger event, and [`InputPin::interrupt_stream`] returns an [`InterruptStream`]
+//! that yields every trigger event. Instead of spawning a thread for each pin, a single
+//! background thread wakes the relevant tasks, which makes them suitable for use with any
+//! async runtime. [`InterruptStream`] implements the `futures-core` `Stream` trait, and
+//! when the `hal` feature is also enabled, [`InputPin`] and [`IoPin`] implement the
+//! `embedded-hal-async` `Wait` trait. The `async` feature requires Rust v1.75.0 or later.
+//!
+//! Trigger events are reported as an [`Event`], which contains the edge that triggered the
+//! interrupt and the timestamp recorded by the kernel when the edge was detected.
+//!
+//! ## Pad control
+//!
+//! [`OutputPin`] and [`IoPin`] provide access to the pin's pad configuration, which
+//! determines the electrical characteristics of the pin, such as the output drive strength,
+//! slew rate and whether the input Schmitt trigger is enabled. Pad control is currently only
+//! supported on the Raspberry Pi 5 through the [`Backend::GpioMem`] backend. Unsupported
+//! configurations return `Err(`[`Error::NotSupported`]`)`.
+//!
+//! Earlier models configure the pads through a separate PADS register block, which isn't
+//! part of the memory range exposed by `/dev/gpiomem`, and is only accessible through
+//! `/dev/mem` with root privileges. The BCM283x and BCM2711 also don't configure the pads
+//! for each pin individually. The drive strength, slew rate and hysteresis are shared by
+//! every pin in the same bank (GPIO0-27, GPIO28-45 and GPIO46-53), which can't be
+//! represented by the per-pin methods on [`OutputPin`] and [`IoPin`] without affecting
+//! other pins, and the input buffer can't be disabled at all.
 //!
 //! ## Software-based PWM
 //!
@@ -51,6 +124,20 @@
 //! function call overhead, typical jitter is expected to be up to 10 µs on debug builds, and up to
 //! 2 µs on release builds.
 //!
+//! ## Simulation and custom backends
+//!
+//! [`Gpio::simulated`] constructs a `Gpio` instance that simulates the GPIO peripheral of a
+//! specific Raspberry Pi model in memory, which allows code that uses the GPIO pins to be
+//! tested without any hardware, for instance as part of a CI pipeline. Pins are configured
+//! through the same API. The [`Simulator`] returned by [`Gpio::simulator`] drives the pins'
+//! input levels, and provides access to their output levels, modes and bias. Level changes
+//! are reported to any configured interrupt triggers.
+//!
+//! Custom backends can be plugged in by implementing the [`Registers`] trait, and
+//! constructing a `Gpio` instance through [`Gpio::with_registers`]. The custom backend
+//! controls each pin's level, mode and bias, while the pin numbers, header pinout and
+//! alternate functions match the specified Raspberry Pi model.
+//!
 //! ## Examples
 //!
 //! Basic example:
@@ -65,9 +152,9 @@
 //! let gpio = Gpio::new()?;
 //! let mut pin = gpio.get(23)?.into_output();
 //!
-//! pin.set_high();
+//! pin.set_high()?;
 //! thread::sleep(Duration::from_secs(1));
-//! pin.set_low();
+//! pin.set_low()?;
 //! # Ok(())
 //! # }
 //! ```
@@ -94,36 +181,73 @@
 //! can be found at [raspberrypi/linux#1225] and [raspberrypi/linux#2289].
 //!
 //! [`Error::PinNotAvailable`]: enum.Error.html#variant.PinNotAvailable
+//! [`Gpio::get_physical`]: struct.Gpio.html#method.get_physical
+//! [`Gpio::set_line_ownership`]: struct.Gpio.html#method.set_line_ownership
+//! [`Gpio::line_info`]: struct.Gpio.html#method.line_info
+//! [`Gpio::line_infos`]: struct.Gpio.html#method.line_infos
+//! [`LineInfo`]: struct.LineInfo.html
+//! [`Gpio::watch_lines`]: struct.Gpio.html#method.watch_lines
+//! [`Gpio::watch_lines_async`]: struct.Gpio.html#method.watch_lines_async
+//! [`LineWatcher`]: struct.LineWatcher.html
+//! [`LineChange`]: struct.LineChange.html
+//! [`Gpio::header`]: struct.Gpio.html#method.header
+//! [`Pin::function_name`]: struct.Pin.html#method.function_name
+//! [`function_name`]: fn.function_name.html
+//! [`function_pins`]: fn.function_pins.html
+//! [`Error::NotSupported`]: enum.Error.html#variant.NotSupported
+//! [`Backend::GpioMem`]: enum.Backend.html#variant.GpioMem
 //! [`PermissionDenied`]: enum.Error.html#variant.PermissionDenied
 //! [raspberrypi/linux#1225]: https://github.com/raspberrypi/linux/issues/1225
 //! [raspberrypi/linux#2289]: https://github.com/raspberrypi/linux/issues/2289
 //! [`Gpio`]: struct.Gpio.html
+//! [`Gpio::new`]: struct.Gpio.html#method.new
+//! [`Gpio::with_backend`]: struct.Gpio.html#method.with_backend
+//! [`Gpio::with_chip`]: struct.Gpio.html#method.with_chip
+//! [`Backend`]: enum.Backend.html
 //! [`Gpio::get`]: struct.Gpio.html#method.get
 //! [`Gpio::poll_interrupts`]: struct.Gpio.html#method.poll_interrupts
+//! [`Event`]: struct.Event.html
 //! [`Pin`]: struct.Pin.html
 //! [`InputPin`]: struct.InputPin.html
+//! [`InputPort`]: struct.InputPort.html
 //! [`InputPin::set_reset_on_drop(false)`]: struct.InputPin.html#method.set_reset_on_drop
 //! [`InputPin::set_interrupt`]: struct.InputPin.html#method.set_interrupt
 //! [`InputPin::poll_interrupt`]: struct.InputPin.html#method.poll_interrupt
 //! [`InputPin::set_async_interrupt`]: struct.InputPin.html#method.set_async_interrupt
+//! [`Gpio::poll_interrupts_batch`]: struct.Gpio.html#method.poll_interrupts_batch
+//! [`Gpio::set_interrupt_dispatcher`]: struct.Gpio.html#method.set_interrupt_dispatcher
+//! [`InputPin::interrupt_fd`]: struct.InputPin.html#method.interrupt_fd
+//! [`InterruptFd`]: struct.InterruptFd.html
+//! [`InputPin::wait_for_edge`]: struct.InputPin.html#method.wait_for_edge
+//! [`InputPin::interrupt_stream`]: struct.InputPin.html#method.interrupt_stream
+//! [`InterruptStream`]: struct.InterruptStream.html
 //! [`OutputPin`]: struct.OutputPin.html
+//! [`OutputPort`]: struct.OutputPort.html
 //! [`OutputPin::set_reset_on_drop(false)`]: struct.OutputPin.html#method.set_reset_on_drop
 //! [`IoPin`]: struct.IoPin.html
 //! [`IoPin::set_reset_on_drop(false)`]: struct.IoPin.html#method.set_reset_on_drop
 //! [`Pwm`]: ../pwm/struct.Pwm.html
+//! [`Gpio::simulated`]: struct.Gpio.html#method.simulated
+//! [`Gpio::simulator`]: struct.Gpio.html#method.simulator
+//! [`Simulator`]: struct.Simulator.html
+//! [`Registers`]: trait.Registers.html
+//! [`Gpio::with_registers`]: struct.Gpio.html#method.with_registers
 
 use std::error;
 use std::fmt;
 use std::io;
 use std::mem::MaybeUninit;
 use std::ops::Not;
+use std::os::unix::fs::MetadataExt;
 use std::os::unix::io::AsRawFd;
 use std::result;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex, Once, Weak};
+use std::sync::{Arc, Mutex, MutexGuard, Once, Weak};
 use std::time::Duration;
 
+mod dispatcher;
 mod epoll;
+mod function;
 mod gpiomem;
 #[cfg(feature = "hal")]
 mod hal;
@@ -131,13 +255,29 @@ mod hal;
 mod hal_unproven;
 mod interrupt;
 mod ioctl;
+mod line_info;
 mod pin;
+#[cfg(feature = "async")]
+mod reactor;
+mod registers;
+mod simulator;
 mod soft_pwm;
+#[cfg(feature = "async")]
+mod stream;
+mod watch;
 
 use crate::system;
-use crate::system::DeviceInfo;
+use crate::system::{DeviceInfo, Header, Model};
 
-pub use self::pin::{InputPin, IoPin, OutputPin, Pin};
+pub use self::function::{function_name, function_pins};
+pub use self::interrupt::InterruptFd;
+pub use self::line_info::LineInfo;
+pub use self::pin::{InputPin, InputPort, InterruptPin, IoPin, OutputPin, OutputPort, Pin};
+pub use self::registers::Registers;
+pub use self::simulator::Simulator;
+#[cfg(feature = "async")]
+pub use self::stream::InterruptStream;
+pub use self::watch::{AsyncLineWatcher, LineChange, LineChangeKind, LineWatcher};
 
 /// Errors that can occur when accessing the GPIO peripheral.
 #[derive(Debug)]
@@ -152,6 +292,10 @@ pub enum Error {
     /// You may also encounter this error if your Linux distribution
     /// doesn't provide any of the common user-accessible system files
     /// that are used to identify the model and SoC.
+    ///
+    /// GPIO controllers on other devices can be accessed through [`Gpio::with_chip`].
+    ///
+    /// [`Gpio::with_chip`]: struct.Gpio.html#method.with_chip
     UnknownModel,
     /// Pin is already in use.
     ///
@@ -168,8 +312,24 @@ pub enum Error {
     ///
     /// The GPIO peripheral doesn't expose a GPIO pin with the specified number. Pins are
     /// addressed by their BCM GPIO numbers, rather than their physical location on the GPIO
-    /// header.
+    /// header, unless they're retrieved through [`Gpio::get_physical`], in which case the
+    /// physical pin isn't a GPIO pin.
+    ///
+    /// [`Gpio::get_physical`]: struct.Gpio.html#method.get_physical
     PinNotAvailable(u8),
+    /// Pin is claimed by another consumer.
+    ///
+    /// The pin's `gpiochip` line has already been requested by another process or by a
+    /// kernel driver. Contains the pin number and the consumer label of the current
+    /// owner, which may be empty if the owner didn't provide a label.
+    ///
+    /// Pins are only requested from the `gpiochip` when using the [`Backend::Cdev`]
+    /// backend, when line ownership is enabled through [`Gpio::set_line_ownership`],
+    /// or when configuring an interrupt.
+    ///
+    /// [`Backend::Cdev`]: enum.Backend.html#variant.Cdev
+    /// [`Gpio::set_line_ownership`]: struct.Gpio.html#method.set_line_ownership
+    PinClaimed(u8, String),
     /// Permission denied when opening `/dev/gpiomem`, `/dev/mem` or `/dev/gpiochipN` for
     /// read/write access.
     ///
@@ -181,6 +341,32 @@ pub enum Error {
     Io(io::Error),
     /// Thread panicked.
     ThreadPanic,
+    /// Backend mismatch.
+    ///
+    /// A [`Gpio`] instance using a different [`Backend`] is still in scope. All
+    /// [`Gpio`] and [`Pin`] instances share the same backend, which can only be
+    /// changed after they have all gone out of scope. Contains the backend that's
+    /// currently in use.
+    ///
+    /// [`Gpio`]: struct.Gpio.html
+    /// [`Backend`]: enum.Backend.html
+    /// [`Pin`]: struct.Pin.html
+    BackendMismatch(Backend),
+    /// Feature not supported.
+    ///
+    /// The requested feature isn't supported by the SoC, or by the [`Backend`]
+    /// that's currently in use.
+    ///
+    /// [`Backend`]: enum.Backend.html
+    NotSupported,
+    /// Operation not supported in the pin's current mode.
+    ///
+    /// Interrupts can only be configured for pins in input mode, and for [`OutputPin`]s
+    /// configured as an open-drain or open-source output. Contains the pin number and its
+    /// current mode.
+    ///
+    /// [`OutputPin`]: struct.OutputPin.html
+    UnsupportedMode(u8, Mode),
 }
 
 impl fmt::Display for Error {
@@ -189,9 +375,26 @@ impl fmt::Display for Error {
             Error::UnknownModel => write!(f, "Unknown Raspberry Pi model"),
             Error::PinUsed(pin) => write!(f, "Pin {} is already in use", pin),
             Error::PinNotAvailable(pin) => write!(f, "Pin {} is not available", pin),
+            Error::PinClaimed(pin, ref consumer) if consumer.is_empty() => {
+                write!(f, "Pin {} is claimed by another consumer", pin)
+            }
+            Error::PinClaimed(pin, ref consumer) => {
+                write!(f, "Pin {} is claimed by {}", pin, consumer)
+            }
             Error::PermissionDenied(ref path) => write!(f, "Permission denied: {}", path),
             Error::Io(ref err) => write!(f, "I/O error: {}", err),
             Error::ThreadPanic => write!(f, "Thread panicked"),
+            Error::BackendMismatch(backend) => {
+                write!(f, "GPIO backend mismatch: {} is already in use", backend)
+            }
+            Error::NotSupported => write!(f, "Feature not supported"),
+            Error::UnsupportedMode(pin, mode) => {
+                write!(
+                    f,
+                    "Operation not supported for pin {} in {} mode",
+                    pin, mode
+                )
+            }
         }
     }
 }
@@ -314,6 +517,60 @@ impl fmt::Display for Bias {
     }
 }
 
+/// Output drive strength.
+///
+/// The drive strength determines the maximum current a pin can source or sink
+/// while maintaining valid logic levels.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum DriveStrength {
+    /// 2 mA
+    Ma2 = 0b00,
+    /// 4 mA
+    Ma4 = 0b01,
+    /// 8 mA
+    Ma8 = 0b10,
+    /// 12 mA
+    Ma12 = 0b11,
+}
+
+impl fmt::Display for DriveStrength {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            DriveStrength::Ma2 => write!(f, "2 mA"),
+            DriveStrength::Ma4 => write!(f, "4 mA"),
+            DriveStrength::Ma8 => write!(f, "8 mA"),
+            DriveStrength::Ma12 => write!(f, "12 mA"),
+        }
+    }
+}
+
+/// Output slew rates.
+///
+/// A slow slew rate limits the rise and fall times of the output signal, which
+/// reduces ringing and electromagnetic interference on long traces or cables.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum SlewRate {
+    Slow = 0,
+    Fast = 1,
+}
+
+impl fmt::Display for SlewRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            SlewRate::Slow => write!(f, "Slow"),
+            SlewRate::Fast => write!(f, "Fast"),
+        }
+    }
+}
+
+// Output drive configurations.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub(crate) enum Drive {
+    PushPull,
+    OpenDrain,
+    OpenSource,
+}
+
 /// Interrupt trigger conditions.
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub enum Trigger {
@@ -334,28 +591,265 @@ impl fmt::Display for Trigger {
     }
 }
 
+/// Interrupt trigger event.
+///
+/// `Event`s are returned by [`InputPin::poll_interrupt`], [`Gpio::poll_interrupts`] and
+/// [`InterruptFd::read_event`], and passed to the callback configured with
+/// [`InputPin::set_async_interrupt`].
+///
+/// [`InputPin::poll_interrupt`]: struct.InputPin.html#method.poll_interrupt
+/// [`Gpio::poll_interrupts`]: struct.Gpio.html#method.poll_interrupts
+/// [`InterruptFd::read_event`]: struct.InterruptFd.html#method.read_event
+/// [`InputPin::set_async_interrupt`]: struct.InputPin.html#method.set_async_interrupt
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Event {
+    /// Edge that triggered the interrupt. Either [`Trigger::RisingEdge`] or [`Trigger::FallingEdge`].
+    ///
+    /// [`Trigger::RisingEdge`]: enum.Trigger.html#variant.RisingEdge
+    /// [`Trigger::FallingEdge`]: enum.Trigger.html#variant.FallingEdge
+    pub trigger: Trigger,
+    /// Kernel timestamp taken when the edge was detected, measured as the elapsed time
+    /// since the system was booted (`CLOCK_MONOTONIC`).
+    pub timestamp: Duration,
+    /// Sequence number of this event across all pins requested by the same interrupt
+    /// trigger. Always set to `0` on kernels that don't support the `gpiochip` v2 uAPI.
+    pub seqno: u32,
+    /// Sequence number of this event on this pin. Always set to `0` on kernels that don't
+    /// support the `gpiochip` v2 uAPI.
+    pub line_seqno: u32,
+    /// Number of events on this pin that were dropped since the previous event, because
+    /// an event buffer overflowed before the events could be retrieved. Overflows of the
+    /// kernel's event buffer are only detected on kernels that support the `gpiochip` v2 uAPI.
+    pub missed: u32,
+}
+
+impl Event {
+    /// Returns the logic level the pin changed to.
+    pub fn level(&self) -> Level {
+        match self.trigger {
+            Trigger::FallingEdge => Level::Low,
+            _ => Level::High,
+        }
+    }
+}
+
+/// GPIO backends.
+///
+/// `GpioMem` directly accesses the GPIO registers through `/dev/gpiomem` or `/dev/mem`,
+/// which offers the best performance.
+///
+/// `Cdev` controls the pins exclusively through line requests on the `gpiochip`
+/// character device. Changing a pin's level, mode or bias requires a system call,
+/// which is considerably slower than direct register access. Any errors reported by
+/// the `gpiochip` are returned by the methods that change a pin's output state, mode
+/// or bias, and by [`Pin::into_output_open_drain`] and [`Pin::into_output_open_source`].
+/// Other [`Pin`] conversions can't fail, so errors are ignored while the pin is being
+/// reconfigured. Alternate functions aren't supported. `Cdev` can be used when the
+/// GPIO registers can't be memory-mapped, for instance when running inside a container
+/// with only `/dev/gpiochipN` available.
+///
+/// `Simulated` keeps the state of each pin in memory, without accessing any hardware.
+/// It's only available through [`Gpio::simulated`].
+///
+/// `Custom` forwards register access to a user-provided [`Registers`] implementation.
+/// It's only available through [`Gpio::with_registers`].
+///
+/// [`Pin`]: struct.Pin.html
+/// [`Pin::into_output_open_drain`]: struct.Pin.html#method.into_output_open_drain
+/// [`Pin::into_output_open_source`]: struct.Pin.html#method.into_output_open_source
+/// [`Gpio::simulated`]: struct.Gpio.html#method.simulated
+/// [`Registers`]: trait.Registers.html
+/// [`Gpio::with_registers`]: struct.Gpio.html#method.with_registers
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Backend {
+    GpioMem,
+    Cdev,
+    Simulated,
+    Custom,
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Backend::GpioMem => write!(f, "GpioMem"),
+            Backend::Cdev => write!(f, "Cdev"),
+            Backend::Simulated => write!(f, "Simulated"),
+            Backend::Custom => write!(f, "Custom"),
+        }
+    }
+}
+
 // Store Gpio's state separately, so we can conveniently share it through
 // a cloned Arc.
 pub(crate) struct GpioState {
-    gpio_mem: Box<dyn gpiomem::GpioRegisters>,
-    cdev: std::fs::File,
+    gpio_mem: Arc<dyn gpiomem::GpioRegisters>,
+    backend: Backend,
+    // None for the simulated backend
+    cdev: Option<std::fs::File>,
+    // Device number of the gpiochip
+    chip: u64,
     sync_interrupts: Mutex<interrupt::EventLoop>,
     pins_taken: [AtomicBool; u8::MAX as usize],
     gpio_lines: u8,
+    // Includes any lines reserved for internal use
+    gpio_lines_extended: u8,
+    extended_pins: AtomicBool,
+    // Request gpiochip lines for claimed pins
+    line_ownership: AtomicBool,
+    // Raspberry Pi model and SoC, if the gpiochip is the main GPIO peripheral
+    device_info: Option<DeviceInfo>,
+    // Use a shared thread for all asynchronous interrupt triggers
+    interrupt_dispatcher: AtomicBool,
+    // Started when the first asynchronous interrupt trigger is configured while
+    // interrupt_dispatcher is enabled
+    dispatcher: Mutex<Option<Arc<dispatcher::Dispatcher>>>,
+    // Shared by all InterruptStreams, started when the first stream is constructed
+    #[cfg(feature = "async")]
+    reactor: Mutex<Option<Arc<reactor::Reactor>>>,
+    // Controls the simulated backend
+    simulator: Option<Arc<gpiomem::sim::GpioSim>>,
 }
 
 impl fmt::Debug for GpioState {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("EventLoop")
             .field("gpio_mem", &self.gpio_mem)
+            .field("backend", &self.backend)
             .field("cdev", &self.cdev)
+            .field("chip", &self.chip)
             .field("sync_interrupts", &self.sync_interrupts)
             .field("pins_taken", &format_args!("{{ .. }}"))
             .field("gpio_lines", &self.gpio_lines)
+            .field("gpio_lines_extended", &self.gpio_lines_extended)
+            .field("extended_pins", &self.extended_pins)
+            .field("line_ownership", &self.line_ownership)
+            .field("device_info", &self.device_info)
+            .field("interrupt_dispatcher", &self.interrupt_dispatcher)
+            .field("dispatcher", &self.dispatcher)
+            .field("simulator", &self.simulator)
             .finish()
     }
 }
 
+impl GpioState {
+    fn new(
+        gpio_mem: Arc<dyn gpiomem::GpioRegisters>,
+        backend: Backend,
+        cdev: Option<std::fs::File>,
+        chip: u64,
+        gpio_lines: u8,
+        gpio_lines_extended: u8,
+        device_info: Option<DeviceInfo>,
+    ) -> Result<GpioState> {
+        let sync_interrupts = Mutex::new(interrupt::EventLoop::new(
+            cdev.as_ref().map_or(-1, |cdev| cdev.as_raw_fd()),
+            gpio_mem.clone(),
+            u8::MAX as usize,
+        )?);
+        let pins_taken = init_array!(AtomicBool::new(false), u8::MAX as usize);
+
+        Ok(GpioState {
+            gpio_mem,
+            backend,
+            cdev,
+            chip,
+            sync_interrupts,
+            pins_taken,
+            gpio_lines,
+            gpio_lines_extended,
+            extended_pins: AtomicBool::new(false),
+            line_ownership: AtomicBool::new(false),
+            device_info,
+            interrupt_dispatcher: AtomicBool::new(false),
+            dispatcher: Mutex::new(None),
+            #[cfg(feature = "async")]
+            reactor: Mutex::new(None),
+            simulator: None,
+        })
+    }
+
+    fn cdev(&self) -> Result<&std::fs::File> {
+        self.cdev.as_ref().ok_or(Error::NotSupported)
+    }
+
+    // The simulated backend doesn't have a gpiochip. Its edge events are requested
+    // through GpioRegisters::request_events, so the fd is never used.
+    fn cdev_fd(&self) -> i32 {
+        self.cdev.as_ref().map_or(-1, |cdev| cdev.as_raw_fd())
+    }
+
+    fn dispatcher(&self) -> Result<Arc<dispatcher::Dispatcher>> {
+        let mut dispatcher = self.dispatcher.lock().unwrap();
+
+        if let Some(ref dispatcher) = *dispatcher {
+            return Ok(dispatcher.clone());
+        }
+
+        let new_dispatcher = Arc::new(dispatcher::Dispatcher::new()?);
+        *dispatcher = Some(new_dispatcher.clone());
+
+        Ok(new_dispatcher)
+    }
+
+    #[cfg(feature = "async")]
+    fn reactor(&self) -> Result<Arc<reactor::Reactor>> {
+        let mut reactor = self.reactor.lock().unwrap();
+
+        if let Some(ref reactor) = *reactor {
+            return Ok(reactor.clone());
+        }
+
+        let new_reactor = Arc::new(reactor::Reactor::new()?);
+        *reactor = Some(new_reactor.clone());
+
+        Ok(new_reactor)
+    }
+}
+
+// Shared state between Gpio and Pin instances, one for each gpiochip. A GpioState
+// is dropped after all Gpio and Pin instances using it go out of scope, guaranteeing
+// we won't have any pins on the same gpiochip simultaneously using different EventLoop
+// or GpioMem instances.
+#[derive(Default)]
+struct GpioStates {
+    // State used by Gpio::new and Gpio::with_backend
+    default: Weak<GpioState>,
+    chips: Vec<Weak<GpioState>>,
+}
+
+impl GpioStates {
+    fn lock() -> MutexGuard<'static, GpioStates> {
+        // Replace this when std::sync::SyncLazy is stabilized. https://github.com/rust-lang/rust/issues/74465
+        static mut GPIO_STATES: MaybeUninit<Mutex<GpioStates>> = MaybeUninit::uninit();
+        static ONCE: Once = Once::new();
+
+        // call_once is thread-safe, guaranteed to be called only once, and memory writes performed
+        // by the closure can be observed by other threads after execution completes.
+        unsafe {
+            ONCE.call_once(|| {
+                GPIO_STATES.write(Mutex::new(GpioStates::default()));
+            });
+
+            // GPIO_STATES will always be initialized at this point.
+            GPIO_STATES.assume_init_ref().lock().unwrap()
+        }
+    }
+
+    fn find(&self, chip: u64) -> Option<Arc<GpioState>> {
+        self.chips
+            .iter()
+            .filter_map(Weak::upgrade)
+            .find(|state| state.chip == chip)
+    }
+
+    // Store a weak reference to the state. This gets dropped when
+    // all Gpio and Pin instances go out of scope.
+    fn insert(&mut self, gpio_state: &Arc<GpioState>) {
+        self.chips.retain(|state| state.strong_count() > 0);
+        self.chips.push(Arc::downgrade(gpio_state));
+    }
+}
+
 /// Provides access to the Raspberry Pi's GPIO peripheral.
 #[derive(Clone, Debug)]
 pub struct Gpio {
@@ -364,61 +858,269 @@ pub struct Gpio {
 
 impl Gpio {
     /// Constructs a new `Gpio`.
+    ///
+    /// `new` uses the [`Backend::GpioMem`] backend if the GPIO registers can be
+    /// memory-mapped, and falls back to the [`Backend::Cdev`] backend otherwise.
+    /// If neither backend is available, the error returned while accessing
+    /// `/dev/gpiomem` or `/dev/mem` is returned.
+    ///
+    /// [`Backend::GpioMem`]: enum.Backend.html#variant.GpioMem
+    /// [`Backend::Cdev`]: enum.Backend.html#variant.Cdev
     pub fn new() -> Result<Gpio> {
-        // Replace this when std::sync::SyncLazy is stabilized. https://github.com/rust-lang/rust/issues/74465
+        Gpio::with_state(None)
+    }
 
-        // Shared state between Gpio and Pin instances. GpioState is dropped after
-        // all Gpio and Pin instances go out of scope, guaranteeing we won't have
-        // any pins simultaneously using different EventLoop or GpioMem instances.
-        static mut GPIO_STATE: MaybeUninit<Mutex<Weak<GpioState>>> = MaybeUninit::uninit();
-        static ONCE: Once = Once::new();
+    /// Constructs a new `Gpio` using the specified [`Backend`].
+    ///
+    /// All [`Gpio`] and [`Pin`] instances share the same backend. If a `Gpio` instance
+    /// using a different backend is still in scope, `with_backend` returns
+    /// `Err(`[`Error::BackendMismatch`]`)`.
+    ///
+    /// Simulated instances are constructed by [`simulated`], and instances using a custom
+    /// backend by [`with_registers`]. Specifying [`Backend::Simulated`] or [`Backend::Custom`]
+    /// returns `Err(`[`Error::NotSupported`]`)`.
+    ///
+    /// [`Backend`]: enum.Backend.html
+    /// [`Gpio`]: struct.Gpio.html
+    /// [`Pin`]: struct.Pin.html
+    /// [`Error::BackendMismatch`]: enum.Error.html#variant.BackendMismatch
+    /// [`simulated`]: #method.simulated
+    /// [`with_registers`]: #method.with_registers
+    /// [`Backend::Simulated`]: enum.Backend.html#variant.Simulated
+    /// [`Backend::Custom`]: enum.Backend.html#variant.Custom
+    /// [`Error::NotSupported`]: enum.Error.html#variant.NotSupported
+    pub fn with_backend(backend: Backend) -> Result<Gpio> {
+        Gpio::with_state(Some(backend))
+    }
 
-        // call_once is thread-safe, guaranteed to be called only once, and memory writes performed
-        // by the closure can be observed by other threads after execution completes.
-        let mut weak_state = unsafe {
-            ONCE.call_once(|| {
-                GPIO_STATE.write(Mutex::new(Weak::new()));
-            });
+    /// Constructs a new `Gpio` for the specified `gpiochip` character device.
+    ///
+    /// `chip` can either be the path to the character device (for instance `/dev/gpiochip4`),
+    /// its name (`gpiochip4`) or its label (`pinctrl-rp1`). Each line exposed by the
+    /// `gpiochip` is available as a pin through [`Gpio::get`], using the line's offset
+    /// as its pin number. This allows access to GPIO controllers other than the
+    /// Raspberry Pi's main GPIO peripheral, such as the Raspberry Pi 5's `brcmstb`
+    /// controllers, I/O expanders with a kernel driver, or GPIO controllers on other
+    /// single-board computers.
+    ///
+    /// Pins are controlled through the [`Backend::Cdev`] backend. Each `gpiochip` has its
+    /// own shared state, so pins on different `gpiochip`s can be used simultaneously.
+    /// If the specified `gpiochip` is already in use by another `Gpio` instance,
+    /// `with_chip` returns a `Gpio` sharing the existing state, including its backend.
+    ///
+    /// [`Gpio::get`]: struct.Gpio.html#method.get
+    /// [`Backend::Cdev`]: enum.Backend.html#variant.Cdev
+    pub fn with_chip(chip: &str) -> Result<Gpio> {
+        let mut gpio_states = GpioStates::lock();
+
+        let cdev = ioctl::open_gpiochip(chip)?;
+        let chip = cdev.metadata()?.rdev();
+
+        if let Some(state) = gpio_states.find(chip) {
+            return Ok(Gpio { inner: state });
+        }
 
-            // GPIO_STATE will always be initialized at this point.
-            GPIO_STATE.assume_init_ref().lock().unwrap()
+        // Pin numbers are limited to u8
+        let chip_info = ioctl::ChipInfo::new(cdev.as_raw_fd())?;
+        let gpio_lines = chip_info.lines.min(u32::from(u8::MAX)) as u8;
+
+        // The header pinout and alternate functions only apply to the Raspberry Pi's
+        // main gpiochip
+        let device_info = DeviceInfo::new().ok().filter(|_| {
+            matches!(
+                ioctl::find_gpiochip().and_then(|cdev| Ok(cdev.metadata()?.rdev())),
+                Ok(main_chip) if main_chip == chip
+            )
+        });
+
+        let gpio_mem = Arc::new(gpiomem::cdev::GpioCdev::open(&cdev, gpio_lines)?);
+        let gpio_state = Arc::new(GpioState::new(
+            gpio_mem,
+            Backend::Cdev,
+            Some(cdev),
+            chip,
+            gpio_lines,
+            gpio_lines,
+            device_info,
+        )?);
+
+        gpio_states.insert(&gpio_state);
+
+        Ok(Gpio { inner: gpio_state })
+    }
+
+    /// Constructs a new `Gpio` that simulates the GPIO peripheral of the specified
+    /// Raspberry Pi model.
+    ///
+    /// The simulated peripheral keeps the state of each pin in memory, without accessing
+    /// any hardware, which allows code that uses the GPIO pins to be tested on any Linux
+    /// system. Pins are retrieved and configured as usual. The pin numbers, header pinout
+    /// and alternate functions match the specified model. Use [`simulator`] to drive the
+    /// pins' input levels, and inspect their output levels, modes and bias.
+    ///
+    /// All pins start out as inputs with the built-in pull-up/pull-down resistors disabled.
+    /// Open-drain and open-source outputs, and inverted logic levels, are emulated in software.
+    /// [`line_info`], [`line_infos`] and [`watch_lines`] return
+    /// `Err(`[`Error::NotSupported`]`)`.
+    ///
+    /// Each call to `simulated` constructs an independent simulated peripheral that isn't
+    /// shared with any other `Gpio` instance, except for its clones.
+    ///
+    /// [`simulator`]: #method.simulator
+    /// [`line_info`]: #method.line_info
+    /// [`line_infos`]: #method.line_infos
+    /// [`watch_lines`]: #method.watch_lines
+    /// [`Error::NotSupported`]: enum.Error.html#variant.NotSupported
+    pub fn simulated(model: Model) -> Result<Gpio> {
+        let device_info = DeviceInfo::with_model(model);
+        let gpio_lines = device_info.gpio_lines();
+        let gpio_lines_extended = device_info.gpio_lines_extended();
+
+        let sim = Arc::new(gpiomem::sim::GpioSim::new(gpio_lines_extended));
+        let gpio_state = GpioState {
+            simulator: Some(sim.clone()),
+            ..GpioState::new(
+                sim,
+                Backend::Simulated,
+                None,
+                0,
+                gpio_lines,
+                gpio_lines_extended,
+                Some(device_info),
+            )?
         };
 
+        Ok(Gpio {
+            inner: Arc::new(gpio_state),
+        })
+    }
+
+    /// Constructs a new `Gpio` that controls the pins through the specified [`Registers`]
+    /// implementation.
+    ///
+    /// The pin numbers, header pinout and alternate functions match the specified
+    /// Raspberry Pi model. Pins are retrieved and configured as usual, and any changes
+    /// to a pin's level, mode or bias are forwarded to `registers`.
+    ///
+    /// Open-drain and open-source outputs, and inverted logic levels, are emulated in
+    /// software. Interrupts, [`line_info`], [`line_infos`] and [`watch_lines`] return
+    /// `Err(`[`Error::NotSupported`]`)`.
+    ///
+    /// Each call to `with_registers` constructs an independent `Gpio` instance that isn't
+    /// shared with any other `Gpio` instance, except for its clones.
+    ///
+    /// [`Registers`]: trait.Registers.html
+    /// [`line_info`]: #method.line_info
+    /// [`line_infos`]: #method.line_infos
+    /// [`watch_lines`]: #method.watch_lines
+    /// [`Error::NotSupported`]: enum.Error.html#variant.NotSupported
+    pub fn with_registers<R>(model: Model, registers: R) -> Result<Gpio>
+    where
+        R: Registers + 'static,
+    {
+        let device_info = DeviceInfo::with_model(model);
+        let gpio_lines = device_info.gpio_lines();
+        let gpio_lines_extended = device_info.gpio_lines_extended();
+
+        let gpio_mem = Arc::new(gpiomem::custom::GpioCustom::new(Box::new(registers)));
+        let gpio_state = GpioState::new(
+            gpio_mem,
+            Backend::Custom,
+            None,
+            0,
+            gpio_lines,
+            gpio_lines_extended,
+            Some(device_info),
+        )?;
+
+        Ok(Gpio {
+            inner: Arc::new(gpio_state),
+        })
+    }
+
+    /// Returns a [`Simulator`] that controls the simulated GPIO peripheral.
+    ///
+    /// Returns `None` if this `Gpio` instance wasn't constructed by [`simulated`].
+    ///
+    /// [`Simulator`]: struct.Simulator.html
+    /// [`simulated`]: #method.simulated
+    pub fn simulator(&self) -> Option<Simulator> {
+        self.inner.simulator.clone().map(Simulator::new)
+    }
+
+    fn with_state(backend: Option<Backend>) -> Result<Gpio> {
+        let mut gpio_states = GpioStates::lock();
+
         // Clone a strong reference if a GpioState instance already exists, otherwise
         // initialize it here so we can return any relevant errors.
-        if let Some(ref state) = weak_state.upgrade() {
-            Ok(Gpio {
-                inner: state.clone(),
-            })
+        let state = if let Some(state) = gpio_states.default.upgrade() {
+            state
         } else {
             let device_info = DeviceInfo::new().map_err(|_| Error::UnknownModel)?;
+            let gpio_lines = device_info.gpio_lines();
+            let gpio_lines_extended = device_info.gpio_lines_extended();
+            let cdev = ioctl::find_gpiochip()?;
+            let chip = cdev.metadata()?.rdev();
 
-            let gpio_mem: Box<dyn gpiomem::GpioRegisters> = match device_info.gpio_interface() {
-                system::GpioInterface::Bcm => Box::new(gpiomem::bcm::GpioMem::open()?),
-                system::GpioInterface::Rp1 => Box::new(gpiomem::rp1::GpioMem::open()?),
-            };
+            // The gpiochip might already be in use through Gpio::with_chip
+            if let Some(state) = gpio_states.find(chip) {
+                gpio_states.default = Arc::downgrade(&state);
 
-            let cdev = ioctl::find_gpiochip()?;
-            let sync_interrupts = Mutex::new(interrupt::EventLoop::new(
-                cdev.as_raw_fd(),
-                u8::MAX as usize,
-            )?);
-            let pins_taken = init_array!(AtomicBool::new(false), u8::MAX as usize);
-            let gpio_lines = device_info.gpio_lines();
+                state
+            } else {
+                let open_gpio_mem = || -> Result<Arc<dyn gpiomem::GpioRegisters>> {
+                    Ok(match device_info.gpio_interface() {
+                        system::GpioInterface::Bcm => {
+                            Arc::new(gpiomem::bcm::GpioMem::open(&cdev, gpio_lines_extended)?)
+                        }
+                        system::GpioInterface::Rp1 => {
+                            Arc::new(gpiomem::rp1::GpioMem::open(&cdev, gpio_lines_extended)?)
+                        }
+                    })
+                };
 
-            let gpio_state = Arc::new(GpioState {
-                gpio_mem,
-                cdev,
-                sync_interrupts,
-                pins_taken,
-                gpio_lines,
-            });
+                let open_cdev = || -> Result<Arc<dyn gpiomem::GpioRegisters>> {
+                    Ok(Arc::new(gpiomem::cdev::GpioCdev::open(
+                        &cdev,
+                        gpio_lines_extended,
+                    )?))
+                };
+
+                let (gpio_mem, backend) = match backend {
+                    Some(Backend::GpioMem) => (open_gpio_mem()?, Backend::GpioMem),
+                    Some(Backend::Cdev) => (open_cdev()?, Backend::Cdev),
+                    // Simulated and custom instances are constructed by Gpio::simulated
+                    // and Gpio::with_registers
+                    Some(Backend::Simulated) | Some(Backend::Custom) => {
+                        return Err(Error::NotSupported)
+                    }
+                    None => match open_gpio_mem() {
+                        Ok(gpio_mem) => (gpio_mem, Backend::GpioMem),
+                        // Report the original error if the gpiochip can't be used either
+                        Err(e) => (open_cdev().map_err(|_| e)?, Backend::Cdev),
+                    },
+                };
+
+                let gpio_state = Arc::new(GpioState::new(
+                    gpio_mem,
+                    backend,
+                    Some(cdev),
+                    chip,
+                    gpio_lines,
+                    gpio_lines_extended,
+                    Some(device_info),
+                )?);
+
+                gpio_states.default = Arc::downgrade(&gpio_state);
+                gpio_states.insert(&gpio_state);
 
-            // Store a weak reference to our state. This gets dropped when
-            // all Gpio and Pin instances go out of scope.
-            *weak_state = Arc::downgrade(&gpio_state);
+                gpio_state
+            }
+        };
 
-            Ok(Gpio { inner: gpio_state })
+        match backend {
+            Some(backend) if backend != state.backend => Err(Error::BackendMismatch(state.backend)),
+            _ => Ok(Gpio { inner: state }),
         }
     }
 
@@ -429,13 +1131,17 @@ impl Gpio {
     /// After a [`Pin`] (or a derived [`InputPin`], [`OutputPin`] or [`IoPin`]) goes out
     /// of scope, it can be retrieved again through another `get` call.
     ///
+    /// Pins that are reserved for internal use are only available after
+    /// enabling them with [`set_extended_pins`].
+    ///
     /// [`Pin`]: struct.Pin.html
     /// [`InputPin`]: struct.InputPin.html
     /// [`OutputPin`]: struct.OutputPin.html
     /// [`IoPin`]: struct.IoPin.html
     /// [`Error::PinUsed`]: enum.Error.html#variant.PinUsed
+    /// [`set_extended_pins`]: #method.set_extended_pins
     pub fn get(&self, pin: u8) -> Result<Pin> {
-        if pin >= self.inner.gpio_lines {
+        if pin >= self.gpio_lines() {
             return Err(Error::PinNotAvailable(pin));
         }
 
@@ -446,16 +1152,241 @@ impl Gpio {
         {
             // Pin is taken
             Err(Error::PinUsed(pin))
+        } else if let Err(e) = self
+            .inner
+            .gpio_mem
+            .claim(pin, self.inner.line_ownership.load(Ordering::SeqCst))
+        {
+            // The backend couldn't acquire the pin
+            self.inner.pins_taken[pin as usize].store(false, Ordering::SeqCst);
+
+            Err(e)
         } else {
             // Return an owned Pin
             Ok(Pin::new(pin, self.inner.clone()))
         }
     }
 
+    /// Returns a [`Pin`] for the specified physical pin number on the GPIO header.
+    ///
+    /// Physical pin numbers start at 1, and follow the header's numbering scheme. The
+    /// pin number is translated to the matching BCM GPIO number based on the Raspberry
+    /// Pi's model, after which `get_physical` behaves the same as [`get`]. If the physical
+    /// pin isn't a GPIO pin, or the GPIO header pinout is unknown,
+    /// `get_physical` returns `Err(`[`Error::PinNotAvailable`]`)`.
+    ///
+    /// [`Pin`]: struct.Pin.html
+    /// [`get`]: #method.get
+    /// [`Error::PinNotAvailable`]: enum.Error.html#variant.PinNotAvailable
+    pub fn get_physical(&self, pin: u8) -> Result<Pin> {
+        match self.header().and_then(|header| header.gpio(pin)) {
+            Some(gpio) => self.get(gpio),
+            None => Err(Error::PinNotAvailable(pin)),
+        }
+    }
+
+    /// Returns the pinout of the GPIO header.
+    ///
+    /// Returns `None` if the Raspberry Pi's model doesn't have a GPIO header, or if
+    /// this `Gpio` instance controls a `gpiochip` that isn't connected to the header.
+    pub fn header(&self) -> Option<Header> {
+        self.inner
+            .device_info
+            .and_then(|device_info| device_info.header())
+    }
+
+    // Returns the number of GPIO lines that are currently accessible
+    fn gpio_lines(&self) -> u8 {
+        if self.inner.extended_pins.load(Ordering::SeqCst) {
+            self.inner.gpio_lines_extended
+        } else {
+            self.inner.gpio_lines
+        }
+    }
+
+    /// Returns the kernel's [`LineInfo`] for the specified BCM GPIO number.
+    ///
+    /// Line info is retrieved from the `gpiochip`, and is available for all pins,
+    /// including pins that are currently in use by another process or a kernel driver.
+    /// Retrieving line info doesn't affect the pin's state. If the GPIO peripheral doesn't
+    /// expose a pin with the specified number, `line_info` returns
+    /// `Err(`[`Error::PinNotAvailable`]`)`.
+    ///
+    /// [`LineInfo`]: struct.LineInfo.html
+    /// [`Error::PinNotAvailable`]: enum.Error.html#variant.PinNotAvailable
+    pub fn line_info(&self, pin: u8) -> Result<LineInfo> {
+        if pin >= self.gpio_lines() {
+            return Err(Error::PinNotAvailable(pin));
+        }
+
+        let line_info = ioctl::LineInfo::new(self.inner.cdev()?.as_raw_fd(), u32::from(pin))?;
+
+        Ok(LineInfo::new(pin, &line_info))
+    }
+
+    /// Returns an iterator over the kernel's [`LineInfo`] for all available pins,
+    /// ordered by BCM GPIO number.
+    ///
+    /// See [`line_info`] for more details.
+    ///
+    /// [`LineInfo`]: struct.LineInfo.html
+    /// [`line_info`]: #method.line_info
+    pub fn line_infos(&self) -> impl Iterator<Item = Result<LineInfo>> + '_ {
+        (0..self.gpio_lines()).map(move |pin| self.line_info(pin))
+    }
+
+    /// Returns a [`LineWatcher`] that watches the specified pins for configuration changes.
+    ///
+    /// The kernel reports a [`LineChange`] when a line is requested, released or
+    /// reconfigured through the `gpiochip` by the current process, another process or a
+    /// kernel driver. Call [`LineWatcher::poll`] to wait for changes, either blocking
+    /// indefinitely or with a timeout.
+    ///
+    /// If the GPIO peripheral doesn't expose a pin with one of the specified numbers,
+    /// `watch_lines` returns `Err(`[`Error::PinNotAvailable`]`)`. Watching lines requires
+    /// a kernel that supports the `gpiochip` v2 uAPI (5.10 or newer).
+    ///
+    /// [`LineWatcher`]: struct.LineWatcher.html
+    /// [`LineChange`]: struct.LineChange.html
+    /// [`LineWatcher::poll`]: struct.LineWatcher.html#method.poll
+    /// [`Error::PinNotAvailable`]: enum.Error.html#variant.PinNotAvailable
+    pub fn watch_lines(&self, pins: &[u8]) -> Result<LineWatcher> {
+        let gpio_lines = self.gpio_lines();
+        if let Some(&pin) = pins.iter().find(|&&pin| pin >= gpio_lines) {
+            return Err(Error::PinNotAvailable(pin));
+        }
+
+        // Each watcher needs its own file description, since watched lines
+        // are tracked per file description.
+        LineWatcher::new(ioctl::reopen_gpiochip(self.inner.cdev()?)?, pins)
+    }
+
+    /// Watches the specified pins for configuration changes on a separate thread,
+    /// and executes `callback` for each [`LineChange`].
+    ///
+    /// The lines are watched until the returned [`AsyncLineWatcher`] is stopped
+    /// or goes out of scope. See [`watch_lines`] for more details.
+    ///
+    /// [`LineChange`]: struct.LineChange.html
+    /// [`AsyncLineWatcher`]: struct.AsyncLineWatcher.html
+    /// [`watch_lines`]: #method.watch_lines
+    pub fn watch_lines_async<C>(&self, pins: &[u8], callback: C) -> Result<AsyncLineWatcher>
+    where
+        C: FnMut(LineChange) + Send + 'static,
+    {
+        AsyncLineWatcher::new(self.watch_lines(pins)?, callback)
+    }
+
+    /// Enables or disables access to GPIO pins that are reserved for internal use.
+    ///
+    /// The Raspberry Pi 5's RP1 exposes GPIO0-27 on bank 0 for general use. Banks 1 and 2,
+    /// containing GPIO28-53, are specified as internal-use only. On the Raspberry Pi 5 those
+    /// pins are connected to on-board peripherals, but a Compute Module 5 carrier board may
+    /// route them to external signals. Setting `extended_pins` to `true` allows [`get`] to
+    /// retrieve those pins. Make sure you know what's connected to a pin before changing
+    /// its configuration.
+    ///
+    /// This setting is shared between all `Gpio` instances, and has no effect on models
+    /// without any internal-use GPIO pins. Pins that have already been retrieved aren't
+    /// affected when access is disabled.
+    ///
+    /// By default, `extended_pins` is set to `false`.
+    ///
+    /// [`get`]: #method.get
+    pub fn set_extended_pins(&self, extended_pins: bool) {
+        self.inner
+            .extended_pins
+            .store(extended_pins, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if access to GPIO pins reserved for internal use is enabled.
+    pub fn extended_pins(&self) -> bool {
+        self.inner.extended_pins.load(Ordering::SeqCst)
+    }
+
+    /// Enables or disables cross-process ownership of GPIO pins.
+    ///
+    /// By default, [`get`] only prevents a pin from being used more than once within the
+    /// current process. When `line_ownership` is set to `true`, [`get`] also requests the
+    /// pin's line from the `gpiochip` with the consumer label `RPPAL`, and holds the request
+    /// until the [`Pin`] (or a derived [`InputPin`], [`OutputPin`] or [`IoPin`]) goes out of
+    /// scope. While the line is requested, other processes can't request it, and tools such
+    /// as `gpioinfo` list it as in use. If the line has already been requested by another
+    /// process or by a kernel driver, [`get`] returns `Err(`[`Error::PinClaimed`]`)`.
+    ///
+    /// Lines are always requested when using the [`Backend::Cdev`] backend, regardless of
+    /// this setting. Note that processes that access the GPIO registers directly through
+    /// `/dev/gpiomem` or `/dev/mem` bypass the `gpiochip`, and aren't affected.
+    ///
+    /// This setting is shared between all `Gpio` instances, and only applies to pins
+    /// retrieved after it's changed.
+    ///
+    /// By default, `line_ownership` is set to `false`.
+    ///
+    /// [`get`]: #method.get
+    /// [`Pin`]: struct.Pin.html
+    /// [`InputPin`]: struct.InputPin.html
+    /// [`OutputPin`]: struct.OutputPin.html
+    /// [`IoPin`]: struct.IoPin.html
+    /// [`Error::PinClaimed`]: enum.Error.html#variant.PinClaimed
+    /// [`Backend::Cdev`]: enum.Backend.html#variant.Cdev
+    pub fn set_line_ownership(&self, line_ownership: bool) {
+        self.inner
+            .line_ownership
+            .store(line_ownership, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if cross-process ownership of GPIO pins is enabled.
+    pub fn line_ownership(&self) -> bool {
+        self.inner.line_ownership.load(Ordering::SeqCst)
+    }
+
+    /// Enables or disables the shared interrupt dispatcher.
+    ///
+    /// By default, [`InputPin::set_async_interrupt`] spawns a separate thread for each
+    /// asynchronous interrupt trigger. When `interrupt_dispatcher` is set to `true`, all
+    /// asynchronous interrupt triggers are served by a single dispatcher thread instead,
+    /// which waits for trigger events on every pin, and executes the callbacks one at a time.
+    /// The dispatcher thread is started when the first asynchronous interrupt trigger is
+    /// configured, and keeps running until all `Gpio` and pin instances go out of scope.
+    ///
+    /// Because the callbacks share a thread, a callback that blocks for a long time delays
+    /// the callbacks of other pins. If a callback panics, its pin is no longer served, and
+    /// [`InputPin::clear_async_interrupt`] returns `Err(`[`Error::ThreadPanic`]`)`. The
+    /// callbacks of other pins are unaffected.
+    ///
+    /// This setting is shared between all `Gpio` instances, and only applies to asynchronous
+    /// interrupt triggers configured after it's changed.
+    ///
+    /// By default, `interrupt_dispatcher` is set to `false`.
+    ///
+    /// [`InputPin::set_async_interrupt`]: struct.InputPin.html#method.set_async_interrupt
+    /// [`InputPin::clear_async_interrupt`]: struct.InputPin.html#method.clear_async_interrupt
+    /// [`Error::ThreadPanic`]: enum.Error.html#variant.ThreadPanic
+    pub fn set_interrupt_dispatcher(&self, interrupt_dispatcher: bool) {
+        self.inner
+            .interrupt_dispatcher
+            .store(interrupt_dispatcher, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if the shared interrupt dispatcher is enabled.
+    pub fn interrupt_dispatcher(&self) -> bool {
+        self.inner.interrupt_dispatcher.load(Ordering::SeqCst)
+    }
+
+    /// Returns the [`Backend`] used to access the GPIO peripheral.
+    ///
+    /// [`Backend`]: enum.Backend.html
+    pub fn backend(&self) -> Backend {
+        self.inner.backend
+    }
+
     /// Blocks until an interrupt is triggered on any of the specified pins, or until a timeout occurs.
     ///
-    /// Only pins that have been previously configured for synchronous interrupts using [`InputPin::set_interrupt`]
-    /// can be polled. Asynchronous interrupt triggers are automatically polled on a separate thread.
+    /// Only pins that have been previously configured for synchronous interrupts using [`InputPin::set_interrupt`],
+    /// [`IoPin::set_interrupt`] or [`OutputPin::set_interrupt`] can be polled. Pins of different types can be polled
+    /// simultaneously by passing them as `&dyn `[`InterruptPin`]. Asynchronous interrupt triggers are automatically
+    /// polled on a separate thread.
     ///
     /// Calling `poll_interrupts` blocks any other calls to `poll_interrupts` or [`InputPin::poll_interrupt`] until
     /// it returns. If you need to poll multiple pins simultaneously on different threads, consider using
@@ -469,21 +1400,71 @@ impl Gpio {
     /// `timeout` can be set to `None` to wait indefinitely.
     ///
     /// When an interrupt event is triggered, `poll_interrupts` returns
-    /// `Ok((&`[`InputPin`]`, `[`Level`]`))` containing the corresponding pin and logic level. If multiple events trigger
+    /// `Ok((&pin, `[`Event`]`))` containing the corresponding pin and trigger event. If multiple events trigger
     /// at the same time, only the first one is returned. The remaining events are cached and will be returned
     /// the next time [`InputPin::poll_interrupt`] or `poll_interrupts` is called.
     ///
     /// [`InputPin::set_interrupt`]: struct.InputPin.html#method.set_interrupt
+    /// [`IoPin::set_interrupt`]: struct.IoPin.html#method.set_interrupt
+    /// [`OutputPin::set_interrupt`]: struct.OutputPin.html#method.set_interrupt
+    /// [`InterruptPin`]: trait.InterruptPin.html
     /// [`InputPin::poll_interrupt`]: struct.InputPin.html#method.poll_interrupt
     /// [`InputPin::set_async_interrupt`]: struct.InputPin.html#method.set_async_interrupt
-    /// [`InputPin`]: struct.InputPin.html
-    /// [`Level`]: enum.Level.html
-    pub fn poll_interrupts<'a>(
+    /// [`Event`]: struct.Event.html
+    pub fn poll_interrupts<'a, P: InterruptPin + ?Sized>(
         &self,
-        pins: &[&'a InputPin],
+        pins: &[&'a P],
         reset: bool,
         timeout: Option<Duration>,
-    ) -> Result<Option<(&'a InputPin, Level)>> {
-        (*self.inner.sync_interrupts.lock().unwrap()).poll(pins, reset, timeout)
+    ) -> Result<Option<(&'a P, Event)>> {
+        let pin_numbers: Vec<u8> = pins.iter().map(|pin| pin.pin()).collect();
+        let opt =
+            (*self.inner.sync_interrupts.lock().unwrap()).poll(&pin_numbers, reset, timeout)?;
+
+        Ok(opt.map(|(index, event)| (pins[index], pins[index].logical_event(event))))
+    }
+
+    /// Blocks until interrupts are triggered on any of the specified pins, or until a timeout occurs,
+    /// and returns all trigger events.
+    ///
+    /// `poll_interrupts_batch` works similarly to [`poll_interrupts`], but instead of returning a single
+    /// trigger event, it returns every trigger event that's available for the specified pins, including
+    /// any events that were cached by a previous call to [`poll_interrupts`] or [`InputPin::poll_interrupt`].
+    /// If events have already been cached, `poll_interrupts_batch` doesn't block, and returns the cached
+    /// events together with any other events that are currently waiting.
+    ///
+    /// All events that are waiting in the kernel's event buffer for a pin are retrieved with a single read,
+    /// which makes `poll_interrupts_batch` suitable for pins that trigger at a high rate. Events are sorted by
+    /// their timestamp. Multiple events for the same pin are returned in the order in which they occurred.
+    ///
+    /// If edges were dropped because the kernel's event buffer overflowed, the number of dropped
+    /// edges is reported through [`Event::missed`].
+    ///
+    /// Setting `reset` to `true` clears all cached events before polling for new events.
+    ///
+    /// The `timeout` duration indicates how long the call to `poll_interrupts_batch` will block while waiting
+    /// for interrupt trigger events, after which an empty `Vec` is returned. `timeout` can be set to `None`
+    /// to wait indefinitely.
+    ///
+    /// [`poll_interrupts`]: #method.poll_interrupts
+    /// [`InputPin::poll_interrupt`]: struct.InputPin.html#method.poll_interrupt
+    /// [`Event::missed`]: struct.Event.html#structfield.missed
+    pub fn poll_interrupts_batch<'a, P: InterruptPin + ?Sized>(
+        &self,
+        pins: &[&'a P],
+        reset: bool,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<(&'a P, Event)>> {
+        let pin_numbers: Vec<u8> = pins.iter().map(|pin| pin.pin()).collect();
+        let events = (*self.inner.sync_interrupts.lock().unwrap()).poll_batch(
+            &pin_numbers,
+            reset,
+            timeout,
+        )?;
+
+        Ok(events
+            .into_iter()
+            .map(|(index, event)| (pins[index], pins[index].logical_event(event)))
+            .collect())
     }
 }
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::os::unix::io::AsRawFd;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::gpio::epoll::{epoll_event, Epoll, EventFd, EPOLLERR, EPOLLET, EPOLLIN, EPOLLPRI};
+use crate::gpio::{Error, Event, InterruptFd, Result};
+
+// Maximum number of events handled per epoll_wait() call
+const MAX_EVENTS: usize = 16;
+
+// Epoll id for the EventFd used to stop the dispatcher thread. Pins use their
+// pin number as id.
+const ID_STOP: u64 = u64::MAX;
+
+struct Handler {
+    // Set to None when the handler is removed, in case the dispatcher thread
+    // still holds a reference
+    interrupt: Option<InterruptFd>,
+    callback: Box<dyn FnMut(Event) + Send>,
+    // Error returned while reading events, or ThreadPanic if the callback panicked.
+    // Reported when the handler is removed.
+    error: Option<Error>,
+}
+
+impl fmt::Debug for Handler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Handler")
+            .field("interrupt", &self.interrupt)
+            .field("callback", &format_args!("{{ .. }}"))
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+struct Shared {
+    poll: Epoll,
+    handlers: Mutex<HashMap<u8, Arc<Mutex<Handler>>>>,
+}
+
+// Dispatches the events of all asynchronous interrupt triggers on a gpiochip
+// from a single thread, and calls each callback in turn.
+#[derive(Debug)]
+pub(crate) struct Dispatcher {
+    shared: Arc<Shared>,
+    poll_thread: Option<thread::JoinHandle<Result<()>>>,
+    tx: EventFd,
+}
+
+impl Dispatcher {
+    pub(crate) fn new() -> Result<Dispatcher> {
+        let shared = Arc::new(Shared {
+            poll: Epoll::new()?,
+            handlers: Mutex::new(HashMap::new()),
+        });
+
+        let tx = EventFd::new()?;
+
+        // tx becomes readable when the dispatcher is dropped
+        shared
+            .poll
+            .add(tx.fd(), ID_STOP, EPOLLERR | EPOLLET | EPOLLIN)?;
+
+        let poll_shared = shared.clone();
+        let poll_thread = thread::spawn(move || -> Result<()> {
+            let mut events = [epoll_event { events: 0, u64: 0 }; MAX_EVENTS];
+            loop {
+                let num_events = poll_shared.poll.wait(&mut events, None)?;
+                for event in &events[0..num_events] {
+                    if event.u64 == ID_STOP {
+                        return Ok(()); // The dispatcher is being dropped
+                    }
+
+                    let pin = event.u64 as u8;
+                    let handler = poll_shared.handlers.lock().unwrap().get(&pin).cloned();
+                    if let Some(handler) = handler {
+                        poll_shared.dispatch(&mut handler.lock().unwrap());
+                    }
+                }
+            }
+        });
+
+        Ok(Dispatcher {
+            shared,
+            poll_thread: Some(poll_thread),
+            tx,
+        })
+    }
+
+    pub(crate) fn insert<C>(&self, interrupt: InterruptFd, callback: C) -> Result<()>
+    where
+        C: FnMut(Event) + Send + 'static,
+    {
+        let pin = interrupt.pin();
+
+        // Any existing handler for this pin needs to be removed first
+        self.remove(pin)?;
+
+        let mut handlers = self.shared.handlers.lock().unwrap();
+        self.shared
+            .poll
+            .add(interrupt.as_raw_fd(), u64::from(pin), EPOLLIN | EPOLLPRI)?;
+        handlers.insert(
+            pin,
+            Arc::new(Mutex::new(Handler {
+                interrupt: Some(interrupt),
+                callback: Box::new(callback),
+                error: None,
+            })),
+        );
+
+        Ok(())
+    }
+
+    // Waits for the pin's callback to return if it's currently executing, and closes
+    // the event fd. Returns any error that occurred while reading events.
+    pub(crate) fn remove(&self, pin: u8) -> Result<()> {
+        let handler = self.shared.handlers.lock().unwrap().remove(&pin);
+
+        if let Some(handler) = handler {
+            // Panics are caught in dispatch(), but check for a poisoned mutex anyway
+            let mut handler = match handler.lock() {
+                Ok(handler) => handler,
+                Err(_) => return Err(Error::ThreadPanic),
+            };
+
+            if let Some(interrupt) = handler.interrupt.take() {
+                self.shared.poll.delete(interrupt.as_raw_fd())?;
+            }
+
+            if let Some(error) = handler.error.take() {
+                return Err(error);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Shared {
+    fn dispatch(&self, handler: &mut Handler) {
+        // Read all waiting events. Stale events that were reported for an fd that
+        // has since been replaced by a new handler for the same pin don't block,
+        // because read_event returns None when nothing can be read. If the handler
+        // was removed after the event was reported, interrupt is None.
+        while let Some(ref mut interrupt) = handler.interrupt {
+            match interrupt.read_event() {
+                Ok(Some(event)) => {
+                    // A panicking callback shouldn't end the dispatcher thread, which
+                    // would silently stop the callbacks of all other pins
+                    let callback = &mut handler.callback;
+                    if panic::catch_unwind(AssertUnwindSafe(|| callback(event))).is_err() {
+                        self.stop(handler, Error::ThreadPanic);
+                    }
+                }
+                Ok(None) => return,
+                Err(e) => self.stop(handler, e),
+            }
+        }
+    }
+
+    // Stops polling the handler's fd, so we don't keep retrying a read that fails
+    // or calling a callback that panicked
+    fn stop(&self, handler: &mut Handler, error: Error) {
+        if let Some(interrupt) = handler.interrupt.take() {
+            let _ = self.poll.delete(interrupt.as_raw_fd());
+        }
+
+        handler.error = Some(error);
+    }
+}
+
+impl Drop for Dispatcher {
+    fn drop(&mut self) {
+        let _ = self.tx.notify();
+
+        // Don't wait for the dispatcher thread to exit if the current thread is panicking,
+        // because we could potentially block indefinitely while unwinding if a callback
+        // doesn't return.
+        if !thread::panicking() {
+            if let Some(poll_thread) = self.poll_thread.take() {
+                let _ = poll_thread.join();
+            }
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use crate::gpio::Mode;
+use crate::system::SoC;
+
+// Number of alternate functions per pin
+const ALT_FUNCTIONS_BCM: usize = 6;
+const ALT_FUNCTIONS_RP1: usize = 9;
+
+// Alternate functions Alt0 - Alt5 for GPIO0 - GPIO53 on the BCM2835, BCM2836
+// and BCM2837. Empty strings indicate reserved or internal functions.
+#[rustfmt::skip]
+const FUNCTIONS_BCM283X: [[&str; ALT_FUNCTIONS_BCM]; 54] = [
+    ["SDA0",        "SA5",          "PCLK",       "AVEOUT_VCLK",     "AVEIN_VCLK",   ""        ], // GPIO0
+    ["SCL0",        "SA4",          "DE",         "AVEOUT_DSYNC",    "AVEIN_DSYNC",  ""        ], // GPIO1
+    ["SDA1",        "SA3",          "LCD_VSYNC",  "AVEOUT_VSYNC",    "AVEIN_VSYNC",  ""        ], // GPIO2
+    ["SCL1",        "SA2",          "LCD_HSYNC",  "AVEOUT_HSYNC",    "AVEIN_HSYNC",  ""        ], // GPIO3
+    ["GPCLK0",      "SA1",          "DPI_D0",     "AVEOUT_VID0",     "AVEIN_VID0",   "ARM_TDI" ], // GPIO4
+    ["GPCLK1",      "SA0",          "DPI_D1",     "AVEOUT_VID1",     "AVEIN_VID1",   "ARM_TDO" ], // GPIO5
+    ["GPCLK2",      "SOE_N_SE",     "DPI_D2",     "AVEOUT_VID2",     "AVEIN_VID2",   "ARM_RTCK"], // GPIO6
+    ["SPI0_CE1_N",  "SWE_N_SRW_N",  "DPI_D3",     "AVEOUT_VID3",     "AVEIN_VID3",   ""        ], // GPIO7
+    ["SPI0_CE0_N",  "SD0",          "DPI_D4",     "AVEOUT_VID4",     "AVEIN_VID4",   ""        ], // GPIO8
+    ["SPI0_MISO",   "SD1",          "DPI_D5",     "AVEOUT_VID5",     "AVEIN_VID5",   ""        ], // GPIO9
+    ["SPI0_MOSI",   "SD2",          "DPI_D6",     "AVEOUT_VID6",     "AVEIN_VID6",   ""        ], // GPIO10
+    ["SPI0_SCLK",   "SD3",          "DPI_D7",     "AVEOUT_VID7",     "AVEIN_VID7",   ""        ], // GPIO11
+    ["PWM0",        "SD4",          "DPI_D8",     "AVEOUT_VID8",     "AVEIN_VID8",   "ARM_TMS" ], // GPIO12
+    ["PWM1",        "SD5",          "DPI_D9",     "AVEOUT_VID9",     "AVEIN_VID9",   "ARM_TCK" ], // GPIO13
+    ["TXD0",        "SD6",          "DPI_D10",    "AVEOUT_VID10",    "AVEIN_VID10",  "TXD1"    ], // GPIO14
+    ["RXD0",        "SD7",          "DPI_D11",    "AVEOUT_VID11",    "AVEIN_VID11",  "RXD1"    ], // GPIO15
+    ["",            "SD8",          "DPI_D12",    "CTS0",            "SPI1_CE2_N",   "CTS1"    ], // GPIO16
+    ["",            "SD9",          "DPI_D13",    "RTS0",            "SPI1_CE1_N",   "RTS1"    ], // GPIO17
+    ["PCM_CLK",     "SD10",         "DPI_D14",    "BSCSL_SDA_MOSI",  "SPI1_CE0_N",   "PWM0"    ], // GPIO18
+    ["PCM_FS",      "SD11",         "DPI_D15",    "BSCSL_SCL_SCLK",  "SPI1_MISO",    "PWM1"    ], // GPIO19
+    ["PCM_DIN",     "SD12",         "DPI_D16",    "BSCSL_MISO",      "SPI1_MOSI",    "GPCLK0"  ], // GPIO20
+    ["PCM_DOUT",    "SD13",         "DPI_D17",    "BSCSL_CE_N",      "SPI1_SCLK",    "GPCLK1"  ], // GPIO21
+    ["SD0_CLK",     "SD14",         "DPI_D18",    "SD1_CLK",         "ARM_TRST",     ""        ], // GPIO22
+    ["SD0_CMD",     "SD15",         "DPI_D19",    "SD1_CMD",         "ARM_RTCK",     ""        ], // GPIO23
+    ["SD0_DAT0",    "SD16",         "DPI_D20",    "SD1_DAT0",        "ARM_TDO",      ""        ], // GPIO24
+    ["SD0_DAT1",    "SD17",         "DPI_D21",    "SD1_DAT1",        "ARM_TCK",      ""        ], // GPIO25
+    ["SD0_DAT2",    "TE0",          "DPI_D22",    "SD1_DAT2",        "ARM_TDI",      ""        ], // GPIO26
+    ["SD0_DAT3",    "TE1",          "DPI_D23",    "SD1_DAT3",        "ARM_TMS",      ""        ], // GPIO27
+    ["SDA0",        "SA5",          "PCM_CLK",    "",                "",             ""        ], // GPIO28
+    ["SCL0",        "SA4",          "PCM_FS",     "",                "",             ""        ], // GPIO29
+    ["TE0",         "SA3",          "PCM_DIN",    "CTS0",            "",             "CTS1"    ], // GPIO30
+    ["",            "SA2",          "PCM_DOUT",   "RTS0",            "",             "RTS1"    ], // GPIO31
+    ["GPCLK0",      "SA1",          "RING_OCLK",  "TXD0",            "",             "TXD1"    ], // GPIO32
+    ["",            "SA0",          "TE1",        "RXD0",            "",             "RXD1"    ], // GPIO33
+    ["GPCLK0",      "SOE_N_SE",     "TE2",        "SD1_CLK",         "",             ""        ], // GPIO34
+    ["SPI0_CE1_N",  "SWE_N_SRW_N",  "",           "SD1_CMD",         "",             ""        ], // GPIO35
+    ["SPI0_CE0_N",  "SD0",          "TXD0",       "SD1_DAT0",        "",             ""        ], // GPIO36
+    ["SPI0_MISO",   "SD1",          "RXD0",       "SD1_DAT1",        "",             ""        ], // GPIO37
+    ["SPI0_MOSI",   "SD2",          "RTS0",       "SD1_DAT2",        "",             ""        ], // GPIO38
+    ["SPI0_SCLK",   "SD3",          "CTS0",       "SD1_DAT3",        "",             ""        ], // GPIO39
+    ["PWM0",        "SD4",          "",           "SD1_DAT4",        "SPI2_MISO",    "TXD1"    ], // GPIO40
+    ["PWM1",        "SD5",          "",           "SD1_DAT5",        "SPI2_MOSI",    "RXD1"    ], // GPIO41
+    ["GPCLK1",      "SD6",          "",           "SD1_DAT6",        "SPI2_SCLK",    "RTS1"    ], // GPIO42
+    ["GPCLK2",      "SD7",          "",           "SD1_DAT7",        "SPI2_CE0_N",   "CTS1"    ], // GPIO43
+    ["GPCLK1",      "SDA0",         "SDA1",       "",                "SPI2_CE1_N",   ""        ], // GPIO44
+    ["PWM1",        "SCL0",         "SCL1",       "",                "SPI2_CE2_N",   ""        ], // GPIO45
+    ["",            "",             "",           "",                "",             ""        ], // GPIO46
+    ["",            "",             "",           "",                "",             ""        ], // GPIO47
+    ["",            "",             "",           "SD1_CLK",         "",             ""        ], // GPIO48
+    ["",            "",             "",           "SD1_CMD",         "",             ""        ], // GPIO49
+    ["",            "",             "",           "SD1_DAT0",        "",             ""        ], // GPIO50
+    ["",            "",             "",           "SD1_DAT1",        "",             ""        ], // GPIO51
+    ["",            "",             "",           "SD1_DAT2",        "",             ""        ], // GPIO52
+    ["",            "",             "",           "SD1_DAT3",        "",             ""        ], // GPIO53
+];
+
+// Alternate functions Alt0 - Alt5 for GPIO0 - GPIO45 on the BCM2711. GPIO46 - GPIO57
+// are only used internally.
+#[rustfmt::skip]
+const FUNCTIONS_BCM2711: [[&str; ALT_FUNCTIONS_BCM]; 46] = [
+    ["SDA0",        "SA5",          "PCLK",       "SPI3_CE0_N",      "TXD2",              "SDA6"        ], // GPIO0
+    ["SCL0",        "SA4",          "DE",         "SPI3_MISO",       "RXD2",              "SCL6"        ], // GPIO1
+    ["SDA1",        "SA3",          "LCD_VSYNC",  "SPI3_MOSI",       "CTS2",              "SDA3"        ], // GPIO2
+    ["SCL1",        "SA2",          "LCD_HSYNC",  "SPI3_SCLK",       "RTS2",              "SCL3"        ], // GPIO3
+    ["GPCLK0",      "SA1",          "DPI_D0",     "SPI4_CE0_N",      "TXD3",              "SDA3"        ], // GPIO4
+    ["GPCLK1",      "SA0",          "DPI_D1",     "SPI4_MISO",       "RXD3",              "SCL3"        ], // GPIO5
+    ["GPCLK2",      "SOE_N_SE",     "DPI_D2",     "SPI4_MOSI",       "CTS3",              "SDA4"        ], // GPIO6
+    ["SPI0_CE1_N",  "SWE_N_SRW_N",  "DPI_D3",     "SPI4_SCLK",       "RTS3",              "SCL4"        ], // GPIO7
+    ["SPI0_CE0_N",  "SD0",          "DPI_D4",     "BSCSL_CE_N",      "TXD4",              "SDA4"        ], // GPIO8
+    ["SPI0_MISO",   "SD1",          "DPI_D5",     "BSCSL_MISO",      "RXD4",              "SCL4"        ], // GPIO9
+    ["SPI0_MOSI",   "SD2",          "DPI_D6",     "BSCSL_SDA_MOSI",  "CTS4",              "SDA5"        ], // GPIO10
+    ["SPI0_SCLK",   "SD3",          "DPI_D7",     "BSCSL_SCL_SCLK",  "RTS4",              "SCL5"        ], // GPIO11
+    ["PWM0_0",      "SD4",          "DPI_D8",     "SPI5_CE0_N",      "TXD5",              "SDA5"        ], // GPIO12
+    ["PWM0_1",      "SD5",          "DPI_D9",     "SPI5_MISO",       "RXD5",              "SCL5"        ], // GPIO13
+    ["TXD0",        "SD6",          "DPI_D10",    "SPI5_MOSI",       "CTS5",              "TXD1"        ], // GPIO14
+    ["RXD0",        "SD7",          "DPI_D11",    "SPI5_SCLK",       "RTS5",              "RXD1"        ], // GPIO15
+    ["",            "SD8",          "DPI_D12",    "CTS0",            "SPI1_CE2_N",        "CTS1"        ], // GPIO16
+    ["",            "SD9",          "DPI_D13",    "RTS0",            "SPI1_CE1_N",        "RTS1"        ], // GPIO17
+    ["PCM_CLK",     "SD10",         "DPI_D14",    "SPI6_CE0_N",      "SPI1_CE0_N",        "PWM0_0"      ], // GPIO18
+    ["PCM_FS",      "SD11",         "DPI_D15",    "SPI6_MISO",       "SPI1_MISO",         "PWM0_1"      ], // GPIO19
+    ["PCM_DIN",     "SD12",         "DPI_D16",    "SPI6_MOSI",       "SPI1_MOSI",         "GPCLK0"      ], // GPIO20
+    ["PCM_DOUT",    "SD13",         "DPI_D17",    "SPI6_SCLK",       "SPI1_SCLK",         "GPCLK1"      ], // GPIO21
+    ["SD0_CLK",     "SD14",         "DPI_D18",    "SD1_CLK",         "ARM_TRST",          "SDA6"        ], // GPIO22
+    ["SD0_CMD",     "SD15",         "DPI_D19",    "SD1_CMD",         "ARM_RTCK",          "SCL6"        ], // GPIO23
+    ["SD0_DAT0",    "SD16",         "DPI_D20",    "SD1_DAT0",        "ARM_TDO",           "SPI3_CE1_N"  ], // GPIO24
+    ["SD0_DAT1",    "SD17",         "DPI_D21",    "SD1_DAT1",        "ARM_TCK",           "SPI4_CE1_N"  ], // GPIO25
+    ["SD0_DAT2",    "",             "DPI_D22",    "SD1_DAT2",        "ARM_TDI",           "SPI5_CE1_N"  ], // GPIO26
+    ["SD0_DAT3",    "",             "DPI_D23",    "SD1_DAT3",        "ARM_TMS",           "SPI6_CE1_N"  ], // GPIO27
+    ["SDA0",        "SA5",          "PCM_CLK",    "",                "MII_A_RX_ERR",      "RGMII_MDIO"  ], // GPIO28
+    ["SCL0",        "SA4",          "PCM_FS",     "",                "MII_A_TX_ERR",      "RGMII_MDC"   ], // GPIO29
+    ["",            "SA3",          "PCM_DIN",    "CTS0",            "MII_A_CRS",         "CTS1"        ], // GPIO30
+    ["",            "SA2",          "PCM_DOUT",   "RTS0",            "MII_A_COL",         "RTS1"        ], // GPIO31
+    ["GPCLK0",      "SA1",          "",           "TXD0",            "SD_CARD_PRES",      "TXD1"        ], // GPIO32
+    ["",            "SA0",          "",           "RXD0",            "SD_CARD_WRPROT",    "RXD1"        ], // GPIO33
+    ["GPCLK0",      "SOE_N_SE",     "",           "SD1_CLK",         "SD_CARD_LED",       "RGMII_IRQ"   ], // GPIO34
+    ["SPI0_CE1_N",  "SWE_N_SRW_N",  "",           "SD1_CMD",         "RGMII_START_STOP",  ""            ], // GPIO35
+    ["SPI0_CE0_N",  "SD0",          "TXD0",       "SD1_DAT0",        "RGMII_RX_OK",       "MII_A_RX_ERR"], // GPIO36
+    ["SPI0_MISO",   "SD1",          "RXD0",       "SD1_DAT1",        "RGMII_MDIO",        "MII_A_TX_ERR"], // GPIO37
+    ["SPI0_MOSI",   "SD2",          "RTS0",       "SD1_DAT2",        "RGMII_MDC",         "MII_A_CRS"   ], // GPIO38
+    ["SPI0_SCLK",   "SD3",          "CTS0",       "SD1_DAT3",        "RGMII_IRQ",         "MII_A_COL"   ], // GPIO39
+    ["PWM1_0",      "SD4",          "",           "SD1_DAT4",        "SPI0_MISO",         "TXD1"        ], // GPIO40
+    ["PWM1_1",      "SD5",          "",           "SD1_DAT5",        "SPI0_MOSI",         "RXD1"        ], // GPIO41
+    ["GPCLK1",      "SD6",          "",           "SD1_DAT6",        "SPI0_SCLK",         "RTS1"        ], // GPIO42
+    ["GPCLK2",      "SD7",          "",           "SD1_DAT7",        "SPI0_CE0_N",        "CTS1"        ], // GPIO43
+    ["GPCLK1",      "SDA0",         "SDA1",       "",                "SPI0_CE1_N",        "SD_CARD_VOLT"], // GPIO44
+    ["PWM0_1",      "SCL0",         "SCL1",       "",                "SPI0_CE2_N",        "SD_CARD_PWR0"], // GPIO45
+];
+
+// Alternate functions Alt0 - Alt8 for GPIO0 - GPIO27 on the RP1's bank 0. Alt5 selects
+// the registered I/O (RIO) block, which is used for regular GPIO access. Banks 1 and 2
+// are only used internally.
+#[rustfmt::skip]
+const FUNCTIONS_RP1: [[&str; ALT_FUNCTIONS_RP1]; 28] = [
+    ["SPI0_SIO3",   "DPI_PCLK",   "TXD1",          "SDA0",        "",            "SYS_RIO00",  "PROC_RIO00",  "PIO0",   "SPI2_CE0" ], // GPIO0
+    ["SPI0_SIO2",   "DPI_DE",     "RXD1",          "SCL0",        "",            "SYS_RIO01",  "PROC_RIO01",  "PIO1",   "SPI2_SIO1"], // GPIO1
+    ["SPI0_CE3",    "DPI_VSYNC",  "CTS1",          "SDA1",        "IR_RX0",      "SYS_RIO02",  "PROC_RIO02",  "PIO2",   "SPI2_SIO0"], // GPIO2
+    ["SPI0_CE2",    "DPI_HSYNC",  "RTS1",          "SCL1",        "IR_TX0",      "SYS_RIO03",  "PROC_RIO03",  "PIO3",   "SPI2_SCLK"], // GPIO3
+    ["GPCLK0",      "DPI_D0",     "TXD2",          "SDA2",        "RI0",         "SYS_RIO04",  "PROC_RIO04",  "PIO4",   "SPI3_CE0" ], // GPIO4
+    ["GPCLK1",      "DPI_D1",     "RXD2",          "SCL2",        "DTR0",        "SYS_RIO05",  "PROC_RIO05",  "PIO5",   "SPI3_SIO1"], // GPIO5
+    ["GPCLK2",      "DPI_D2",     "CTS2",          "SDA3",        "DCD0",        "SYS_RIO06",  "PROC_RIO06",  "PIO6",   "SPI3_SIO0"], // GPIO6
+    ["SPI0_CE1",    "DPI_D3",     "RTS2",          "SCL3",        "DSR0",        "SYS_RIO07",  "PROC_RIO07",  "PIO7",   "SPI3_SCLK"], // GPIO7
+    ["SPI0_CE0",    "DPI_D4",     "TXD3",          "SDA0",        "",            "SYS_RIO08",  "PROC_RIO08",  "PIO8",   "SPI4_CE0" ], // GPIO8
+    ["SPI0_MISO",   "DPI_D5",     "RXD3",          "SCL0",        "",            "SYS_RIO09",  "PROC_RIO09",  "PIO9",   "SPI4_SIO0"], // GPIO9
+    ["SPI0_MOSI",   "DPI_D6",     "CTS3",          "SDA1",        "",            "SYS_RIO10",  "PROC_RIO10",  "PIO10",  "SPI4_SIO1"], // GPIO10
+    ["SPI0_SCLK",   "DPI_D7",     "RTS3",          "SCL1",        "",            "SYS_RIO11",  "PROC_RIO11",  "PIO11",  "SPI4_SCLK"], // GPIO11
+    ["PWM0_CHAN0",  "DPI_D8",     "TXD4",          "SDA2",        "AAUD_LEFT",   "SYS_RIO12",  "PROC_RIO12",  "PIO12",  "SPI5_CE0" ], // GPIO12
+    ["PWM0_CHAN1",  "DPI_D9",     "RXD4",          "SCL2",        "AAUD_RIGHT",  "SYS_RIO13",  "PROC_RIO13",  "PIO13",  "SPI5_SIO1"], // GPIO13
+    ["PWM0_CHAN2",  "DPI_D10",    "CTS4",          "SDA3",        "TXD0",        "SYS_RIO14",  "PROC_RIO14",  "PIO14",  "SPI5_SIO0"], // GPIO14
+    ["PWM0_CHAN3",  "DPI_D11",    "RTS4",          "SCL3",        "RXD0",        "SYS_RIO15",  "PROC_RIO15",  "PIO15",  "SPI5_SCLK"], // GPIO15
+    ["SPI1_CE2",    "DPI_D12",    "MIPI0_DSI_TE",  "",            "CTS0",        "SYS_RIO16",  "PROC_RIO16",  "PIO16",  ""         ], // GPIO16
+    ["SPI1_CE1",    "DPI_D13",    "MIPI1_DSI_TE",  "",            "RTS0",        "SYS_RIO17",  "PROC_RIO17",  "PIO17",  ""         ], // GPIO17
+    ["SPI1_CE0",    "DPI_D14",    "I2S0_SCLK",     "PWM0_CHAN2",  "I2S1_SCLK",   "SYS_RIO18",  "PROC_RIO18",  "PIO18",  "GPCLK1"   ], // GPIO18
+    ["SPI1_MISO",   "DPI_D15",    "I2S0_WS",       "PWM0_CHAN3",  "I2S1_WS",     "SYS_RIO19",  "PROC_RIO19",  "PIO19",  ""         ], // GPIO19
+    ["SPI1_MOSI",   "DPI_D16",    "I2S0_SDI0",     "GPCLK0",      "I2S1_SDI0",   "SYS_RIO20",  "PROC_RIO20",  "PIO20",  ""         ], // GPIO20
+    ["SPI1_SCLK",   "DPI_D17",    "I2S0_SDO0",     "GPCLK1",      "I2S1_SDO0",   "SYS_RIO21",  "PROC_RIO21",  "PIO21",  ""         ], // GPIO21
+    ["SD0_CLK",     "DPI_D18",    "I2S0_SDI1",     "SDA3",        "I2S1_SDI1",   "SYS_RIO22",  "PROC_RIO22",  "PIO22",  ""         ], // GPIO22
+    ["SD0_CMD",     "DPI_D19",    "I2S0_SDO1",     "SCL3",        "I2S1_SDO1",   "SYS_RIO23",  "PROC_RIO23",  "PIO23",  ""         ], // GPIO23
+    ["SD0_DAT0",    "DPI_D20",    "I2S0_SDI2",     "",            "I2S1_SDI2",   "SYS_RIO24",  "PROC_RIO24",  "PIO24",  "SPI2_CE1" ], // GPIO24
+    ["SD0_DAT1",    "DPI_D21",    "I2S0_SDO2",     "MIC_CLK",     "I2S1_SDO2",   "SYS_RIO25",  "PROC_RIO25",  "PIO25",  "SPI3_CE1" ], // GPIO25
+    ["SD0_DAT2",    "DPI_D22",    "I2S0_SDI3",     "MIC_DAT0",    "I2S1_SDI3",   "SYS_RIO26",  "PROC_RIO26",  "PIO26",  "SPI5_CE1" ], // GPIO26
+    ["SD0_DAT3",    "DPI_D23",    "I2S0_SDO3",     "MIC_DAT1",    "I2S1_SDO3",   "SYS_RIO27",  "PROC_RIO27",  "PIO27",  "SPI1_CE1" ], // GPIO27
+];
+
+// Alternate function modes, ordered by their function select index
+const ALT_MODES: [Mode; ALT_FUNCTIONS_RP1] = [
+    Mode::Alt0,
+    Mode::Alt1,
+    Mode::Alt2,
+    Mode::Alt3,
+    Mode::Alt4,
+    Mode::Alt5,
+    Mode::Alt6,
+    Mode::Alt7,
+    Mode::Alt8,
+];
+
+fn lookup(soc: SoC, pin: u8, index: usize) -> Option<&'static str> {
+    // The GPIO pins on the Raspberry Pi 5 are controlled by the RP1 rather than the BCM2712
+    let name = match soc {
+        SoC::Bcm2835 | SoC::Bcm2836 | SoC::Bcm2837A1 | SoC::Bcm2837B0 => {
+            FUNCTIONS_BCM283X.get(pin as usize)?.get(index)?
+        }
+        SoC::Bcm2711 => FUNCTIONS_BCM2711.get(pin as usize)?.get(index)?,
+        SoC::Bcm2712 => FUNCTIONS_RP1.get(pin as usize)?.get(index)?,
+    };
+
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+/// Returns the name of the peripheral signal that's selected when the specified pin is
+/// set to the specified alternate function mode.
+///
+/// Signal names follow the naming used in the SoC's datasheet, for instance `SPI0_MOSI`,
+/// `PWM0_1` or `TXD1`. On the Raspberry Pi 5, the names refer to the RP1's functions,
+/// since the GPIO pins are controlled by the RP1 rather than the BCM2712.
+///
+/// Returns `None` if `mode` is [`Input`] or [`Output`], or if the alternate function
+/// is reserved, only used internally, or unknown for the specified SoC and pin.
+///
+/// [`Input`]: enum.Mode.html#variant.Input
+/// [`Output`]: enum.Mode.html#variant.Output
+pub fn function_name(soc: SoC, pin: u8, mode: Mode) -> Option<&'static str> {
+    let index = ALT_MODES.iter().position(|&alt_mode| alt_mode == mode)?;
+
+    lookup(soc, pin, index)
+}
+
+/// Returns all pins and alternate function modes that select the specified
+/// peripheral signal.
+///
+/// `name` is compared case-insensitively against the signal names returned by
+/// [`function_name`]. The results are ordered by BCM GPIO number and mode. An empty
+/// `Vec` is returned if none of the pins provide the specified signal.
+///
+/// [`function_name`]: fn.function_name.html
+pub fn function_pins(soc: SoC, name: &str) -> Vec<(u8, Mode)> {
+    let mut pins = Vec::new();
+
+    for pin in 0..=u8::MAX {
+        for (index, &mode) in ALT_MODES.iter().enumerate() {
+            if let Some(function) = lookup(soc, pin, index) {
+                if function.eq_ignore_ascii_case(name) {
+                    pins.push((pin, mode));
+                }
+            }
+        }
+    }
+
+    pins
+}
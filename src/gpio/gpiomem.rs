@@ -1,13 +1,227 @@
-use crate::gpio::{Bias, Level, Mode};
+use std::fs::File;
+use std::os::unix::io::AsRawFd;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::gpio::ioctl::{LineConfig, LineRequest};
+use crate::gpio::{Bias, Drive, DriveStrength, Error, Level, Mode, Result, SlewRate, Trigger};
 
 pub mod bcm;
+pub mod cdev;
+pub mod custom;
 pub mod rp1;
+pub mod sim;
 
 pub(crate) trait GpioRegisters: std::fmt::Debug + Sync + Send {
-    fn set_high(&self, pin: u8);
-    fn set_low(&self, pin: u8);
+    // Changing a pin's configuration or output state only fails on backends that
+    // control the pins through the gpiochip.
+    fn set_high(&self, pin: u8) -> Result<()>;
+    fn set_low(&self, pin: u8) -> Result<()>;
     fn level(&self, pin: u8) -> Level;
     fn mode(&self, pin: u8) -> Mode;
-    fn set_mode(&self, pin: u8, mode: Mode);
-    fn set_bias(&self, pin: u8, bias: Bias);
+    fn set_mode(&self, pin: u8, mode: Mode) -> Result<()>;
+    fn set_bias(&self, pin: u8, bias: Bias) -> Result<()>;
+
+    // Reads the logic level of multiple pins, specified as a bitmask indexed by pin
+    // number. Backends that can't read multiple pins in a single register read
+    // read the pins one at a time.
+    fn levels(&self, mask: u64) -> u64 {
+        let mut levels = 0;
+
+        for pin in 0..64 {
+            if (mask >> pin) & 0x01 > 0 {
+                levels |= (self.level(pin) as u64) << pin;
+            }
+        }
+
+        levels
+    }
+
+    // Sets the output state of multiple pins, specified as bitmasks indexed by pin
+    // number. Backends that can't change multiple pins in a single register write
+    // change the pins one at a time.
+    fn set_levels(&self, high: u64, low: u64) -> Result<()> {
+        for pin in 0..64 {
+            if (high >> pin) & 0x01 > 0 {
+                self.set_high(pin)?;
+            } else if (low >> pin) & 0x01 > 0 {
+                self.set_low(pin)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Not every SoC or backend allows the current bias to be read back.
+    fn bias(&self, _pin: u8) -> Result<Bias> {
+        Err(Error::NotSupported)
+    }
+
+    // Only backends that control the pins through the gpiochip can configure
+    // open-drain/open-source outputs. Other backends have them emulated.
+    fn set_drive(&self, _pin: u8, _drive: Drive) -> Result<()> {
+        Err(Error::NotSupported)
+    }
+
+    // Only backends that control the pins through the gpiochip can invert the pins.
+    // Other backends have them inverted in software.
+    fn set_active_low(&self, _pin: u8, _active_low: bool) -> Result<()> {
+        Err(Error::NotSupported)
+    }
+
+    // Pad control. Not every SoC or backend allows the pads to be configured.
+    fn drive_strength(&self, _pin: u8) -> Result<DriveStrength> {
+        Err(Error::NotSupported)
+    }
+
+    fn set_drive_strength(&self, _pin: u8, _drive_strength: DriveStrength) -> Result<()> {
+        Err(Error::NotSupported)
+    }
+
+    fn slew_rate(&self, _pin: u8) -> Result<SlewRate> {
+        Err(Error::NotSupported)
+    }
+
+    fn set_slew_rate(&self, _pin: u8, _slew_rate: SlewRate) -> Result<()> {
+        Err(Error::NotSupported)
+    }
+
+    fn schmitt_trigger(&self, _pin: u8) -> Result<bool> {
+        Err(Error::NotSupported)
+    }
+
+    fn set_schmitt_trigger(&self, _pin: u8, _enabled: bool) -> Result<()> {
+        Err(Error::NotSupported)
+    }
+
+    fn input_enable(&self, _pin: u8) -> Result<bool> {
+        Err(Error::NotSupported)
+    }
+
+    fn set_input_enable(&self, _pin: u8, _enabled: bool) -> Result<()> {
+        Err(Error::NotSupported)
+    }
+
+    // Called when a pin is retrieved through Gpio::get. If request_line is true,
+    // the backend should request the line from the gpiochip, so other processes
+    // can't use the pin until it's released.
+    fn claim(&self, _pin: u8, _request_line: bool) -> Result<()> {
+        Ok(())
+    }
+
+    // Called when a pin goes out of scope.
+    fn release(&self, _pin: u8) {}
+
+    // Backends that hold a gpiochip line request for a pin can't have a second,
+    // separate request opened for interrupts. Those backends reconfigure their
+    // own request for edge detection, and return a duplicate. Returns None if
+    // interrupts should be requested through the gpiochip as usual.
+    fn request_events(
+        &self,
+        _pin: u8,
+        _trigger: Trigger,
+        _debounce: Option<Duration>,
+    ) -> Option<Result<LineRequest>> {
+        None
+    }
+
+    // Called when an interrupt is cleared or goes out of scope. Backends that
+    // reconfigured their own line request for edge detection disable it again.
+    fn release_events(&self, _pin: u8) -> Result<()> {
+        Ok(())
+    }
+}
+
+// gpiochip line requests held by the memory-mapped backends while a pin is in use,
+// which prevents other processes from requesting the same line.
+pub(crate) struct LineRequests {
+    cdev: File,
+    requests: Vec<Mutex<Option<LineRequest>>>,
+}
+
+impl std::fmt::Debug for LineRequests {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LineRequests")
+            .field("cdev", &self.cdev)
+            .field("requests", &format_args!("{{ .. }}"))
+            .finish()
+    }
+}
+
+impl LineRequests {
+    pub fn new(cdev: &File, gpio_lines: u8) -> Result<LineRequests> {
+        let mut requests = Vec::with_capacity(gpio_lines as usize);
+        for _ in 0..gpio_lines {
+            requests.push(Mutex::new(None));
+        }
+
+        Ok(LineRequests {
+            cdev: cdev.try_clone()?,
+            requests,
+        })
+    }
+
+    pub fn claim(&self, pin: u8, request_line: bool) -> Result<()> {
+        if !request_line {
+            return Ok(());
+        }
+
+        // Request the line "as is", so its current configuration doesn't change
+        let request = LineRequest::new(self.cdev.as_raw_fd(), u32::from(pin))?;
+        *self.requests[pin as usize].lock().unwrap() = Some(request);
+
+        Ok(())
+    }
+
+    pub fn release(&self, gpio_mem: &dyn GpioRegisters, pin: u8) {
+        let mut request = self.requests[pin as usize].lock().unwrap();
+
+        if request.is_some() {
+            // Some pinctrl drivers switch the pin to an input when the line
+            // is freed, so restore the mode if it changed.
+            let mode = gpio_mem.mode(pin);
+            *request = None;
+
+            // Only used by the memory-mapped backends, which can't fail
+            if gpio_mem.mode(pin) != mode {
+                let _ = gpio_mem.set_mode(pin, mode);
+            }
+        }
+    }
+
+    pub fn request_events(
+        &self,
+        pin: u8,
+        trigger: Trigger,
+        debounce: Option<Duration>,
+    ) -> Option<Result<LineRequest>> {
+        let mut request = self.requests[pin as usize].lock().unwrap();
+        let request = request.as_mut()?;
+
+        Some(
+            request
+                .set_config(LineConfig::with_trigger(trigger, debounce))
+                .and_then(|_| {
+                    request.clear_events()?;
+                    request.try_clone()
+                }),
+        )
+    }
+
+    pub fn release_events(&self, gpio_mem: &dyn GpioRegisters, pin: u8) -> Result<()> {
+        let mut request = self.requests[pin as usize].lock().unwrap();
+
+        if let Some(ref mut request) = *request {
+            // Disabling edge detection through the gpiochip switches the pin to
+            // input mode, so restore the mode if it changed.
+            let mode = gpio_mem.mode(pin);
+            request.set_config(LineConfig::with_trigger(Trigger::Disabled, None))?;
+
+            if gpio_mem.mode(pin) != mode {
+                gpio_mem.set_mode(pin, mode)?;
+            }
+        }
+
+        Ok(())
+    }
 }
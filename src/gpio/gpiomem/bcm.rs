@@ -1,5 +1,5 @@
 use std::fmt;
-use std::fs::OpenOptions;
+use std::fs::{File, OpenOptions};
 use std::io;
 use std::os::unix::fs::OpenOptionsExt;
 use std::os::unix::io::AsRawFd;
@@ -10,8 +10,9 @@ use std::time::Duration;
 
 use libc::{self, c_void, off_t, size_t, MAP_FAILED, MAP_SHARED, O_SYNC, PROT_READ, PROT_WRITE};
 
-use crate::gpio::gpiomem::GpioRegisters;
-use crate::gpio::{Bias, Error, Level, Mode, Result};
+use crate::gpio::gpiomem::{GpioRegisters, LineRequests};
+use crate::gpio::ioctl::LineRequest;
+use crate::gpio::{Bias, Error, Level, Mode, Result, Trigger};
 use crate::system::{DeviceInfo, SoC};
 
 const PATH_DEV_GPIOMEM: &str = "/dev/gpiomem";
@@ -42,6 +43,7 @@ pub struct GpioMem {
     mem_ptr: *mut u32,
     locks: [AtomicBool; GPIO_MEM_REGISTERS],
     soc: SoC,
+    line_requests: LineRequests,
 }
 
 impl fmt::Debug for GpioMem {
@@ -50,12 +52,13 @@ impl fmt::Debug for GpioMem {
             .field("mem_ptr", &self.mem_ptr)
             .field("locks", &format_args!("{{ .. }}"))
             .field("soc", &self.soc)
+            .field("line_requests", &self.line_requests)
             .finish()
     }
 }
 
 impl GpioMem {
-    pub fn open() -> Result<GpioMem> {
+    pub fn open(cdev: &File, gpio_lines: u8) -> Result<GpioMem> {
         // Try /dev/gpiomem first. If that fails, try /dev/mem instead. If neither works,
         // report back the error that's the most relevant.
         let mem_ptr = match Self::map_devgpiomem() {
@@ -87,6 +90,7 @@ impl GpioMem {
             mem_ptr,
             locks,
             soc,
+            line_requests: LineRequests::new(cdev, gpio_lines)?,
         })
     }
 
@@ -172,19 +176,54 @@ impl Drop for GpioMem {
 
 impl GpioRegisters for GpioMem {
     #[inline(always)]
-    fn set_high(&self, pin: u8) {
+    fn set_high(&self, pin: u8) -> Result<()> {
         let offset = GPSET0 + pin as usize / 32;
         let shift = pin % 32;
 
         self.write(offset, 1 << shift);
+
+        Ok(())
     }
 
     #[inline(always)]
-    fn set_low(&self, pin: u8) {
+    fn set_low(&self, pin: u8) -> Result<()> {
         let offset = GPCLR0 + pin as usize / 32;
         let shift = pin % 32;
 
         self.write(offset, 1 << shift);
+
+        Ok(())
+    }
+
+    fn levels(&self, mask: u64) -> u64 {
+        let mut levels = 0;
+
+        // GPLEV0 contains GPIO0-31, GPLEV1 contains GPIO32-57
+        for index in 0..2 {
+            if (mask >> (index * 32)) as u32 > 0 {
+                levels |= u64::from(self.read(GPLEV0 + index)) << (index * 32);
+            }
+        }
+
+        levels & mask
+    }
+
+    fn set_levels(&self, high: u64, low: u64) -> Result<()> {
+        // GPSET0/GPCLR0 contain GPIO0-31, GPSET1/GPCLR1 contain GPIO32-57
+        for index in 0..2 {
+            let high = (high >> (index * 32)) as u32;
+            let low = (low >> (index * 32)) as u32;
+
+            if high > 0 {
+                self.write(GPSET0 + index, high);
+            }
+
+            if low > 0 {
+                self.write(GPCLR0 + index, low);
+            }
+        }
+
+        Ok(())
     }
 
     #[inline(always)]
@@ -214,7 +253,7 @@ impl GpioRegisters for GpioMem {
         }
     }
 
-    fn set_mode(&self, pin: u8, mode: Mode) {
+    fn set_mode(&self, pin: u8, mode: Mode) -> Result<()> {
         let offset = GPFSEL0 + pin as usize / 10;
         let shift = (pin % 10) * 3;
 
@@ -246,9 +285,11 @@ impl GpioRegisters for GpioMem {
         );
 
         self.locks[offset].store(false, Ordering::SeqCst);
+
+        Ok(())
     }
 
-    fn set_bias(&self, pin: u8, bias: Bias) {
+    fn set_bias(&self, pin: u8, bias: Bias) -> Result<()> {
         // Offset for register.
         let offset: usize;
         // Bit shift for pin position within register value.
@@ -325,6 +366,48 @@ impl GpioRegisters for GpioMem {
             self.locks[offset].store(false, Ordering::SeqCst);
             self.locks[GPPUD].store(false, Ordering::SeqCst);
         }
+
+        Ok(())
+    }
+
+    fn bias(&self, pin: u8) -> Result<Bias> {
+        // Only BCM2711 (RPi4) and BCM2712 (RPi5) have readable bias registers.
+        // GPPUD/GPPUDCLK0 on earlier SoCs are write-only.
+        if self.soc != SoC::Bcm2711 && self.soc != SoC::Bcm2712 {
+            return Err(Error::NotSupported);
+        }
+
+        let offset = GPPUD_CNTRL_REG0 + pin as usize / 16;
+        let shift = pin % 16 * 2;
+        let reg_value = self.read(offset);
+
+        match (reg_value >> shift) & 0b11 {
+            0b00 => Ok(Bias::Off),
+            0b01 => Ok(Bias::PullUp),
+            0b10 => Ok(Bias::PullDown),
+            _ => Err(Error::NotSupported),
+        }
+    }
+
+    fn claim(&self, pin: u8, request_line: bool) -> Result<()> {
+        self.line_requests.claim(pin, request_line)
+    }
+
+    fn release(&self, pin: u8) {
+        self.line_requests.release(self, pin);
+    }
+
+    fn request_events(
+        &self,
+        pin: u8,
+        trigger: Trigger,
+        debounce: Option<Duration>,
+    ) -> Option<Result<LineRequest>> {
+        self.line_requests.request_events(pin, trigger, debounce)
+    }
+
+    fn release_events(&self, pin: u8) -> Result<()> {
+        self.line_requests.release_events(self, pin)
     }
 }
 
@@ -0,0 +1,285 @@
+use std::fmt;
+use std::fs::File;
+use std::os::unix::io::AsRawFd;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::gpio::ioctl::{self, LineConfig, LineInfo, LineRequest};
+use crate::gpio::{Bias, Drive, Error, Level, Mode, Result, Trigger};
+
+use super::GpioRegisters;
+
+// Configuration of a requested line. Changes are applied to the line request
+// through GPIO_V2_LINE_SET_CONFIG_IOCTL.
+#[derive(Debug)]
+struct Line {
+    request: Option<LineRequest>,
+    // Input or Output
+    mode: Mode,
+    // None until the bias is changed, in which case the kernel leaves the
+    // current bias as is.
+    bias: Option<Bias>,
+    // Output level, which is applied when the line is configured as an output
+    level: Level,
+    drive: Drive,
+    active_low: bool,
+    trigger: Trigger,
+    debounce: Option<Duration>,
+}
+
+impl Line {
+    fn new() -> Line {
+        Line {
+            request: None,
+            mode: Mode::Input,
+            bias: None,
+            level: Level::Low,
+            drive: Drive::PushPull,
+            active_low: false,
+            trigger: Trigger::Disabled,
+            debounce: None,
+        }
+    }
+
+    fn config(&self) -> LineConfig {
+        let mut line_config = if self.mode == Mode::Output {
+            let mut line_config = LineConfig::new(
+                ioctl::LINE_FLAG_OUTPUT
+                    | match self.drive {
+                        Drive::PushPull => 0,
+                        Drive::OpenDrain => ioctl::LINE_FLAG_OPEN_DRAIN,
+                        Drive::OpenSource => ioctl::LINE_FLAG_OPEN_SOURCE,
+                    },
+            );
+            line_config.add_attribute(ioctl::LINE_ATTR_ID_OUTPUT_VALUES, self.level as u64, 0x01);
+
+            line_config
+        } else {
+            LineConfig::with_trigger(self.trigger, self.debounce)
+        };
+
+        if self.active_low {
+            line_config.flags |= ioctl::LINE_FLAG_ACTIVE_LOW;
+        }
+
+        line_config.flags |= match self.bias {
+            Some(Bias::Off) => ioctl::LINE_FLAG_BIAS_DISABLED,
+            Some(Bias::PullDown) => ioctl::LINE_FLAG_BIAS_PULL_DOWN,
+            Some(Bias::PullUp) => ioctl::LINE_FLAG_BIAS_PULL_UP,
+            None => 0,
+        };
+
+        line_config
+    }
+
+    // Apply a modified configuration to the line request. The cached
+    // configuration is only updated if the line was reconfigured successfully.
+    fn configure<F>(&mut self, update: F) -> Result<()>
+    where
+        F: FnOnce(&mut Line),
+    {
+        let mut line = Line {
+            request: None,
+            ..*self
+        };
+        update(&mut line);
+
+        if let Some(ref mut request) = self.request {
+            request.set_config(line.config())?;
+        }
+
+        line.request = self.request.take();
+        *self = line;
+
+        Ok(())
+    }
+}
+
+// Controls the GPIO lines exclusively through gpiochip v2 line requests,
+// without any memory-mapped register access. Alternate functions aren't
+// supported.
+pub struct GpioCdev {
+    cdev: File,
+    lines: Vec<Mutex<Line>>,
+}
+
+impl fmt::Debug for GpioCdev {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GpioCdev")
+            .field("cdev", &self.cdev)
+            .field("lines", &format_args!("{{ .. }}"))
+            .finish()
+    }
+}
+
+impl GpioCdev {
+    pub fn open(cdev: &File, gpio_lines: u8) -> Result<GpioCdev> {
+        // Make sure the gpiochip supports the v2 uAPI, which is required
+        // to reconfigure requested lines.
+        LineInfo::new(cdev.as_raw_fd(), 0)?;
+
+        let mut lines = Vec::with_capacity(gpio_lines as usize);
+        for _ in 0..gpio_lines {
+            lines.push(Mutex::new(Line::new()));
+        }
+
+        Ok(GpioCdev {
+            cdev: cdev.try_clone()?,
+            lines,
+        })
+    }
+}
+
+impl GpioRegisters for GpioCdev {
+    fn set_high(&self, pin: u8) -> Result<()> {
+        let mut line = self.lines[pin as usize].lock().unwrap();
+        line.level = Level::High;
+
+        if let (Mode::Output, Some(ref request)) = (line.mode, &line.request) {
+            request.set_levels(0x01, 0x01)?;
+        }
+
+        Ok(())
+    }
+
+    fn set_low(&self, pin: u8) -> Result<()> {
+        let mut line = self.lines[pin as usize].lock().unwrap();
+        line.level = Level::Low;
+
+        if let (Mode::Output, Some(ref request)) = (line.mode, &line.request) {
+            request.set_levels(0x00, 0x01)?;
+        }
+
+        Ok(())
+    }
+
+    fn level(&self, pin: u8) -> Level {
+        let line = self.lines[pin as usize].lock().unwrap();
+
+        if let Some(ref request) = line.request {
+            if let Ok(line_values) = request.levels() {
+                return Level::from((line_values.bits & 0x01) as u8);
+            }
+        }
+
+        line.level
+    }
+
+    fn mode(&self, pin: u8) -> Mode {
+        match LineInfo::new(self.cdev.as_raw_fd(), u32::from(pin)) {
+            Ok(line_info) if line_info.flags().output() => Mode::Output,
+            _ => Mode::Input,
+        }
+    }
+
+    fn set_mode(&self, pin: u8, mode: Mode) -> Result<()> {
+        // Alternate functions can't be selected through the gpiochip
+        if mode != Mode::Input && mode != Mode::Output {
+            return Err(Error::NotSupported);
+        }
+
+        self.lines[pin as usize]
+            .lock()
+            .unwrap()
+            .configure(|line| line.mode = mode)
+    }
+
+    fn set_bias(&self, pin: u8, bias: Bias) -> Result<()> {
+        self.lines[pin as usize]
+            .lock()
+            .unwrap()
+            .configure(|line| line.bias = Some(bias))
+    }
+
+    fn set_drive(&self, pin: u8, drive: Drive) -> Result<()> {
+        self.lines[pin as usize]
+            .lock()
+            .unwrap()
+            .configure(|line| line.drive = drive)
+    }
+
+    fn set_active_low(&self, pin: u8, active_low: bool) -> Result<()> {
+        self.lines[pin as usize].lock().unwrap().configure(|line| {
+            // The output level is stored as a logical value, so it needs to be
+            // inverted to keep the physical output state unchanged.
+            if line.active_low != active_low {
+                line.level = !line.level;
+            }
+
+            line.active_low = active_low;
+        })
+    }
+
+    fn bias(&self, pin: u8) -> Result<Bias> {
+        let line_info = LineInfo::new(self.cdev.as_raw_fd(), u32::from(pin))?;
+        let flags = line_info.flags();
+
+        // The kernel only reports the bias if it was configured through the gpiochip
+        if flags.bias_pull_up() {
+            Ok(Bias::PullUp)
+        } else if flags.bias_pull_down() {
+            Ok(Bias::PullDown)
+        } else if flags.bias_disabled() {
+            Ok(Bias::Off)
+        } else {
+            Err(Error::NotSupported)
+        }
+    }
+
+    // Lines are always requested, since they can't be controlled otherwise
+    fn claim(&self, pin: u8, _request_line: bool) -> Result<()> {
+        let mut line = self.lines[pin as usize].lock().unwrap();
+
+        // Request the line "as is", so its current configuration doesn't change
+        let request = LineRequest::new(self.cdev.as_raw_fd(), u32::from(pin))?;
+        let level = Level::from((request.levels()?.bits & 0x01) as u8);
+
+        *line = Line::new();
+        line.request = Some(request);
+        line.mode = self.mode(pin);
+        line.level = level;
+
+        Ok(())
+    }
+
+    fn release(&self, pin: u8) {
+        *self.lines[pin as usize].lock().unwrap() = Line::new();
+    }
+
+    fn request_events(
+        &self,
+        pin: u8,
+        trigger: Trigger,
+        debounce: Option<Duration>,
+    ) -> Option<Result<LineRequest>> {
+        let mut line = self.lines[pin as usize].lock().unwrap();
+
+        // Unclaimed pins don't hold a line request
+        line.request.as_ref()?;
+
+        let result = line.configure(|line| {
+            line.mode = Mode::Input;
+            line.trigger = trigger;
+            line.debounce = debounce;
+        });
+
+        Some(result.and_then(|_| {
+            let request = line.request.as_ref().unwrap();
+            request.clear_events()?;
+            request.try_clone()
+        }))
+    }
+
+    fn release_events(&self, pin: u8) -> Result<()> {
+        let mut line = self.lines[pin as usize].lock().unwrap();
+
+        if line.trigger == Trigger::Disabled && line.debounce.is_none() {
+            return Ok(());
+        }
+
+        line.configure(|line| {
+            line.trigger = Trigger::Disabled;
+            line.debounce = None;
+        })
+    }
+}
@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use crate::gpio::ioctl::LineRequest;
+use crate::gpio::{Bias, Error, Level, Mode, Registers, Result, Trigger};
+
+use super::GpioRegisters;
+
+// Forwards register access to a user-provided Registers implementation.
+#[derive(Debug)]
+pub struct GpioCustom {
+    registers: Box<dyn Registers>,
+}
+
+impl GpioCustom {
+    pub fn new(registers: Box<dyn Registers>) -> GpioCustom {
+        GpioCustom { registers }
+    }
+}
+
+impl GpioRegisters for GpioCustom {
+    fn set_high(&self, pin: u8) -> Result<()> {
+        self.registers.set_level(pin, Level::High)
+    }
+
+    fn set_low(&self, pin: u8) -> Result<()> {
+        self.registers.set_level(pin, Level::Low)
+    }
+
+    fn level(&self, pin: u8) -> Level {
+        self.registers.level(pin)
+    }
+
+    fn mode(&self, pin: u8) -> Mode {
+        self.registers.mode(pin)
+    }
+
+    fn set_mode(&self, pin: u8, mode: Mode) -> Result<()> {
+        self.registers.set_mode(pin, mode)
+    }
+
+    fn set_bias(&self, pin: u8, bias: Bias) -> Result<()> {
+        self.registers.set_bias(pin, bias)
+    }
+
+    fn bias(&self, pin: u8) -> Result<Bias> {
+        self.registers.bias(pin)
+    }
+
+    // There's no gpiochip to request edge events from
+    fn request_events(
+        &self,
+        _pin: u8,
+        _trigger: Trigger,
+        _debounce: Option<Duration>,
+    ) -> Option<Result<LineRequest>> {
+        Some(Err(Error::NotSupported))
+    }
+}
@@ -1,18 +1,20 @@
 #![allow(dead_code)]
 
 use std::fmt;
-use std::fs::OpenOptions;
+use std::fs::{File, OpenOptions};
 use std::io;
 use std::os::unix::fs::OpenOptionsExt;
 use std::os::unix::io::AsRawFd;
 use std::ptr;
+use std::time::Duration;
 
 use libc::{self, c_void, size_t, MAP_FAILED, MAP_SHARED, O_SYNC, PROT_READ, PROT_WRITE};
 
-use crate::gpio::{Bias, Error, Level, Mode, Result};
+use crate::gpio::ioctl::LineRequest;
+use crate::gpio::{Bias, DriveStrength, Error, Level, Mode, Result, SlewRate, Trigger};
 use crate::system::{DeviceInfo, SoC};
 
-use super::GpioRegisters;
+use super::{GpioRegisters, LineRequests};
 
 const PATH_DEV_GPIOMEM: &str = "/dev/gpiomem0";
 
@@ -21,11 +23,20 @@ const REG_SIZE: usize = std::mem::size_of::<u32>();
 // gpiomem contains IO_BANK0-2, SYS_RIO0-2, PADS_BANK0-2, PADS_ETH
 const MEM_SIZE: usize = 0x30000;
 
-// We're only accessing the first 28 GPIOs. The rest is currently marked
-// as internal-use only, so we only need IO_BANK0/SYS_RIO0/PADS_BANK0.
+// GPIOs are spread across 3 banks. Bank 0 contains GPIO0-27, bank 1 contains
+// GPIO28-33 and bank 2 contains GPIO34-53. Banks 1 and 2 are currently marked as
+// internal-use only, and are only accessible when explicitly enabled.
 const IO_BANK0_OFFSET: usize = 0x00000;
 const SYS_RIO0_OFFSET: usize = 0x10000;
 const PADS_BANK0_OFFSET: usize = 0x20000;
+// Offset to the next bank for the IO_BANK, SYS_RIO and PADS_BANK registers
+const BANK_OFFSET: usize = 0x4000;
+
+// First GPIO in each bank
+const BANK1_GPIO: u8 = 28;
+const BANK2_GPIO: u8 = 34;
+// Total number of GPIOs across all banks
+const GPIO_LINES: u8 = 54;
 
 // Atomic register access (datasheet @ 2.4)
 const RW_OFFSET: usize = 0x0000;
@@ -67,6 +78,12 @@ const PADS_GPIO: usize = 0x04;
 // Offset to the next GPIO for the PADS_BANK registers (datasheet @ 3.1.4)
 const PADS_OFFSET: usize = 4;
 
+const PADS_SLEWFAST_MASK: u32 = 0x01;
+const PADS_SCHMITT_MASK: u32 = 0x02;
+
+const PADS_DRIVE_MASK: u32 = 0x30;
+const PADS_DRIVE_LSB: u32 = 4;
+
 const PADS_IN_ENABLE_MASK: u32 = 0x40;
 const PADS_OUT_DISABLE_MASK: u32 = 0x80;
 
@@ -87,6 +104,7 @@ const RIO_IN: usize = 0x08;
 pub struct GpioMem {
     mem_ptr: *mut u32,
     soc: SoC,
+    line_requests: LineRequests,
 }
 
 impl fmt::Debug for GpioMem {
@@ -94,18 +112,23 @@ impl fmt::Debug for GpioMem {
         f.debug_struct("GpioMem")
             .field("mem_ptr", &self.mem_ptr)
             .field("soc", &self.soc)
+            .field("line_requests", &self.line_requests)
             .finish()
     }
 }
 
 impl GpioMem {
-    pub fn open() -> Result<GpioMem> {
+    pub fn open(cdev: &File, gpio_lines: u8) -> Result<GpioMem> {
         let mem_ptr = Self::map_devgpiomem()?;
 
         // Identify which SoC we're using.
         let soc = DeviceInfo::new().map_err(|_| Error::UnknownModel)?.soc();
 
-        Ok(GpioMem { mem_ptr, soc })
+        Ok(GpioMem {
+            mem_ptr,
+            soc,
+            line_requests: LineRequests::new(cdev, gpio_lines)?,
+        })
     }
 
     fn map_devgpiomem() -> Result<*mut u32> {
@@ -150,9 +173,63 @@ impl GpioMem {
         }
     }
 
+    // Returns the register offset for the pin's bank, and the pin's index within that bank
+    #[inline(always)]
+    fn bank(pin: u8) -> (usize, u8) {
+        if pin >= BANK2_GPIO {
+            (2 * BANK_OFFSET, pin - BANK2_GPIO)
+        } else if pin >= BANK1_GPIO {
+            (BANK_OFFSET, pin - BANK1_GPIO)
+        } else {
+            (0, pin)
+        }
+    }
+
+    // Returns a bitmask covering all GPIOs in the bank that starts with the specified pin
+    #[inline(always)]
+    fn bank_mask(first_pin: u8) -> u32 {
+        let gpio_lines = match first_pin {
+            0 => BANK1_GPIO,
+            BANK1_GPIO => BANK2_GPIO - BANK1_GPIO,
+            _ => GPIO_LINES - BANK2_GPIO,
+        };
+
+        (1 << gpio_lines) - 1
+    }
+
+    #[inline(always)]
+    fn ctrl_offset(pin: u8) -> usize {
+        let (bank_offset, index) = Self::bank(pin);
+
+        (IO_BANK0_OFFSET + bank_offset + GPIO_CTRL + (index as usize * GPIO_OFFSET) + RW_OFFSET)
+            / REG_SIZE
+    }
+
+    #[inline(always)]
+    fn pads_offset(pin: u8, atomic_offset: usize) -> usize {
+        let (bank_offset, index) = Self::bank(pin);
+
+        (PADS_BANK0_OFFSET
+            + bank_offset
+            + PADS_GPIO
+            + (index as usize * PADS_OFFSET)
+            + atomic_offset)
+            / REG_SIZE
+    }
+
+    #[inline(always)]
+    fn rio_offset(pin: u8, register: usize, atomic_offset: usize) -> (usize, u8) {
+        let (bank_offset, index) = Self::bank(pin);
+
+        (
+            (SYS_RIO0_OFFSET + bank_offset + register + atomic_offset) / REG_SIZE,
+            index,
+        )
+    }
+
     fn direction(&self, pin: u8) -> Mode {
-        let offset = (SYS_RIO0_OFFSET + RIO_OE) / REG_SIZE;
-        let reg_value = (self.read(offset) >> pin) as u8 & 0b1;
+        let (offset, shift) = Self::rio_offset(pin, RIO_OE, RW_OFFSET);
+        let reg_value = (self.read(offset) >> shift) as u8 & 0b1;
 
         if reg_value > 0 {
             Mode::Output
@@ -162,24 +239,22 @@ impl GpioMem {
     }
 
     fn set_direction(&self, pin: u8, mode: Mode) {
-        let offset = match mode {
-            Mode::Output => (SYS_RIO0_OFFSET + RIO_OE + SET_OFFSET) / REG_SIZE,
-            _ => (SYS_RIO0_OFFSET + RIO_OE + CLR_OFFSET) / REG_SIZE,
+        let (offset, shift) = match mode {
+            Mode::Output => Self::rio_offset(pin, RIO_OE, SET_OFFSET),
+            _ => Self::rio_offset(pin, RIO_OE, CLR_OFFSET),
         };
 
-        self.write(offset, 1 << pin);
+        self.write(offset, 1 << shift);
     }
 
-    fn input_enable(&self, pin: u8) {
-        let offset =
-            (PADS_BANK0_OFFSET + PADS_GPIO + (pin as usize * PADS_OFFSET) + SET_OFFSET) / REG_SIZE;
+    fn enable_input(&self, pin: u8) {
+        let offset = Self::pads_offset(pin, SET_OFFSET);
 
         self.write(offset, PADS_IN_ENABLE_MASK);
     }
 
-    fn output_enable(&self, pin: u8) {
-        let offset =
-            (PADS_BANK0_OFFSET + PADS_GPIO + (pin as usize * PADS_OFFSET) + CLR_OFFSET) / REG_SIZE;
+    fn enable_output(&self, pin: u8) {
+        let offset = Self::pads_offset(pin, CLR_OFFSET);
 
         self.write(offset, PADS_OUT_DISABLE_MASK);
     }
@@ -187,30 +262,72 @@ impl GpioMem {
 
 impl GpioRegisters for GpioMem {
     #[inline(always)]
-    fn set_high(&self, pin: u8) {
-        let offset = (SYS_RIO0_OFFSET + RIO_OUT + SET_OFFSET) / REG_SIZE;
+    fn set_high(&self, pin: u8) -> Result<()> {
+        let (offset, shift) = Self::rio_offset(pin, RIO_OUT, SET_OFFSET);
+
+        self.write(offset, 1 << shift);
 
-        self.write(offset, 1 << pin);
+        Ok(())
     }
 
     #[inline(always)]
-    fn set_low(&self, pin: u8) {
-        let offset = (SYS_RIO0_OFFSET + RIO_OUT + CLR_OFFSET) / REG_SIZE;
+    fn set_low(&self, pin: u8) -> Result<()> {
+        let (offset, shift) = Self::rio_offset(pin, RIO_OUT, CLR_OFFSET);
+
+        self.write(offset, 1 << shift);
+
+        Ok(())
+    }
+
+    fn levels(&self, mask: u64) -> u64 {
+        let mut levels = 0;
 
-        self.write(offset, 1 << pin);
+        // Each bank has its own SYS_RIO registers
+        for &first_pin in &[0, BANK1_GPIO, BANK2_GPIO] {
+            let (offset, shift) = Self::rio_offset(first_pin, RIO_IN, RW_OFFSET);
+            let bank_mask = Self::bank_mask(first_pin);
+
+            if (mask >> first_pin) as u32 & bank_mask > 0 {
+                let bank_levels = (self.read(offset) >> shift) & bank_mask;
+                levels |= u64::from(bank_levels) << first_pin;
+            }
+        }
+
+        levels & mask
+    }
+
+    fn set_levels(&self, high: u64, low: u64) -> Result<()> {
+        // Each bank has its own SYS_RIO registers
+        for &first_pin in &[0, BANK1_GPIO, BANK2_GPIO] {
+            let (set_offset, shift) = Self::rio_offset(first_pin, RIO_OUT, SET_OFFSET);
+            let (clr_offset, _) = Self::rio_offset(first_pin, RIO_OUT, CLR_OFFSET);
+            let bank_mask = Self::bank_mask(first_pin);
+
+            let high = ((high >> first_pin) as u32 & bank_mask) << shift;
+            let low = ((low >> first_pin) as u32 & bank_mask) << shift;
+
+            if high > 0 {
+                self.write(set_offset, high);
+            }
+
+            if low > 0 {
+                self.write(clr_offset, low);
+            }
+        }
+
+        Ok(())
     }
 
     #[inline(always)]
     fn level(&self, pin: u8) -> Level {
-        let offset = (SYS_RIO0_OFFSET + RIO_IN) / REG_SIZE;
+        let (offset, shift) = Self::rio_offset(pin, RIO_IN, RW_OFFSET);
         let reg_value = self.read(offset);
 
-        unsafe { std::mem::transmute((reg_value >> pin) as u8 & 0b1) }
+        unsafe { std::mem::transmute((reg_value >> shift) as u8 & 0b1) }
     }
 
     fn mode(&self, pin: u8) -> Mode {
-        let offset =
-            (IO_BANK0_OFFSET + GPIO_CTRL + (pin as usize * GPIO_OFFSET) + RW_OFFSET) / REG_SIZE;
+        let offset = Self::ctrl_offset(pin);
         let reg_value = self.read(offset);
 
         match (reg_value & CTRL_FUNCSEL_MASK) as u8 {
@@ -227,12 +344,11 @@ impl GpioRegisters for GpioMem {
         }
     }
 
-    fn set_mode(&self, pin: u8, mode: Mode) {
-        self.input_enable(pin);
-        self.output_enable(pin);
+    fn set_mode(&self, pin: u8, mode: Mode) -> Result<()> {
+        self.enable_input(pin);
+        self.enable_output(pin);
 
-        let offset =
-            (IO_BANK0_OFFSET + GPIO_CTRL + (pin as usize * GPIO_OFFSET) + RW_OFFSET) / REG_SIZE;
+        let offset = Self::ctrl_offset(pin);
         let mut reg_value = self.read(offset);
 
         let fsel_mode = match mode {
@@ -259,11 +375,12 @@ impl GpioRegisters for GpioMem {
         reg_value = (reg_value & !CTRL_FUNCSEL_MASK) | ((fsel_mode as u32) << CTRL_FUNCSEL_LSB);
 
         self.write(offset, reg_value);
+
+        Ok(())
     }
 
-    fn set_bias(&self, pin: u8, bias: Bias) {
-        let offset =
-            (PADS_BANK0_OFFSET + PADS_GPIO + (pin as usize * PADS_OFFSET) + RW_OFFSET) / REG_SIZE;
+    fn set_bias(&self, pin: u8, bias: Bias) -> Result<()> {
+        let offset = Self::pads_offset(pin, RW_OFFSET);
         let mut reg_value = self.read(offset);
 
         reg_value = match bias {
@@ -273,6 +390,120 @@ impl GpioRegisters for GpioMem {
         };
 
         self.write(offset, reg_value);
+
+        Ok(())
+    }
+
+    fn bias(&self, pin: u8) -> Result<Bias> {
+        let reg_value = self.read(Self::pads_offset(pin, RW_OFFSET));
+
+        // Enabling both resistors isn't supported by Bias
+        match (reg_value & PADS_BIAS_MASK) >> PADS_BIAS_LSB {
+            PADS_BIAS_OFF => Ok(Bias::Off),
+            PADS_BIAS_DOWN => Ok(Bias::PullDown),
+            PADS_BIAS_UP => Ok(Bias::PullUp),
+            _ => Err(Error::NotSupported),
+        }
+    }
+
+    fn drive_strength(&self, pin: u8) -> Result<DriveStrength> {
+        let reg_value = self.read(Self::pads_offset(pin, RW_OFFSET));
+
+        Ok(match (reg_value & PADS_DRIVE_MASK) >> PADS_DRIVE_LSB {
+            0b00 => DriveStrength::Ma2,
+            0b01 => DriveStrength::Ma4,
+            0b10 => DriveStrength::Ma8,
+            _ => DriveStrength::Ma12,
+        })
+    }
+
+    fn set_drive_strength(&self, pin: u8, drive_strength: DriveStrength) -> Result<()> {
+        let offset = Self::pads_offset(pin, RW_OFFSET);
+        let mut reg_value = self.read(offset);
+
+        reg_value = (reg_value & !PADS_DRIVE_MASK) | ((drive_strength as u32) << PADS_DRIVE_LSB);
+
+        self.write(offset, reg_value);
+
+        Ok(())
+    }
+
+    fn slew_rate(&self, pin: u8) -> Result<SlewRate> {
+        let reg_value = self.read(Self::pads_offset(pin, RW_OFFSET));
+
+        if reg_value & PADS_SLEWFAST_MASK > 0 {
+            Ok(SlewRate::Fast)
+        } else {
+            Ok(SlewRate::Slow)
+        }
+    }
+
+    fn set_slew_rate(&self, pin: u8, slew_rate: SlewRate) -> Result<()> {
+        let offset = match slew_rate {
+            SlewRate::Fast => Self::pads_offset(pin, SET_OFFSET),
+            SlewRate::Slow => Self::pads_offset(pin, CLR_OFFSET),
+        };
+
+        self.write(offset, PADS_SLEWFAST_MASK);
+
+        Ok(())
+    }
+
+    fn schmitt_trigger(&self, pin: u8) -> Result<bool> {
+        let reg_value = self.read(Self::pads_offset(pin, RW_OFFSET));
+
+        Ok(reg_value & PADS_SCHMITT_MASK > 0)
+    }
+
+    fn set_schmitt_trigger(&self, pin: u8, enabled: bool) -> Result<()> {
+        let offset = if enabled {
+            Self::pads_offset(pin, SET_OFFSET)
+        } else {
+            Self::pads_offset(pin, CLR_OFFSET)
+        };
+
+        self.write(offset, PADS_SCHMITT_MASK);
+
+        Ok(())
+    }
+
+    fn input_enable(&self, pin: u8) -> Result<bool> {
+        let reg_value = self.read(Self::pads_offset(pin, RW_OFFSET));
+
+        Ok(reg_value & PADS_IN_ENABLE_MASK > 0)
+    }
+
+    fn set_input_enable(&self, pin: u8, enabled: bool) -> Result<()> {
+        let offset = if enabled {
+            Self::pads_offset(pin, SET_OFFSET)
+        } else {
+            Self::pads_offset(pin, CLR_OFFSET)
+        };
+
+        self.write(offset, PADS_IN_ENABLE_MASK);
+
+        Ok(())
+    }
+
+    fn claim(&self, pin: u8, request_line: bool) -> Result<()> {
+        self.line_requests.claim(pin, request_line)
+    }
+
+    fn release(&self, pin: u8) {
+        self.line_requests.release(self, pin);
+    }
+
+    fn request_events(
+        &self,
+        pin: u8,
+        trigger: Trigger,
+        debounce: Option<Duration>,
+    ) -> Option<Result<LineRequest>> {
+        self.line_requests.request_events(pin, trigger, debounce)
+    }
+
+    fn release_events(&self, pin: u8) -> Result<()> {
+        self.line_requests.release_events(self, pin)
     }
 }
 
@@ -0,0 +1,281 @@
+use std::fmt;
+use std::mem;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use libc::{self, c_void, timespec, CLOCK_MONOTONIC};
+
+use crate::gpio::ioctl::{self, LineConfig, LineEvent, LineRequest};
+use crate::gpio::{Bias, Level, Mode, Result, Trigger};
+
+use super::GpioRegisters;
+
+// Edge detection configured for a line. Edge events are written to a socket in the
+// same format the kernel uses for gpiochip v2 line requests, so they can be read
+// through the regular interrupt code paths.
+#[derive(Debug)]
+struct EventSender {
+    fd: i32,
+    trigger: Trigger,
+    debounce: Option<Duration>,
+    // Timestamp of the last event, used for debouncing
+    last_timestamp: Option<Duration>,
+    line_seqno: u32,
+}
+
+impl EventSender {
+    fn send(&mut self, pin: u8, trigger: Trigger, seqno: &AtomicU32) -> Result<()> {
+        if self.trigger != Trigger::Both && self.trigger != trigger {
+            return Ok(());
+        }
+
+        let timestamp = monotonic_time();
+
+        if let (Some(debounce), Some(last_timestamp)) = (self.debounce, self.last_timestamp) {
+            if timestamp.saturating_sub(last_timestamp) < debounce {
+                return Ok(());
+            }
+        }
+
+        self.last_timestamp = Some(timestamp);
+        self.line_seqno = self.line_seqno.wrapping_add(1);
+
+        let line_event = LineEvent {
+            timestamp_ns: timestamp.as_nanos() as u64,
+            id: if trigger == Trigger::RisingEdge {
+                ioctl::LINE_EVENT_RISING_EDGE
+            } else {
+                ioctl::LINE_EVENT_FALLING_EDGE
+            },
+            offset: u32::from(pin),
+            seqno: seqno.fetch_add(1, Ordering::SeqCst).wrapping_add(1),
+            line_seqno: self.line_seqno,
+            padding: [0u32; 6],
+        };
+
+        // MSG_NOSIGNAL prevents SIGPIPE when the receiving end has been closed
+        match parse_retval!(unsafe {
+            libc::send(
+                self.fd,
+                &line_event as *const LineEvent as *const c_void,
+                mem::size_of::<LineEvent>(),
+                libc::MSG_DONTWAIT | libc::MSG_NOSIGNAL,
+            )
+        }) {
+            // The event is dropped when the socket buffer is full, similar to the kernel's
+            // event buffer. The gap in line_seqno is reported as missed events.
+            Err(ref e) if e.raw_os_error() == Some(libc::EAGAIN) => Ok(()),
+            Err(e) => Err(e.into()),
+            Ok(_) => Ok(()),
+        }
+    }
+}
+
+impl Drop for EventSender {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.fd);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Line {
+    mode: Mode,
+    bias: Bias,
+    // Output level, which is applied when the line is configured as an output
+    output_level: Level,
+    // Level driven by an external device through Simulator::set_level
+    external_level: Option<Level>,
+    events: Option<EventSender>,
+}
+
+impl Line {
+    fn new() -> Line {
+        Line {
+            mode: Mode::Input,
+            bias: Bias::Off,
+            output_level: Level::Low,
+            external_level: None,
+            events: None,
+        }
+    }
+
+    // Outputs take precedence over any external device. Otherwise, the line is
+    // driven by the external device, or pulled up or down by the bias. A floating
+    // line reads low.
+    fn level(&self) -> Level {
+        match (self.mode, self.external_level) {
+            (Mode::Output, _) => self.output_level,
+            (_, Some(level)) => level,
+            _ if self.bias == Bias::PullUp => Level::High,
+            _ => Level::Low,
+        }
+    }
+}
+
+// Simulates the GPIO lines in memory, without accessing any hardware.
+pub struct GpioSim {
+    lines: Vec<Mutex<Line>>,
+    // Sequence number of the last edge event across all lines
+    seqno: AtomicU32,
+}
+
+impl fmt::Debug for GpioSim {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GpioSim")
+            .field("lines", &format_args!("{{ .. }}"))
+            .field("seqno", &self.seqno)
+            .finish()
+    }
+}
+
+impl GpioSim {
+    pub fn new(gpio_lines: u8) -> GpioSim {
+        let mut lines = Vec::with_capacity(gpio_lines as usize);
+        for _ in 0..gpio_lines {
+            lines.push(Mutex::new(Line::new()));
+        }
+
+        GpioSim {
+            lines,
+            seqno: AtomicU32::new(0),
+        }
+    }
+
+    pub fn gpio_lines(&self) -> u8 {
+        self.lines.len() as u8
+    }
+
+    pub fn set_external_level(&self, pin: u8, level: Option<Level>) {
+        self.update(pin, |line| line.external_level = level);
+    }
+
+    // Applies a change to the line, and sends an edge event if its level changed
+    fn update<F>(&self, pin: u8, f: F)
+    where
+        F: FnOnce(&mut Line),
+    {
+        let mut line = self.lines[pin as usize].lock().unwrap();
+
+        let prev_level = line.level();
+        f(&mut line);
+        let level = line.level();
+
+        if level == prev_level {
+            return;
+        }
+
+        let trigger = match level {
+            Level::High => Trigger::RisingEdge,
+            Level::Low => Trigger::FallingEdge,
+        };
+
+        if let Some(ref mut events) = line.events {
+            // The receiving end has been closed if sending fails
+            if events.send(pin, trigger, &self.seqno).is_err() {
+                line.events = None;
+            }
+        }
+    }
+}
+
+impl GpioRegisters for GpioSim {
+    fn set_high(&self, pin: u8) -> Result<()> {
+        self.update(pin, |line| line.output_level = Level::High);
+
+        Ok(())
+    }
+
+    fn set_low(&self, pin: u8) -> Result<()> {
+        self.update(pin, |line| line.output_level = Level::Low);
+
+        Ok(())
+    }
+
+    fn level(&self, pin: u8) -> Level {
+        self.lines[pin as usize].lock().unwrap().level()
+    }
+
+    fn mode(&self, pin: u8) -> Mode {
+        self.lines[pin as usize].lock().unwrap().mode
+    }
+
+    fn set_mode(&self, pin: u8, mode: Mode) -> Result<()> {
+        self.update(pin, |line| line.mode = mode);
+
+        Ok(())
+    }
+
+    fn set_bias(&self, pin: u8, bias: Bias) -> Result<()> {
+        self.update(pin, |line| line.bias = bias);
+
+        Ok(())
+    }
+
+    fn bias(&self, pin: u8) -> Result<Bias> {
+        Ok(self.lines[pin as usize].lock().unwrap().bias)
+    }
+
+    // Edge events are delivered through a socket pair instead of a gpiochip line
+    // request. Similar to the kernel, the line is switched to input mode. A stream
+    // socket allows a single read to return all waiting events. Each event is sent
+    // in a single call, which is never split for a message this small.
+    fn request_events(
+        &self,
+        pin: u8,
+        trigger: Trigger,
+        debounce: Option<Duration>,
+    ) -> Option<Result<LineRequest>> {
+        let mut fds = [0i32; 2];
+        if let Err(e) = parse_retval!(unsafe {
+            libc::socketpair(
+                libc::AF_UNIX,
+                libc::SOCK_STREAM | libc::SOCK_CLOEXEC,
+                0,
+                fds.as_mut_ptr(),
+            )
+        }) {
+            return Some(Err(e.into()));
+        }
+
+        self.update(pin, |line| line.mode = Mode::Input);
+        self.update(pin, |line| {
+            line.events = Some(EventSender {
+                fd: fds[1],
+                trigger,
+                debounce,
+                last_timestamp: None,
+                line_seqno: 0,
+            });
+        });
+
+        let mut line_request = LineRequest::default();
+        line_request.offsets[0] = u32::from(pin);
+        line_request.num_lines = 1;
+        line_request.config = LineConfig::with_trigger(trigger, debounce);
+        line_request.fd = fds[0];
+
+        Some(Ok(line_request))
+    }
+
+    fn release_events(&self, pin: u8) -> Result<()> {
+        self.lines[pin as usize].lock().unwrap().events = None;
+
+        Ok(())
+    }
+}
+
+fn monotonic_time() -> Duration {
+    let mut ts = timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+
+    unsafe {
+        libc::clock_gettime(CLOCK_MONOTONIC, &mut ts);
+    }
+
+    Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+}
@@ -1,11 +1,19 @@
 use core::convert::Infallible;
 
 use embedded_hal::digital::{
-    ErrorType, InputPin as InputPinHal, OutputPin as OutputPinHal,
-    StatefulOutputPin as StatefulOutputPinHal, ToggleableOutputPin as ToggleableOutputPinHal,
+    self, ErrorType, InputPin as InputPinHal, OutputPin as OutputPinHal,
+    StatefulOutputPin as StatefulOutputPinHal,
 };
 
-use super::{InputPin, IoPin, Level, OutputPin, Pin};
+use super::{Error, InputPin, IoPin, Level, OutputPin, Pin};
+#[cfg(feature = "async")]
+use super::{InterruptStream, Trigger};
+
+impl digital::Error for Error {
+    fn kind(&self) -> digital::ErrorKind {
+        digital::ErrorKind::Other
+    }
+}
 
 /// `ErrorType` trait implementation for `embedded-hal` v1.0.0.
 impl ErrorType for Pin {
@@ -14,59 +22,59 @@ impl ErrorType for Pin {
 
 /// `InputPin` trait implementation for `embedded-hal` v1.0.0.
 impl InputPinHal for Pin {
-    fn is_high(&self) -> Result<bool, Self::Error> {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
         Ok(Self::read(self) == Level::High)
     }
 
-    fn is_low(&self) -> Result<bool, Self::Error> {
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
         Ok(Self::read(self) == Level::Low)
     }
 }
 
 /// `ErrorType` trait implementation for `embedded-hal` v1.0.0.
 impl ErrorType for InputPin {
-    type Error = Infallible;
+    type Error = Error;
 }
 
 /// `InputPin` trait implementation for `embedded-hal` v1.0.0.
 impl InputPinHal for InputPin {
-    fn is_high(&self) -> Result<bool, Self::Error> {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
         Ok(Self::is_high(self))
     }
 
-    fn is_low(&self) -> Result<bool, Self::Error> {
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
         Ok(Self::is_low(self))
     }
 }
 
 /// `ErrorType` trait implementation for `embedded-hal` v1.0.0.
 impl ErrorType for IoPin {
-    type Error = Infallible;
+    type Error = Error;
 }
 
 /// `InputPin` trait implementation for `embedded-hal` v1.0.0.
 impl InputPinHal for IoPin {
-    fn is_high(&self) -> Result<bool, Self::Error> {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
         Ok(Self::is_high(self))
     }
 
-    fn is_low(&self) -> Result<bool, Self::Error> {
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
         Ok(Self::is_low(self))
     }
 }
 
 /// `ErrorType` trait implementation for `embedded-hal` v1.0.0.
 impl ErrorType for OutputPin {
-    type Error = Infallible;
+    type Error = Error;
 }
 
 /// `InputPin` trait implementation for `embedded-hal` v1.0.0.
 impl InputPinHal for OutputPin {
-    fn is_high(&self) -> Result<bool, Self::Error> {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
         Ok(Self::is_set_high(self))
     }
 
-    fn is_low(&self) -> Result<bool, Self::Error> {
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
         Ok(Self::is_set_low(self))
     }
 }
@@ -74,21 +82,17 @@ impl InputPinHal for OutputPin {
 /// `OutputPin` trait implementation for `embedded-hal` v1.0.0.
 impl OutputPinHal for OutputPin {
     fn set_low(&mut self) -> Result<(), Self::Error> {
-        OutputPin::set_low(self);
-
-        Ok(())
+        OutputPin::set_low(self)
     }
 
     fn set_high(&mut self) -> Result<(), Self::Error> {
-        OutputPin::set_high(self);
-
-        Ok(())
+        OutputPin::set_high(self)
     }
 }
 
 /// `OutputPin` trait implementation for `embedded-hal` v0.2.7.
 impl embedded_hal_0::digital::v2::OutputPin for OutputPin {
-    type Error = Infallible;
+    type Error = Error;
 
     fn set_low(&mut self) -> Result<(), Self::Error> {
         OutputPinHal::set_low(self)
@@ -101,42 +105,33 @@ impl embedded_hal_0::digital::v2::OutputPin for OutputPin {
 
 /// `StatefulOutputPin` trait implementation for `embedded-hal` v1.0.0.
 impl StatefulOutputPinHal for OutputPin {
-    fn is_set_high(&self) -> Result<bool, Self::Error> {
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
         Ok(OutputPin::is_set_high(self))
     }
 
-    fn is_set_low(&self) -> Result<bool, Self::Error> {
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
         Ok(OutputPin::is_set_low(self))
     }
-}
 
-/// `ToggleableOutputPin` trait implementation for `embedded-hal` v1.0.0.
-impl ToggleableOutputPinHal for OutputPin {
     fn toggle(&mut self) -> Result<(), Self::Error> {
-        OutputPin::toggle(self);
-
-        Ok(())
+        OutputPin::toggle(self)
     }
 }
 
 /// `OutputPin` trait implementation for `embedded-hal` v1.0.0.
 impl OutputPinHal for IoPin {
     fn set_low(&mut self) -> Result<(), Self::Error> {
-        IoPin::set_low(self);
-
-        Ok(())
+        IoPin::set_low(self)
     }
 
     fn set_high(&mut self) -> Result<(), Self::Error> {
-        IoPin::set_high(self);
-
-        Ok(())
+        IoPin::set_high(self)
     }
 }
 
 /// `OutputPin` trait implementation for `embedded-hal` v0.2.7.
 impl embedded_hal_0::digital::v2::OutputPin for IoPin {
-    type Error = Infallible;
+    type Error = Error;
 
     fn set_low(&mut self) -> Result<(), Self::Error> {
         OutputPinHal::set_low(self)
@@ -149,21 +144,16 @@ impl embedded_hal_0::digital::v2::OutputPin for IoPin {
 
 /// `StatefulOutputPin` trait implementation for `embedded-hal` v1.0.0.
 impl StatefulOutputPinHal for IoPin {
-    fn is_set_high(&self) -> Result<bool, Self::Error> {
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
         Ok(IoPin::is_high(self))
     }
 
-    fn is_set_low(&self) -> Result<bool, Self::Error> {
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
         Ok(IoPin::is_low(self))
     }
-}
 
-/// `ToggleableOutputPin` trait implementation for `embedded-hal` v1.0.0.
-impl ToggleableOutputPinHal for IoPin {
     fn toggle(&mut self) -> Result<(), Self::Error> {
-        IoPin::toggle(self);
-
-        Ok(())
+        IoPin::toggle(self)
     }
 }
 
@@ -224,3 +214,78 @@ impl embedded_hal_0::PwmPin for IoPin {
         }
     }
 }
+
+/// `Wait` trait implementation for `embedded-hal-async` v1.0.0.
+#[cfg(feature = "async")]
+impl embedded_hal_async::digital::Wait for InputPin {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        wait_for_level(
+            self.interrupt_stream(Trigger::RisingEdge, None)?,
+            Level::High,
+        )
+        .await
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        wait_for_level(
+            self.interrupt_stream(Trigger::FallingEdge, None)?,
+            Level::Low,
+        )
+        .await
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_edge(Trigger::RisingEdge).await.map(|_| ())
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_edge(Trigger::FallingEdge).await.map(|_| ())
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_edge(Trigger::Both).await.map(|_| ())
+    }
+}
+
+/// `Wait` trait implementation for `embedded-hal-async` v1.0.0.
+#[cfg(feature = "async")]
+impl embedded_hal_async::digital::Wait for IoPin {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        wait_for_level(
+            self.interrupt_stream(Trigger::RisingEdge, None)?,
+            Level::High,
+        )
+        .await
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        wait_for_level(
+            self.interrupt_stream(Trigger::FallingEdge, None)?,
+            Level::Low,
+        )
+        .await
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_edge(Trigger::RisingEdge).await.map(|_| ())
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_edge(Trigger::FallingEdge).await.map(|_| ())
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_edge(Trigger::Both).await.map(|_| ())
+    }
+}
+
+// The level is checked after the interrupt trigger has been configured, so an edge
+// that occurs in the meantime isn't missed
+#[cfg(feature = "async")]
+async fn wait_for_level(mut stream: InterruptStream<'_>, level: Level) -> Result<(), Error> {
+    if stream.read() != level {
+        stream.next_event().await?;
+    }
+
+    Ok(())
+}
@@ -1,12 +1,9 @@
 use core::convert::Infallible;
 use std::time::Duration;
 
-use embedded_hal::digital::{
-    InputPin as InputPinHal, StatefulOutputPin as StatefulOutputPinHal,
-    ToggleableOutputPin as ToggleableOutputPinHal,
-};
+use embedded_hal::digital::StatefulOutputPin as StatefulOutputPinHal;
 
-use super::{InputPin, IoPin, OutputPin, Pin};
+use super::{Error, InputPin, IoPin, Level, OutputPin, Pin};
 use crate::gpio::Mode;
 
 const NANOS_PER_SEC: f64 = 1_000_000_000.0;
@@ -16,11 +13,11 @@ impl embedded_hal_0::digital::v2::InputPin for Pin {
     type Error = Infallible;
 
     fn is_high(&self) -> Result<bool, Self::Error> {
-        InputPinHal::is_high(self)
+        Ok(Self::read(self) == Level::High)
     }
 
     fn is_low(&self) -> Result<bool, Self::Error> {
-        InputPinHal::is_low(self)
+        Ok(Self::read(self) == Level::Low)
     }
 }
 
@@ -29,77 +26,77 @@ impl embedded_hal_0::digital::v2::InputPin for InputPin {
     type Error = Infallible;
 
     fn is_high(&self) -> Result<bool, Self::Error> {
-        InputPinHal::is_high(self)
+        Ok(Self::is_high(self))
     }
 
     fn is_low(&self) -> Result<bool, Self::Error> {
-        InputPinHal::is_low(self)
+        Ok(Self::is_low(self))
     }
 }
 
 /// Unproven `InputPin` trait implementation for `embedded-hal` v0.2.7.
 impl embedded_hal_0::digital::v2::InputPin for IoPin {
-    type Error = Infallible;
+    type Error = Error;
 
     fn is_high(&self) -> Result<bool, Self::Error> {
-        InputPinHal::is_high(self)
+        Ok(Self::is_high(self))
     }
 
     fn is_low(&self) -> Result<bool, Self::Error> {
-        InputPinHal::is_low(self)
+        Ok(Self::is_low(self))
     }
 }
 
 /// Unproven `InputPin` trait implementation for `embedded-hal` v0.2.7.
 impl embedded_hal_0::digital::v2::InputPin for OutputPin {
-    type Error = Infallible;
+    type Error = Error;
 
     fn is_high(&self) -> Result<bool, Self::Error> {
-        InputPinHal::is_high(self)
+        Ok(Self::is_set_high(self))
     }
 
     fn is_low(&self) -> Result<bool, Self::Error> {
-        InputPinHal::is_low(self)
+        Ok(Self::is_set_low(self))
     }
 }
 
 /// Unproven `StatefulOutputPin` trait implementation for `embedded-hal` v0.2.7.
 impl embedded_hal_0::digital::v2::StatefulOutputPin for IoPin {
     fn is_set_high(&self) -> Result<bool, Self::Error> {
-        StatefulOutputPinHal::is_set_high(self)
+        Ok(IoPin::is_high(self))
     }
 
     fn is_set_low(&self) -> Result<bool, Self::Error> {
-        StatefulOutputPinHal::is_set_low(self)
+        Ok(IoPin::is_low(self))
     }
 }
 
 /// Unproven `StatefulOutputPin` trait implementation for `embedded-hal` v0.2.7.
 impl embedded_hal_0::digital::v2::StatefulOutputPin for OutputPin {
     fn is_set_high(&self) -> Result<bool, Self::Error> {
-        StatefulOutputPinHal::is_set_high(self)
+        Ok(OutputPin::is_set_high(self))
     }
 
     fn is_set_low(&self) -> Result<bool, Self::Error> {
-        StatefulOutputPinHal::is_set_low(self)
+        Ok(OutputPin::is_set_low(self))
     }
 }
 
 /// Unproven `ToggleableOutputPin` trait implementation for `embedded-hal` v0.2.7.
 impl embedded_hal_0::digital::v2::ToggleableOutputPin for IoPin {
-    type Error = Infallible;
+    type Error = Error;
 
     fn toggle(&mut self) -> Result<(), Self::Error> {
-        ToggleableOutputPinHal::toggle(self)
+        StatefulOutputPinHal::toggle(self)
     }
 }
 
 /// Unproven `ToggleableOutputPin` trait implementation for `embedded-hal` v0.2.7.
 impl embedded_hal_0::digital::v2::ToggleableOutputPin for OutputPin {
-    type Error = Infallible;
+    type Error = Error;
 
     fn toggle(&mut self) -> Result<(), Self::Error> {
-        ToggleableOutputPinHal::toggle(self)
+        StatefulOutputPinHal::toggle(self)
     }
 }
 
@@ -223,14 +220,14 @@ impl embedded_hal_0::Pwm for IoPin {
 
 /// Unproven `IoPin` trait implementation for `embedded-hal` v0.2.7.
 impl embedded_hal_0::digital::v2::IoPin<IoPin, IoPin> for IoPin {
-    type Error = Infallible;
+    type Error = Error;
 
     /// Tries to convert this pin to input mode.
     ///
     /// If the pin is already in input mode, this method should succeed.
     fn into_input_pin(mut self) -> Result<IoPin, Self::Error> {
         if self.mode() != Mode::Input {
-            self.set_mode(Mode::Input);
+            self.set_mode(Mode::Input)?;
         }
 
         Ok(self)
@@ -245,12 +242,12 @@ impl embedded_hal_0::digital::v2::IoPin<IoPin, IoPin> for IoPin {
         state: embedded_hal_0::digital::v2::PinState,
     ) -> Result<IoPin, Self::Error> {
         match state {
-            embedded_hal_0::digital::v2::PinState::Low => self.set_low(),
-            embedded_hal_0::digital::v2::PinState::High => self.set_high(),
+            embedded_hal_0::digital::v2::PinState::Low => self.set_low()?,
+            embedded_hal_0::digital::v2::PinState::High => self.set_high()?,
         }
 
         if self.mode() != Mode::Output {
-            self.set_mode(Mode::Output);
+            self.set_mode(Mode::Output)?;
         }
 
         Ok(self)
@@ -1,29 +1,118 @@
 #![allow(dead_code)]
 
+use std::collections::VecDeque;
 use std::fmt;
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
+use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
 
+use crate::gpio::dispatcher::Dispatcher;
 use crate::gpio::epoll::{epoll_event, Epoll, EventFd, EPOLLERR, EPOLLET, EPOLLIN, EPOLLPRI};
+use crate::gpio::gpiomem::GpioRegisters;
 use crate::gpio::ioctl;
-use crate::gpio::pin::InputPin;
-use crate::gpio::{Error, Level, Result, Trigger};
+use crate::gpio::pin::invert_trigger;
+use crate::gpio::{Error, Event, Result, Trigger};
 
+// Edge events are requested through the gpiochip v2 uAPI. Kernels that predate
+// v2 (< 5.10) return ENOTTY, in which case we fall back to the deprecated v1 uAPI.
+// If the GPIO backend already holds a line request for the pin, that request is
+// reused instead.
 #[derive(Debug)]
-struct Interrupt {
+enum EventRequest {
+    V2(Box<ioctl::LineRequest>),
+    V1(ioctl::EventRequest),
+}
+
+impl EventRequest {
+    fn new(
+        cdev_fd: i32,
+        gpio_mem: &dyn GpioRegisters,
+        pin: u8,
+        trigger: Trigger,
+        debounce: Option<Duration>,
+    ) -> Result<EventRequest> {
+        if let Some(line_request) = gpio_mem.request_events(pin, trigger, debounce) {
+            return Ok(EventRequest::V2(Box::new(line_request?)));
+        }
+
+        match ioctl::LineRequest::with_config(
+            cdev_fd,
+            u32::from(pin),
+            ioctl::LineConfig::with_trigger(trigger, debounce),
+        ) {
+            Ok(line_request) => Ok(EventRequest::V2(Box::new(line_request))),
+            Err(Error::Io(ref e)) if e.raw_os_error() == Some(libc::ENOTTY) => Ok(
+                EventRequest::V1(ioctl::EventRequest::new(cdev_fd, pin, trigger)?),
+            ),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn fd(&self) -> i32 {
+        match self {
+            EventRequest::V2(line_request) => line_request.fd,
+            EventRequest::V1(event_request) => event_request.fd,
+        }
+    }
+
+    fn event(&self) -> Result<Event> {
+        match self {
+            EventRequest::V2(line_request) => ioctl::get_line_event(line_request.fd),
+            EventRequest::V1(event_request) => ioctl::get_event(event_request.fd),
+        }
+    }
+
+    // The v1 uAPI only returns a single event per read
+    fn events(&self, events: &mut Vec<Event>) -> Result<()> {
+        match self {
+            EventRequest::V2(line_request) => ioctl::get_line_events(line_request.fd, events),
+            EventRequest::V1(event_request) => {
+                events.push(ioctl::get_event(event_request.fd)?);
+                Ok(())
+            }
+        }
+    }
+
+    fn close(&mut self) {
+        match self {
+            EventRequest::V2(line_request) => line_request.close(),
+            EventRequest::V1(event_request) => event_request.close(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Interrupt {
     pin: u8,
     trigger: Trigger,
+    debounce: Option<Duration>,
     cdev_fd: i32,
-    event_request: ioctl::EventRequest,
+    gpio_mem: Arc<dyn GpioRegisters>,
+    event_request: EventRequest,
+    // Timestamp of the last accepted event, used for software debouncing
+    last_timestamp: Option<Duration>,
+    // Sequence number of the last event, used to detect dropped events
+    last_line_seqno: Option<u32>,
 }
 
 impl Interrupt {
-    fn new(cdev_fd: i32, pin: u8, trigger: Trigger) -> Result<Interrupt> {
+    pub(crate) fn new(
+        cdev_fd: i32,
+        gpio_mem: Arc<dyn GpioRegisters>,
+        pin: u8,
+        trigger: Trigger,
+        debounce: Option<Duration>,
+    ) -> Result<Interrupt> {
         Ok(Interrupt {
             pin,
             trigger,
+            debounce,
             cdev_fd,
-            event_request: ioctl::EventRequest::new(cdev_fd, pin, trigger)?,
+            event_request: EventRequest::new(cdev_fd, &*gpio_mem, pin, trigger, debounce)?,
+            gpio_mem,
+            last_timestamp: None,
+            last_line_seqno: None,
         })
     }
 
@@ -31,46 +120,247 @@ impl Interrupt {
         self.trigger
     }
 
-    fn fd(&self) -> i32 {
-        self.event_request.fd
+    fn debounce(&self) -> Option<Duration> {
+        self.debounce
+    }
+
+    pub(crate) fn fd(&self) -> i32 {
+        self.event_request.fd()
+    }
+
+    // Returns true if an event can be read without blocking
+    pub(crate) fn is_ready(&self) -> Result<bool> {
+        let mut poll_fd = libc::pollfd {
+            fd: self.fd(),
+            events: libc::POLLIN | libc::POLLPRI,
+            revents: 0,
+        };
+
+        Ok(parse_retval!(unsafe { libc::poll(&mut poll_fd, 1, 0) })? > 0)
     }
 
     fn pin(&self) -> u8 {
         self.pin
     }
 
-    fn set_trigger(&mut self, trigger: Trigger) -> Result<()> {
+    fn set_trigger(&mut self, trigger: Trigger, debounce: Option<Duration>) -> Result<()> {
         self.trigger = trigger;
+        self.debounce = debounce;
 
         self.reset()
     }
 
-    fn event(&mut self) -> Result<ioctl::Event> {
+    // Returns None if the event was dropped by the software debounce filter
+    pub(crate) fn event(&mut self) -> Result<Option<Event>> {
         // This might block if there are no events waiting
-        ioctl::get_event(self.event_request.fd)
+        let event = self.event_request.event()?;
+
+        Ok(self.accept(event))
+    }
+
+    // Reads all waiting events, and appends them to events. This might block if there
+    // are no events waiting.
+    pub(crate) fn events(&mut self, events: &mut Vec<Event>) -> Result<()> {
+        let mut new_events = Vec::new();
+        self.event_request.events(&mut new_events)?;
+
+        for event in new_events {
+            if let Some(event) = self.accept(event) {
+                events.push(event);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Applies the software debounce filter, and sets the number of missed events
+    fn accept(&mut self, mut event: Event) -> Option<Event> {
+        // The v2 uAPI debounces in the kernel. For v1, drop any edges that arrive
+        // within the debounce period after the last accepted edge.
+        if let (EventRequest::V1(_), Some(debounce)) = (&self.event_request, self.debounce) {
+            if let Some(last_timestamp) = self.last_timestamp {
+                if event.timestamp.saturating_sub(last_timestamp) < debounce {
+                    return None;
+                }
+            }
+        }
+
+        self.last_timestamp = Some(event.timestamp);
+
+        // The kernel increments line_seqno for every edge, including the ones that were
+        // dropped because its event buffer was full. The v1 uAPI doesn't provide
+        // sequence numbers.
+        if let EventRequest::V2(_) = self.event_request {
+            if let Some(last_line_seqno) = self.last_line_seqno {
+                event.missed = event
+                    .line_seqno
+                    .wrapping_sub(last_line_seqno)
+                    .saturating_sub(1);
+            }
+
+            self.last_line_seqno = Some(event.line_seqno);
+        }
+
+        Some(event)
+    }
+
+    // Discards all waiting events
+    fn clear(&mut self) -> Result<()> {
+        let mut events = Vec::new();
+        while self.is_ready()? {
+            self.event_request.events(&mut events)?;
+            events.clear();
+        }
+
+        self.last_timestamp = None;
+        self.last_line_seqno = None;
+
+        Ok(())
     }
 
     fn reset(&mut self) -> Result<()> {
         // Close the old event fd before opening a new one
         self.event_request.close();
-        self.event_request = ioctl::EventRequest::new(self.cdev_fd, self.pin, self.trigger)?;
+        self.event_request = EventRequest::new(
+            self.cdev_fd,
+            &*self.gpio_mem,
+            self.pin,
+            self.trigger,
+            self.debounce,
+        )?;
+        self.last_timestamp = None;
+        self.last_line_seqno = None;
 
         Ok(())
     }
 }
 
+impl Drop for Interrupt {
+    fn drop(&mut self) {
+        // Close the event fd before disabling edge detection, and ignore any
+        // errors, since they can't be reported while the interrupt is dropped.
+        self.event_request.close();
+        let _ = self.gpio_mem.release_events(self.pin);
+    }
+}
+
+/// Interrupt trigger that can be registered with an external event loop.
+///
+/// `InterruptFd`s are constructed by [`InputPin::interrupt_fd`]. The interrupt trigger
+/// remains configured until the `InterruptFd` goes out of scope, even if the
+/// `InputPin` is dropped first.
+///
+/// `InterruptFd` implements [`AsRawFd`] and [`AsFd`], which provide access to the
+/// underlying event fd. The fd becomes readable when a trigger event is waiting,
+/// which allows it to be registered with `epoll`, `poll`, `mio`, `calloop`, `glib` or any
+/// other event loop alongside sockets and timers. Once the fd is readable, call
+/// [`read_event`] to retrieve the decoded trigger events.
+///
+/// The fd is owned by `InterruptFd`. Don't read from or close the fd directly.
+///
+/// [`InputPin::interrupt_fd`]: struct.InputPin.html#method.interrupt_fd
+/// [`AsRawFd`]: https://doc.rust-lang.org/std/os/unix/io/trait.AsRawFd.html
+/// [`AsFd`]: https://doc.rust-lang.org/std/os/unix/io/trait.AsFd.html
+/// [`read_event`]: #method.read_event
+#[derive(Debug)]
+pub struct InterruptFd {
+    interrupt: Interrupt,
+    active_low_emulated: bool,
+}
+
+impl InterruptFd {
+    pub(crate) fn new(interrupt: Interrupt, active_low_emulated: bool) -> InterruptFd {
+        InterruptFd {
+            interrupt,
+            active_low_emulated,
+        }
+    }
+
+    /// Returns the GPIO pin number.
+    pub fn pin(&self) -> u8 {
+        self.interrupt.pin()
+    }
+
+    /// Returns the next trigger event without blocking.
+    ///
+    /// Returns `Ok(None)` if no trigger events are waiting. When used with an
+    /// edge-triggered event loop, call `read_event` until it returns `Ok(None)` to make sure
+    /// all waiting trigger events have been retrieved.
+    pub fn read_event(&mut self) -> Result<Option<Event>> {
+        while self.interrupt.is_ready()? {
+            // Events dropped by the software debounce filter return None
+            if let Some(mut event) = self.interrupt.event()? {
+                if self.active_low_emulated {
+                    event.trigger = invert_trigger(event.trigger);
+                }
+
+                return Ok(Some(event));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+impl AsRawFd for InterruptFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.interrupt.fd()
+    }
+}
+
+impl AsFd for InterruptFd {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        // The fd remains open for as long as the InterruptFd exists
+        unsafe { BorrowedFd::borrow_raw(self.interrupt.fd()) }
+    }
+}
+
+// Maximum number of events cached for each pin between polls. When the cache is
+// full, the oldest event is dropped.
+const CACHED_EVENTS_MAX: usize = 64;
+
 #[derive(Debug)]
 struct TriggerStatus {
     interrupt: Option<Interrupt>,
-    triggered: bool,
-    level: Level,
+    events: VecDeque<Event>,
+}
+
+impl TriggerStatus {
+    // Reads all waiting events, and adds them to the cache
+    fn read_events(&mut self, buffer: &mut Vec<Event>) -> Result<()> {
+        if let Some(ref mut interrupt) = self.interrupt {
+            buffer.clear();
+            interrupt.events(buffer)?;
+
+            for &event in buffer.iter() {
+                if self.events.len() == CACHED_EVENTS_MAX {
+                    // Account for the dropped event in the next oldest event
+                    if let Some(dropped) = self.events.pop_front() {
+                        if let Some(oldest) = self.events.front_mut() {
+                            oldest.missed = oldest
+                                .missed
+                                .saturating_add(dropped.missed)
+                                .saturating_add(1);
+                        }
+                    }
+                }
+
+                self.events.push_back(event);
+            }
+        }
+
+        Ok(())
+    }
 }
 
 pub struct EventLoop {
     poll: Epoll,
     events: Vec<epoll_event>,
     trigger_status: Vec<TriggerStatus>,
+    // Reused for each read to avoid allocations
+    buffer: Vec<Event>,
     cdev_fd: i32,
+    gpio_mem: Arc<dyn GpioRegisters>,
 }
 
 impl fmt::Debug for EventLoop {
@@ -79,21 +369,26 @@ impl fmt::Debug for EventLoop {
             .field("poll", &self.poll)
             .field("events", &format_args!("{{ .. }}"))
             .field("trigger_status", &format_args!("{{ .. }}"))
+            .field("buffer", &format_args!("{{ .. }}"))
             .field("cdev_fd", &self.cdev_fd)
+            .field("gpio_mem", &self.gpio_mem)
             .finish()
     }
 }
 
 impl EventLoop {
-    pub fn new(cdev_fd: i32, capacity: usize) -> Result<EventLoop> {
+    pub fn new(
+        cdev_fd: i32,
+        gpio_mem: Arc<dyn GpioRegisters>,
+        capacity: usize,
+    ) -> Result<EventLoop> {
         let mut trigger_status = Vec::with_capacity(capacity);
 
         // Initialize trigger_status while circumventing the Copy/Clone requirement
         for _ in 0..trigger_status.capacity() {
             trigger_status.push(TriggerStatus {
                 interrupt: None,
-                triggered: false,
-                level: Level::Low,
+                events: VecDeque::new(),
             });
         }
 
@@ -101,72 +396,71 @@ impl EventLoop {
             poll: Epoll::new()?,
             events: vec![epoll_event { events: 0, u64: 0 }; capacity],
             trigger_status,
+            buffer: Vec::new(),
             cdev_fd,
+            gpio_mem,
         })
     }
 
-    pub fn poll<'a>(
-        &mut self,
-        pins: &[&'a InputPin],
-        reset: bool,
-        timeout: Option<Duration>,
-    ) -> Result<Option<(&'a InputPin, Level)>> {
-        for pin in pins {
-            let trigger_status = &mut self.trigger_status[pin.pin() as usize];
-
-            // Did we cache any trigger events during the previous poll?
-            if trigger_status.triggered {
-                trigger_status.triggered = false;
+    // Clears any cached events, and discards any events waiting in the kernel's buffer
+    fn reset(&mut self, pins: &[u8]) -> Result<()> {
+        for &pin in pins {
+            let trigger_status = &mut self.trigger_status[pin as usize];
 
-                if !reset {
-                    return Ok(Some((pin, trigger_status.level)));
-                }
-            }
+            trigger_status.events.clear();
 
-            // Reset any pending trigger events
             if let Some(ref mut interrupt) = trigger_status.interrupt {
-                if reset {
-                    self.poll.delete(interrupt.fd())?;
-                    interrupt.reset()?;
-                    self.poll.add(
-                        interrupt.fd(),
-                        u64::from(interrupt.pin()),
-                        EPOLLIN | EPOLLPRI,
-                    )?;
-                }
+                interrupt.clear()?;
             }
         }
 
-        // Loop until we get any of the events we're waiting for, or a timeout occurs
-        let now = Instant::now();
-        loop {
-            let num_events = self.poll.wait(&mut self.events, timeout)?;
+        Ok(())
+    }
 
-            // No events means a timeout occurred
-            if num_events == 0 {
-                return Ok(None);
-            }
+    // Waits for events on any pin, and adds them to the cache. Returns false if a
+    // timeout occurred.
+    fn wait(&mut self, timeout: Option<Duration>) -> Result<bool> {
+        let num_events = self.poll.wait(&mut self.events, timeout)?;
+
+        // No events means a timeout occurred
+        if num_events == 0 {
+            return Ok(false);
+        }
+
+        for event in &self.events[0..num_events] {
+            let pin = event.u64 as usize;
+
+            self.trigger_status[pin].read_events(&mut self.buffer)?;
+        }
 
-            for event in &self.events[0..num_events] {
-                let pin = event.u64 as usize;
+        Ok(true)
+    }
 
-                let trigger_status = &mut self.trigger_status[pin];
+    // Returns the index of the pin that triggered, and the event
+    pub fn poll(
+        &mut self,
+        pins: &[u8],
+        reset: bool,
+        timeout: Option<Duration>,
+    ) -> Result<Option<(usize, Event)>> {
+        if reset {
+            self.reset(pins)?;
+        } else if let Some(event) = self.cached_event(pins) {
+            // We cached trigger events during the previous poll
+            return Ok(Some(event));
+        }
 
-                if let Some(ref mut interrupt) = trigger_status.interrupt {
-                    trigger_status.level = interrupt.event()?.level();
-                    trigger_status.triggered = true;
-                };
+        // Loop until we get any of the events we're waiting for, or a timeout occurs
+        let now = Instant::now();
+        loop {
+            if !self.wait(timeout)? {
+                return Ok(None);
             }
 
             // Were any interrupts triggered? If so, return one. The rest
             // will be saved for the next poll.
-            for pin in pins {
-                let trigger_status = &mut self.trigger_status[pin.pin() as usize];
-
-                if trigger_status.triggered {
-                    trigger_status.triggered = false;
-                    return Ok(Some((pin, trigger_status.level)));
-                }
+            if let Some(event) = self.cached_event(pins) {
+                return Ok(Some(event));
             }
 
             // It's possible a pin we're not waiting for continuously triggers
@@ -181,17 +475,83 @@ impl EventLoop {
         }
     }
 
-    pub fn set_interrupt(&mut self, pin: u8, trigger: Trigger) -> Result<()> {
+    fn cached_event(&mut self, pins: &[u8]) -> Option<(usize, Event)> {
+        for (index, &pin) in pins.iter().enumerate() {
+            if let Some(event) = self.trigger_status[pin as usize].events.pop_front() {
+                return Some((index, event));
+            }
+        }
+
+        None
+    }
+
+    // Returns the index of the pin that triggered, and the event, for each event
+    pub fn poll_batch(
+        &mut self,
+        pins: &[u8],
+        reset: bool,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<(usize, Event)>> {
+        if reset {
+            self.reset(pins)?;
+        }
+
+        // If we already have cached events, only collect the events that are
+        // currently waiting, without blocking
+        let cached = pins
+            .iter()
+            .any(|&pin| !self.trigger_status[pin as usize].events.is_empty());
+        let timeout = if cached {
+            Some(Duration::from_millis(0))
+        } else {
+            timeout
+        };
+
+        let mut events = Vec::new();
+
+        // Loop until we get any of the events we're waiting for, or a timeout occurs
+        let now = Instant::now();
+        loop {
+            let timed_out = !self.wait(timeout)?;
+
+            for (index, &pin) in pins.iter().enumerate() {
+                let trigger_status = &mut self.trigger_status[pin as usize];
+
+                events.extend(trigger_status.events.drain(..).map(|event| (index, event)));
+            }
+
+            if timed_out || !events.is_empty() {
+                // Events are ordered by pin. Sort them chronologically instead.
+                events.sort_by_key(|(_, event)| event.timestamp);
+
+                return Ok(events);
+            }
+
+            // Make sure we haven't been looping longer than the requested timeout
+            if let Some(t) = timeout {
+                if now.elapsed() > t {
+                    return Ok(events);
+                }
+            }
+        }
+    }
+
+    pub fn set_interrupt(
+        &mut self,
+        pin: u8,
+        trigger: Trigger,
+        debounce: Option<Duration>,
+    ) -> Result<()> {
         let trigger_status = &mut self.trigger_status[pin as usize];
 
-        trigger_status.triggered = false;
+        trigger_status.events.clear();
 
         // Interrupt already exists. We just need to change the trigger.
         if let Some(ref mut interrupt) = trigger_status.interrupt {
-            if interrupt.trigger != trigger {
+            if interrupt.trigger != trigger || interrupt.debounce != debounce {
                 // This requires a new event request, so the fd might change
                 self.poll.delete(interrupt.fd())?;
-                interrupt.set_trigger(trigger)?;
+                interrupt.set_trigger(trigger, debounce)?;
                 self.poll
                     .add(interrupt.fd(), u64::from(pin), EPOLLIN | EPOLLPRI)?;
             }
@@ -200,7 +560,8 @@ impl EventLoop {
         }
 
         // Register a new interrupt
-        let interrupt = Interrupt::new(self.cdev_fd, pin, trigger)?;
+        let interrupt =
+            Interrupt::new(self.cdev_fd, self.gpio_mem.clone(), pin, trigger, debounce)?;
         self.poll
             .add(interrupt.fd(), u64::from(pin), EPOLLIN | EPOLLPRI)?;
         trigger_status.interrupt = Some(interrupt);
@@ -211,7 +572,7 @@ impl EventLoop {
     pub fn clear_interrupt(&mut self, pin: u8) -> Result<()> {
         let trigger_status = &mut self.trigger_status[pin as usize];
 
-        trigger_status.triggered = false;
+        trigger_status.events.clear();
 
         if let Some(interrupt) = trigger_status.interrupt.take() {
             self.poll.delete(interrupt.fd())?;
@@ -221,16 +582,24 @@ impl EventLoop {
     }
 }
 
+// Asynchronous interrupt trigger, with either a dedicated thread, or a handler
+// registered with the gpiochip's shared dispatcher thread.
 #[derive(Debug)]
-pub struct AsyncInterrupt {
-    poll_thread: Option<thread::JoinHandle<Result<()>>>,
-    tx: EventFd,
+pub enum AsyncInterrupt {
+    Thread {
+        poll_thread: Option<thread::JoinHandle<Result<()>>>,
+        tx: EventFd,
+    },
+    Dispatcher {
+        dispatcher: Arc<Dispatcher>,
+        pin: u8,
+    },
 }
 
 impl AsyncInterrupt {
-    pub fn new<C>(fd: i32, pin: u8, trigger: Trigger, mut callback: C) -> Result<AsyncInterrupt>
+    pub fn new<C>(mut interrupt: InterruptFd, mut callback: C) -> Result<AsyncInterrupt>
     where
-        C: FnMut(Level) + Send + 'static,
+        C: FnMut(Event) + Send + 'static,
     {
         let tx = EventFd::new()?;
         let rx = tx.fd();
@@ -241,51 +610,71 @@ impl AsyncInterrupt {
             // rx becomes readable when the main thread calls notify()
             poll.add(rx, rx as u64, EPOLLERR | EPOLLET | EPOLLIN)?;
 
-            let mut interrupt = Interrupt::new(fd, pin, trigger)?;
-            poll.add(interrupt.fd(), interrupt.fd() as u64, EPOLLIN | EPOLLPRI)?;
+            let fd = interrupt.as_raw_fd();
+            poll.add(fd, fd as u64, EPOLLIN | EPOLLPRI)?;
 
             let mut events = [epoll_event { events: 0, u64: 0 }; 2];
             loop {
                 let num_events = poll.wait(&mut events, None)?;
                 if num_events > 0 {
                     for event in &events[0..num_events] {
-                        let fd = event.u64 as i32;
-                        if fd == rx {
+                        let event_fd = event.u64 as i32;
+                        if event_fd == rx {
                             return Ok(()); // The main thread asked us to stop
-                        } else if fd == interrupt.fd() {
-                            let level = interrupt.event()?.level();
-                            callback(level);
+                        } else if event_fd == fd {
+                            while let Some(event) = interrupt.read_event()? {
+                                callback(event);
+                            }
                         }
                     }
                 }
             }
         });
 
-        Ok(AsyncInterrupt {
+        Ok(AsyncInterrupt::Thread {
             poll_thread: Some(poll_thread),
             tx,
         })
     }
 
+    pub fn with_dispatcher<C>(
+        dispatcher: Arc<Dispatcher>,
+        interrupt: InterruptFd,
+        callback: C,
+    ) -> Result<AsyncInterrupt>
+    where
+        C: FnMut(Event) + Send + 'static,
+    {
+        let pin = interrupt.pin();
+        dispatcher.insert(interrupt, callback)?;
+
+        Ok(AsyncInterrupt::Dispatcher { dispatcher, pin })
+    }
+
     pub fn stop(&mut self) -> Result<()> {
-        self.tx.notify()?;
+        match self {
+            AsyncInterrupt::Thread { poll_thread, tx } => {
+                tx.notify()?;
+
+                if let Some(poll_thread) = poll_thread.take() {
+                    match poll_thread.join() {
+                        Ok(r) => return r,
+                        Err(_) => return Err(Error::ThreadPanic),
+                    }
+                }
 
-        if let Some(poll_thread) = self.poll_thread.take() {
-            match poll_thread.join() {
-                Ok(r) => return r,
-                Err(_) => return Err(Error::ThreadPanic),
+                Ok(())
             }
+            AsyncInterrupt::Dispatcher { dispatcher, pin } => dispatcher.remove(*pin),
         }
-
-        Ok(())
     }
 }
 
 impl Drop for AsyncInterrupt {
     fn drop(&mut self) {
-        // Don't wait for the poll thread to exit if the main thread is panicking,
-        // because we could potentially block indefinitely while unwinding if the
-        // poll thread is executing a callback that doesn't return.
+        // Don't wait for the poll thread to exit or the callback to return if the main
+        // thread is panicking, because we could potentially block indefinitely while
+        // unwinding if the poll thread is executing a callback that doesn't return.
         if !thread::panicking() {
             let _ = self.stop();
         }
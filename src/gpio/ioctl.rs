@@ -1,5 +1,5 @@
 mod v1;
 mod v2;
 
-pub use v1::*;
-// pub use v2::*;
+// pub use v1::*;
+pub use v2::*;
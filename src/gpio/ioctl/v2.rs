@@ -1,7 +1,7 @@
 #![allow(clippy::unnecessary_cast)]
 #![allow(dead_code)]
 
-use crate::gpio::{Error, Level, Result, Trigger};
+use crate::gpio::{Error, Event, Result, Trigger};
 use libc::{self, c_int, c_void, ENOENT};
 use std::ffi::CString;
 use std::fmt;
@@ -25,6 +25,10 @@ const DRIVER_NAME_BCM2712: &[u8] = b"pinctrl-rp1\0";
 // The first 27 offsets correspond to the 40-pin header
 pub const MAX_OFFSET: u32 = 27;
 
+// Maximum number of line events read at once. The kernel's default event buffer
+// holds 16 events per requested line.
+const LINE_EVENTS_MAX: usize = 16;
+
 const BITS_NR: u8 = 8;
 const BITS_TYPE: u8 = 8;
 const BITS_SIZE: u8 = 14;
@@ -83,30 +87,30 @@ const LINES_MAX: usize = 64;
 // Maximum number of configuration attributes.
 const LINE_NUM_ATTRS_MAX: usize = 10;
 
-const LINE_FLAG_USED: u64 = 0x01;
-const LINE_FLAG_ACTIVE_LOW: u64 = 0x02;
-const LINE_FLAG_INPUT: u64 = 0x04;
-const LINE_FLAG_OUTPUT: u64 = 0x08;
-const LINE_FLAG_EDGE_RISING: u64 = 0x10;
-const LINE_FLAG_EDGE_FALLING: u64 = 0x20;
-const LINE_FLAG_OPEN_DRAIN: u64 = 0x40;
-const LINE_FLAG_OPEN_SOURCE: u64 = 0x80;
-const LINE_FLAG_BIAS_PULL_UP: u64 = 0x1000;
-const LINE_FLAG_BIAS_PULL_DOWN: u64 = 0x2000;
-const LINE_FLAG_BIAS_DISABLED: u64 = 0x4000;
-const LINE_FLAG_EVENT_CLOCK_REALTIME: u64 = 0x8000;
-const LINE_FLAG_EVENT_CLOCK_HTE: u64 = 0x100000;
-
-const LINE_ATTR_ID_FLAGS: u32 = 1;
-const LINE_ATTR_ID_OUTPUT_VALUES: u32 = 2;
-const LINE_ATTR_ID_DEBOUNCE: u32 = 3;
+pub const LINE_FLAG_USED: u64 = 0x01;
+pub const LINE_FLAG_ACTIVE_LOW: u64 = 0x02;
+pub const LINE_FLAG_INPUT: u64 = 0x04;
+pub const LINE_FLAG_OUTPUT: u64 = 0x08;
+pub const LINE_FLAG_EDGE_RISING: u64 = 0x10;
+pub const LINE_FLAG_EDGE_FALLING: u64 = 0x20;
+pub const LINE_FLAG_OPEN_DRAIN: u64 = 0x40;
+pub const LINE_FLAG_OPEN_SOURCE: u64 = 0x80;
+pub const LINE_FLAG_BIAS_PULL_UP: u64 = 0x1000;
+pub const LINE_FLAG_BIAS_PULL_DOWN: u64 = 0x2000;
+pub const LINE_FLAG_BIAS_DISABLED: u64 = 0x4000;
+pub const LINE_FLAG_EVENT_CLOCK_REALTIME: u64 = 0x8000;
+pub const LINE_FLAG_EVENT_CLOCK_HTE: u64 = 0x100000;
+
+pub const LINE_ATTR_ID_FLAGS: u32 = 1;
+pub const LINE_ATTR_ID_OUTPUT_VALUES: u32 = 2;
+pub const LINE_ATTR_ID_DEBOUNCE: u32 = 3;
 
 const LINE_CHANGED_REQUESTED: u32 = 1;
 const LINE_CHANGED_RELEASED: u32 = 2;
 const LINE_CHANGED_CONFIG: u32 = 3;
 
-const LINE_EVENT_RISING_EDGE: u32 = 1;
-const LINE_EVENT_FALLING_EDGE: u32 = 2;
+pub const LINE_EVENT_RISING_EDGE: u32 = 1;
+pub const LINE_EVENT_FALLING_EDGE: u32 = 2;
 
 #[derive(Copy, Clone)]
 #[repr(C)]
@@ -140,6 +144,7 @@ impl fmt::Debug for ChipInfo {
     }
 }
 
+#[derive(PartialEq, Eq, Copy, Clone)]
 pub struct LineFlags {
     flags: u64,
 }
@@ -258,7 +263,7 @@ impl LineFlags {
     }
 
     pub fn bias_disabled(&self) -> bool {
-        (self.flags & LINE_FLAG_EDGE_FALLING) > 0
+        (self.flags & LINE_FLAG_BIAS_DISABLED) > 0
     }
 
     pub fn event_clock_realtime(&self) -> bool {
@@ -311,8 +316,8 @@ pub struct LineInfo {
 }
 
 impl LineInfo {
-    pub fn new(cdev_fd: c_int, offset: u32) -> Result<LineInfo> {
-        let mut line_info = LineInfo {
+    fn empty(offset: u32) -> LineInfo {
+        LineInfo {
             name: [0u8; NAME_BUFSIZE],
             consumer: [0u8; LABEL_BUFSIZE],
             offset,
@@ -320,13 +325,39 @@ impl LineInfo {
             flags: 0,
             attrs: [LineAttribute::new(); 10],
             padding: [0u32; 4],
-        };
+        }
+    }
+
+    pub fn new(cdev_fd: c_int, offset: u32) -> Result<LineInfo> {
+        let mut line_info = LineInfo::empty(offset);
 
         parse_retval!(unsafe { libc::ioctl(cdev_fd, GPIO_V2_GET_LINEINFO_IOCTL, &mut line_info) })?;
 
         Ok(line_info)
     }
 
+    // Retrieve the line info, and start watching the line for changes. Change
+    // events can be read from the gpiochip with LineInfoChanged::new.
+    pub fn watch(cdev_fd: c_int, offset: u32) -> Result<LineInfo> {
+        let mut line_info = LineInfo::empty(offset);
+
+        parse_retval!(unsafe {
+            libc::ioctl(cdev_fd, GPIO_V2_GET_LINEINFO_WATCH_IOCTL, &mut line_info)
+        })?;
+
+        Ok(line_info)
+    }
+
+    pub fn unwatch(cdev_fd: c_int, offset: u32) -> Result<()> {
+        let mut offset = offset;
+
+        parse_retval!(unsafe {
+            libc::ioctl(cdev_fd, GPIO_GET_LINEINFO_UNWATCH_IOCTL, &mut offset)
+        })?;
+
+        Ok(())
+    }
+
     pub fn flags(&self) -> LineFlags {
         LineFlags::new(self.flags)
     }
@@ -355,6 +386,59 @@ pub struct LineInfoChanged {
     pub padding: [u32; 5],
 }
 
+impl LineInfoChanged {
+    // Read a line info change event from a gpiochip with watched lines
+    pub fn new(cdev_fd: c_int) -> Result<LineInfoChanged> {
+        let mut line_info_changed = LineInfoChanged {
+            info: LineInfo::empty(0),
+            timestamp_ns: 0,
+            event_type: 0,
+            padding: [0u32; 5],
+        };
+
+        let bytes_read = parse_retval!(unsafe {
+            libc::read(
+                cdev_fd,
+                &mut line_info_changed as *mut LineInfoChanged as *mut c_void,
+                mem::size_of::<LineInfoChanged>(),
+            )
+        })?;
+
+        if bytes_read < mem::size_of::<LineInfoChanged>() as isize {
+            Err(io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "failed to fill whole buffer",
+            )
+            .into())
+        } else {
+            Ok(line_info_changed)
+        }
+    }
+
+    pub fn requested(&self) -> bool {
+        self.event_type == LINE_CHANGED_REQUESTED
+    }
+
+    pub fn released(&self) -> bool {
+        self.event_type == LINE_CHANGED_RELEASED
+    }
+
+    pub fn config(&self) -> bool {
+        self.event_type == LINE_CHANGED_CONFIG
+    }
+}
+
+impl fmt::Debug for LineInfoChanged {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LineInfoChanged")
+            .field("info", &self.info)
+            .field("timestamp_ns", &self.timestamp_ns)
+            .field("event_type", &self.event_type)
+            .field("padding", &self.padding)
+            .finish()
+    }
+}
+
 #[derive(Copy, Clone, Default)]
 #[repr(C)]
 pub struct LineConfigAttribute {
@@ -380,6 +464,57 @@ pub struct LineConfig {
     pub attrs: [LineConfigAttribute; LINE_NUM_ATTRS_MAX],
 }
 
+impl LineConfig {
+    pub fn new(flags: u64) -> LineConfig {
+        LineConfig {
+            flags,
+            ..Default::default()
+        }
+    }
+
+    // Input line with edge detection enabled for the specified trigger, and an
+    // optional debounce period. If the GPIO driver doesn't support hardware
+    // debouncing, the kernel falls back to a software implementation.
+    pub fn with_trigger(trigger: Trigger, debounce: Option<Duration>) -> LineConfig {
+        let edge_flags = match trigger {
+            Trigger::Disabled => 0,
+            Trigger::RisingEdge => LINE_FLAG_EDGE_RISING,
+            Trigger::FallingEdge => LINE_FLAG_EDGE_FALLING,
+            Trigger::Both => LINE_FLAG_EDGE_RISING | LINE_FLAG_EDGE_FALLING,
+        };
+
+        let mut line_config = LineConfig::new(LINE_FLAG_INPUT | edge_flags);
+
+        if let Some(debounce) = debounce {
+            // The debounce period is specified in microseconds
+            let debounce_us = debounce.as_micros().min(u128::from(u32::MAX)) as u64;
+
+            line_config.add_attribute(LINE_ATTR_ID_DEBOUNCE, debounce_us, 0x01);
+        }
+
+        line_config
+    }
+
+    // Add a configuration attribute that applies to the lines selected by mask
+    pub fn add_attribute(&mut self, id: u32, values: u64, mask: u64) {
+        let idx = self.num_attrs as usize;
+        if idx >= LINE_NUM_ATTRS_MAX {
+            return;
+        }
+
+        self.attrs[idx] = LineConfigAttribute {
+            attr: LineAttribute {
+                id,
+                padding: 0,
+                values,
+            },
+            mask,
+        };
+
+        self.num_attrs += 1;
+    }
+}
+
 impl fmt::Debug for LineConfig {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("LineConfig")
@@ -391,7 +526,7 @@ impl fmt::Debug for LineConfig {
     }
 }
 
-#[derive(Clone)]
+// LineRequest owns its fd, which is closed on drop. Use try_clone to duplicate it.
 #[repr(C)]
 pub struct LineRequest {
     pub offsets: [u32; LINES_MAX],
@@ -433,14 +568,31 @@ impl fmt::Debug for LineRequest {
 
 impl LineRequest {
     pub fn new(cdev_fd: c_int, offset: u32) -> Result<LineRequest> {
+        LineRequest::with_config(cdev_fd, offset, LineConfig::default())
+    }
+
+    pub fn with_config(cdev_fd: c_int, offset: u32, config: LineConfig) -> Result<LineRequest> {
         let mut line_request = LineRequest::default();
         line_request.offsets[0] = offset;
         line_request.num_lines = 1;
+        line_request.config = config;
 
-        // Set consumer label, so other processes know we're monitoring this event
+        // Set consumer label, so other processes know we're using this line
         line_request.consumer[0..CONSUMER_LABEL.len()].copy_from_slice(CONSUMER_LABEL.as_bytes());
 
-        parse_retval!(unsafe { libc::ioctl(cdev_fd, GPIO_V2_GET_LINE_IOCTL, &mut line_request) })?;
+        match parse_retval!(unsafe {
+            libc::ioctl(cdev_fd, GPIO_V2_GET_LINE_IOCTL, &mut line_request)
+        }) {
+            Err(ref e) if e.raw_os_error() == Some(libc::EBUSY) => {
+                // The line has already been requested by another process or a kernel driver
+                let consumer = LineInfo::new(cdev_fd, offset)
+                    .map(|line_info| cbuf_to_string(&line_info.consumer))
+                    .unwrap_or_default();
+
+                return Err(Error::PinClaimed(offset as u8, consumer));
+            }
+            result => result?,
+        };
 
         // If the fd is zero or negative, an error occurred
         if line_request.fd <= 0 {
@@ -460,6 +612,57 @@ impl LineRequest {
         Ok(line_values)
     }
 
+    pub fn set_levels(&self, bits: u64, mask: u64) -> Result<()> {
+        let mut line_values = LineValues::new(bits, mask);
+
+        parse_retval!(unsafe {
+            libc::ioctl(self.fd, GPIO_V2_LINE_SET_VALUES_IOCTL, &mut line_values)
+        })?;
+
+        Ok(())
+    }
+
+    pub fn set_config(&mut self, config: LineConfig) -> Result<()> {
+        let mut config = config;
+
+        parse_retval!(unsafe { libc::ioctl(self.fd, GPIO_V2_LINE_SET_CONFIG_IOCTL, &mut config) })?;
+
+        self.config = config;
+
+        Ok(())
+    }
+
+    // Duplicate the request fd, so the same line request can be shared with
+    // an interrupt handler.
+    pub fn try_clone(&self) -> Result<LineRequest> {
+        let fd = parse_retval!(unsafe { libc::dup(self.fd) })?;
+
+        Ok(LineRequest {
+            offsets: self.offsets,
+            consumer: self.consumer,
+            config: self.config,
+            num_lines: self.num_lines,
+            event_buffer_size: self.event_buffer_size,
+            padding: self.padding,
+            fd,
+        })
+    }
+
+    // Discard any edge events that are still queued
+    pub fn clear_events(&self) -> Result<()> {
+        let mut poll_fd = libc::pollfd {
+            fd: self.fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+
+        while parse_retval!(unsafe { libc::poll(&mut poll_fd, 1, 0) })? > 0 {
+            LineEvent::new(self.fd)?;
+        }
+
+        Ok(())
+    }
+
     pub fn close(&mut self) {
         if self.fd > 0 {
             unsafe {
@@ -499,7 +702,7 @@ impl LineValues {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, Default)]
 #[repr(C)]
 pub struct LineEvent {
     pub timestamp_ns: u64,
@@ -510,20 +713,94 @@ pub struct LineEvent {
     pub padding: [u32; 6],
 }
 
+impl LineEvent {
+    fn new(request_fd: c_int) -> Result<LineEvent> {
+        let mut line_event = LineEvent::default();
+
+        let bytes_read = parse_retval!(unsafe {
+            libc::read(
+                request_fd,
+                &mut line_event as *mut LineEvent as *mut c_void,
+                mem::size_of::<LineEvent>(),
+            )
+        })?;
+
+        if bytes_read < mem::size_of::<LineEvent>() as isize {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "failed to fill whole buffer",
+            )
+            .into())
+        } else {
+            Ok(line_event)
+        }
+    }
+}
+
+impl From<LineEvent> for Event {
+    fn from(line_event: LineEvent) -> Event {
+        Event {
+            trigger: match line_event.id {
+                LINE_EVENT_RISING_EDGE => Trigger::RisingEdge,
+                LINE_EVENT_FALLING_EDGE => Trigger::FallingEdge,
+                _ => unreachable!(),
+            },
+            timestamp: Duration::from_nanos(line_event.timestamp_ns),
+            seqno: line_event.seqno,
+            line_seqno: line_event.line_seqno,
+            missed: 0,
+        }
+    }
+}
+
+// Read interrupt event from a line request
+pub fn get_line_event(request_fd: c_int) -> Result<Event> {
+    Ok(LineEvent::new(request_fd)?.into())
+}
+
+// Read all queued interrupt events from a line request, up to LINE_EVENTS_MAX
+// events per call. Blocks if no events are queued.
+pub fn get_line_events(request_fd: c_int, events: &mut Vec<Event>) -> Result<()> {
+    let mut line_events = [LineEvent::default(); LINE_EVENTS_MAX];
+
+    let bytes_read = parse_retval!(unsafe {
+        libc::read(
+            request_fd,
+            line_events.as_mut_ptr() as *mut c_void,
+            mem::size_of_val(&line_events),
+        )
+    })? as usize;
+
+    // The kernel only returns whole events
+    let num_events = bytes_read / mem::size_of::<LineEvent>();
+    if num_events == 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "failed to fill whole buffer",
+        )
+        .into());
+    }
+
+    events.extend(line_events[..num_events].iter().map(|&e| Event::from(e)));
+
+    Ok(())
+}
+
+// Open the gpiochip device at the specified path
+fn open_gpiochip_path(path: &str) -> Result<File> {
+    match OpenOptions::new().read(true).write(true).open(path) {
+        Ok(file) => Ok(file),
+        Err(ref e) if e.kind() == io::ErrorKind::PermissionDenied => {
+            Err(Error::PermissionDenied(path.to_string()))
+        }
+        Err(e) => Err(Error::from(e)),
+    }
+}
+
 // Find the correct gpiochip device based on its label
 pub fn find_gpiochip() -> Result<File> {
     for id in 0..=255 {
-        let gpiochip = match OpenOptions::new()
-            .read(true)
-            .write(true)
-            .open(format!("{}{}", PATH_GPIOCHIP, id))
-        {
-            Ok(file) => file,
-            Err(ref e) if e.kind() == io::ErrorKind::PermissionDenied => {
-                return Err(Error::PermissionDenied(format!("{}{}", PATH_GPIOCHIP, id)));
-            }
-            Err(e) => return Err(Error::from(e)),
-        };
+        let gpiochip = open_gpiochip_path(&format!("{}{}", PATH_GPIOCHIP, id))?;
 
         let chip_info = ChipInfo::new(gpiochip.as_raw_fd())?;
         if chip_info.label[0..DRIVER_NAME.len()] == DRIVER_NAME[..]
@@ -538,6 +815,37 @@ pub fn find_gpiochip() -> Result<File> {
     Err(Error::Io(io::Error::from_raw_os_error(ENOENT)))
 }
 
+// Open a new file description for an already opened gpiochip. Line info watches
+// are tracked per file description, so a duplicated fd can't be used to
+// watch lines independently.
+pub fn reopen_gpiochip(cdev: &File) -> Result<File> {
+    open_gpiochip_path(&format!("/proc/self/fd/{}", cdev.as_raw_fd()))
+}
+
+// Open a gpiochip by its path, name or label
+pub fn open_gpiochip(chip: &str) -> Result<File> {
+    if chip.starts_with('/') {
+        return open_gpiochip_path(chip);
+    }
+
+    for id in 0..=255 {
+        // gpiochip numbering isn't necessarily contiguous
+        let gpiochip = match open_gpiochip_path(&format!("{}{}", PATH_GPIOCHIP, id)) {
+            Ok(file) => file,
+            Err(Error::Io(ref e)) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+
+        let chip_info = ChipInfo::new(gpiochip.as_raw_fd())?;
+        if cbuf_to_string(&chip_info.name) == chip || cbuf_to_string(&chip_info.label) == chip {
+            return Ok(gpiochip);
+        }
+    }
+
+    // File Not Found I/O error
+    Err(Error::Io(io::Error::from_raw_os_error(ENOENT)))
+}
+
 // Create a CString from a C-style NUL-terminated char array. This workaround
 // is needed for fixed-length buffers that fill the remaining bytes with NULs,
 // because CString::new() interprets those as a NUL in the middle of the byte
@@ -665,42 +973,19 @@ impl EventData {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-pub struct Event {
-    trigger: Trigger,
-    timestamp: Duration,
-}
-
-impl Event {
-    fn from_event_data(event_data: EventData) -> Event {
-        Event {
-            trigger: match event_data.id {
-                EVENT_TYPE_RISING_EDGE => Trigger::RisingEdge,
-                EVENT_TYPE_FALLING_EDGE => Trigger::FallingEdge,
-                _ => unreachable!(),
-            },
-            timestamp: Duration::from_nanos(event_data.timestamp),
-        }
-    }
-
-    pub fn trigger(&self) -> Trigger {
-        self.trigger
-    }
-
-    pub fn level(&self) -> Level {
-        match self.trigger {
-            Trigger::RisingEdge => Level::High,
-            Trigger::FallingEdge => Level::Low,
-            _ => {
-                // SAFETY: `Event` can only be constructed with either `RisingEdge` or `FallingEdge`.
-                unsafe { std::hint::unreachable_unchecked() }
-            }
-        }
-    }
-}
-
-// Read interrupt event
+// Read interrupt event. The v1 uAPI doesn't provide sequence numbers.
 pub fn get_event(event_fd: c_int) -> Result<Event> {
     let event_data = EventData::new(event_fd)?;
-    Ok(Event::from_event_data(event_data))
+
+    Ok(Event {
+        trigger: match event_data.id {
+            EVENT_TYPE_RISING_EDGE => Trigger::RisingEdge,
+            EVENT_TYPE_FALLING_EDGE => Trigger::FallingEdge,
+            _ => unreachable!(),
+        },
+        timestamp: Duration::from_nanos(event_data.timestamp),
+        seqno: 0,
+        line_seqno: 0,
+        missed: 0,
+    })
 }
@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use crate::gpio::ioctl;
+use crate::gpio::{Bias, Mode, Trigger};
+
+/// GPIO line information reported by the kernel.
+///
+/// `LineInfo` contains a snapshot of a `gpiochip` line's configuration at the time it
+/// was retrieved through [`Gpio::line_info`] or [`Gpio::line_infos`].
+///
+/// [`Gpio::line_info`]: struct.Gpio.html#method.line_info
+/// [`Gpio::line_infos`]: struct.Gpio.html#method.line_infos
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LineInfo {
+    pin: u8,
+    name: String,
+    consumer: String,
+    flags: ioctl::LineFlags,
+    debounce: Option<Duration>,
+}
+
+impl LineInfo {
+    pub(crate) fn new(pin: u8, line_info: &ioctl::LineInfo) -> LineInfo {
+        let debounce = line_info
+            .attrs
+            .iter()
+            .take(line_info.num_attrs as usize)
+            .find(|attr| attr.id == ioctl::LINE_ATTR_ID_DEBOUNCE)
+            .map(|attr| Duration::from_micros(attr.values & u64::from(u32::MAX)));
+
+        LineInfo {
+            pin,
+            name: ioctl::cbuf_to_string(&line_info.name),
+            consumer: ioctl::cbuf_to_string(&line_info.consumer),
+            flags: line_info.flags(),
+            debounce,
+        }
+    }
+
+    /// Returns the GPIO pin number.
+    pub fn pin(&self) -> u8 {
+        self.pin
+    }
+
+    /// Returns the line's name, as specified in the device tree.
+    ///
+    /// On the Raspberry Pi, line names usually describe the pin's function on the
+    /// board, for instance `GPIO17`, `ID_SDA` or `PWR_LED_OFF`. Returns an empty string
+    /// if the line doesn't have a name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the consumer label of the process or kernel driver using the line.
+    ///
+    /// Returns an empty string if the line isn't in use, or if the current
+    /// user didn't provide a label.
+    pub fn consumer(&self) -> &str {
+        &self.consumer
+    }
+
+    /// Returns `true` if the line is in use.
+    ///
+    /// A line is in use if it has been requested by a process, including the current
+    /// process, or if it's claimed by a kernel driver, for instance when a device tree
+    /// overlay assigned the pin to a peripheral.
+    pub fn is_used(&self) -> bool {
+        self.flags.used()
+    }
+
+    /// Returns the line's direction.
+    ///
+    /// Returns either [`Mode::Input`] or [`Mode::Output`]. Alternate functions
+    /// aren't reported by the kernel.
+    ///
+    /// [`Mode::Input`]: enum.Mode.html#variant.Input
+    /// [`Mode::Output`]: enum.Mode.html#variant.Output
+    pub fn mode(&self) -> Mode {
+        if self.flags.output() {
+            Mode::Output
+        } else {
+            Mode::Input
+        }
+    }
+
+    /// Returns the line's bias.
+    ///
+    /// The kernel only reports the bias if it was configured through the `gpiochip`.
+    /// Returns `None` otherwise.
+    pub fn bias(&self) -> Option<Bias> {
+        if self.flags.bias_pull_up() {
+            Some(Bias::PullUp)
+        } else if self.flags.bias_pull_down() {
+            Some(Bias::PullDown)
+        } else if self.flags.bias_disabled() {
+            Some(Bias::Off)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the line is configured as an open-drain output.
+    pub fn is_open_drain(&self) -> bool {
+        self.flags.open_drain()
+    }
+
+    /// Returns `true` if the line is configured as an open-source output.
+    pub fn is_open_source(&self) -> bool {
+        self.flags.open_source()
+    }
+
+    /// Returns `true` if the line's logic level is inverted.
+    pub fn is_active_low(&self) -> bool {
+        self.flags.active_low()
+    }
+
+    /// Returns the edges the line is monitoring for interrupts.
+    pub fn trigger(&self) -> Trigger {
+        match (self.flags.edge_rising(), self.flags.edge_falling()) {
+            (true, true) => Trigger::Both,
+            (true, false) => Trigger::RisingEdge,
+            (false, true) => Trigger::FallingEdge,
+            (false, false) => Trigger::Disabled,
+        }
+    }
+
+    /// Returns the debounce period configured for the line, if any.
+    pub fn debounce(&self) -> Option<Duration> {
+        self.debounce
+    }
+}
@@ -1,10 +1,16 @@
-use std::os::unix::io::AsRawFd;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::Duration;
 
+use super::gpiomem::GpioRegisters;
 use super::soft_pwm::SoftPwm;
-use crate::gpio::{interrupt::AsyncInterrupt, Bias, GpioState, Level, Mode, Result, Trigger};
+#[cfg(feature = "async")]
+use crate::gpio::InterruptStream;
+use crate::gpio::{
+    function_name,
+    interrupt::{AsyncInterrupt, Interrupt, InterruptFd},
+    Bias, Drive, DriveStrength, Error, Event, GpioState, Level, Mode, Result, SlewRate, Trigger,
+};
 
 const NANOS_PER_SEC: f64 = 1_000_000_000.0;
 
@@ -49,8 +55,11 @@ macro_rules! impl_input {
 macro_rules! impl_output {
     () => {
         /// Sets the pin's output state.
+        ///
+        /// Changing the output state can only fail when using the `Cdev` backend, in which
+        /// case the error reported by the `gpiochip` is returned.
         #[inline]
-        pub fn write(&mut self, level: Level) {
+        pub fn write(&mut self, level: Level) -> Result<()> {
             self.pin.write(level)
         }
 
@@ -58,7 +67,7 @@ macro_rules! impl_output {
         ///
         /// [`Low`]: enum.Level.html#variant.Low
         #[inline]
-        pub fn set_low(&mut self) {
+        pub fn set_low(&mut self) -> Result<()> {
             self.pin.set_low()
         }
 
@@ -66,7 +75,7 @@ macro_rules! impl_output {
         ///
         /// [`High`]: enum.Level.html#variant.High
         #[inline]
-        pub fn set_high(&mut self) {
+        pub fn set_high(&mut self) -> Result<()> {
             self.pin.set_high()
         }
 
@@ -75,11 +84,11 @@ macro_rules! impl_output {
         /// [`Low`]: enum.Level.html#variant.Low
         /// [`High`]: enum.Level.html#variant.High
         #[inline]
-        pub fn toggle(&mut self) {
+        pub fn toggle(&mut self) -> Result<()> {
             if self.pin.read() == Level::Low {
-                self.set_high();
+                self.set_high()
             } else {
-                self.set_low();
+                self.set_low()
             }
         }
 
@@ -100,12 +109,19 @@ macro_rules! impl_output {
         /// [`Pwm`]: ../pwm/struct.Pwm.html
         /// [here]: index.html#software-based-pwm
         pub fn set_pwm(&mut self, period: Duration, pulse_width: Duration) -> Result<()> {
+            // The PWM thread directly toggles the output level, which would actively
+            // drive an emulated open-drain/open-source output.
+            if self.pin.is_drive_emulated() {
+                return Err(Error::NotSupported);
+            }
+
             if let Some(ref mut soft_pwm) = self.soft_pwm {
                 soft_pwm.reconfigure(period, pulse_width);
             } else {
                 self.soft_pwm = Some(SoftPwm::new(
                     self.pin.pin,
                     self.pin.gpio_state.clone(),
+                    self.pin.active_low_emulated,
                     period,
                     pulse_width,
                 ));
@@ -158,7 +174,8 @@ macro_rules! impl_output {
         /// Stops a previously configured software-based PWM signal.
         ///
         /// The thread responsible for emulating the PWM signal is stopped at the end
-        /// of the current cycle.
+        /// of the current cycle. If the thread already stopped because the output state
+        /// couldn't be changed, `clear_pwm` returns that error.
         pub fn clear_pwm(&mut self) -> Result<()> {
             if let Some(mut soft_pwm) = self.soft_pwm.take() {
                 soft_pwm.stop()?;
@@ -169,6 +186,384 @@ macro_rules! impl_output {
     };
 }
 
+macro_rules! impl_pad {
+    () => {
+        /// Returns the pin's output drive strength.
+        ///
+        /// Pad control is currently only supported on the Raspberry Pi 5. On other models,
+        /// `drive_strength` returns `Err(`[`Error::NotSupported`]`)`.
+        ///
+        /// [`Error::NotSupported`]: enum.Error.html#variant.NotSupported
+        #[inline]
+        pub fn drive_strength(&self) -> Result<DriveStrength> {
+            self.pin.gpio_state.gpio_mem.drive_strength(self.pin.pin)
+        }
+
+        /// Sets the pin's output drive strength.
+        ///
+        /// Pad control is currently only supported on the Raspberry Pi 5. On other models,
+        /// `set_drive_strength` returns `Err(`[`Error::NotSupported`]`)`.
+        ///
+        /// The pad configuration isn't reset when the pin goes out of scope.
+        ///
+        /// [`Error::NotSupported`]: enum.Error.html#variant.NotSupported
+        #[inline]
+        pub fn set_drive_strength(&mut self, drive_strength: DriveStrength) -> Result<()> {
+            self.pin
+                .gpio_state
+                .gpio_mem
+                .set_drive_strength(self.pin.pin, drive_strength)
+        }
+
+        /// Returns the pin's output slew rate.
+        ///
+        /// Pad control is currently only supported on the Raspberry Pi 5. On other models,
+        /// `slew_rate` returns `Err(`[`Error::NotSupported`]`)`.
+        ///
+        /// [`Error::NotSupported`]: enum.Error.html#variant.NotSupported
+        #[inline]
+        pub fn slew_rate(&self) -> Result<SlewRate> {
+            self.pin.gpio_state.gpio_mem.slew_rate(self.pin.pin)
+        }
+
+        /// Sets the pin's output slew rate.
+        ///
+        /// Pad control is currently only supported on the Raspberry Pi 5. On other models,
+        /// `set_slew_rate` returns `Err(`[`Error::NotSupported`]`)`.
+        ///
+        /// The pad configuration isn't reset when the pin goes out of scope.
+        ///
+        /// [`Error::NotSupported`]: enum.Error.html#variant.NotSupported
+        #[inline]
+        pub fn set_slew_rate(&mut self, slew_rate: SlewRate) -> Result<()> {
+            self.pin
+                .gpio_state
+                .gpio_mem
+                .set_slew_rate(self.pin.pin, slew_rate)
+        }
+
+        /// Returns `true` if the pin's input Schmitt trigger is enabled.
+        ///
+        /// Pad control is currently only supported on the Raspberry Pi 5. On other models,
+        /// `schmitt_trigger` returns `Err(`[`Error::NotSupported`]`)`.
+        ///
+        /// [`Error::NotSupported`]: enum.Error.html#variant.NotSupported
+        #[inline]
+        pub fn schmitt_trigger(&self) -> Result<bool> {
+            self.pin.gpio_state.gpio_mem.schmitt_trigger(self.pin.pin)
+        }
+
+        /// Enables or disables the pin's input Schmitt trigger.
+        ///
+        /// The Schmitt trigger adds hysteresis to the input, which prevents slow or noisy
+        /// signals from causing the logic level to rapidly toggle.
+        ///
+        /// Pad control is currently only supported on the Raspberry Pi 5. On other models,
+        /// `set_schmitt_trigger` returns `Err(`[`Error::NotSupported`]`)`.
+        ///
+        /// The pad configuration isn't reset when the pin goes out of scope.
+        ///
+        /// [`Error::NotSupported`]: enum.Error.html#variant.NotSupported
+        #[inline]
+        pub fn set_schmitt_trigger(&mut self, enabled: bool) -> Result<()> {
+            self.pin
+                .gpio_state
+                .gpio_mem
+                .set_schmitt_trigger(self.pin.pin, enabled)
+        }
+
+        /// Returns `true` if the pin's input buffer is enabled.
+        ///
+        /// Pad control is currently only supported on the Raspberry Pi 5. On other models,
+        /// `input_enable` returns `Err(`[`Error::NotSupported`]`)`.
+        ///
+        /// [`Error::NotSupported`]: enum.Error.html#variant.NotSupported
+        #[inline]
+        pub fn input_enable(&self) -> Result<bool> {
+            self.pin.gpio_state.gpio_mem.input_enable(self.pin.pin)
+        }
+
+        /// Enables or disables the pin's input buffer.
+        ///
+        /// While the input buffer is disabled, the pin's logic level always reads as [`Low`].
+        /// The input buffer is automatically enabled whenever the pin's mode changes.
+        ///
+        /// Pad control is currently only supported on the Raspberry Pi 5. On other models,
+        /// `set_input_enable` returns `Err(`[`Error::NotSupported`]`)`.
+        ///
+        /// [`Low`]: enum.Level.html#variant.Low
+        /// [`Error::NotSupported`]: enum.Error.html#variant.NotSupported
+        #[inline]
+        pub fn set_input_enable(&mut self, enabled: bool) -> Result<()> {
+            self.pin
+                .gpio_state
+                .gpio_mem
+                .set_input_enable(self.pin.pin, enabled)
+        }
+    };
+}
+
+macro_rules! impl_active_low {
+    () => {
+        /// Returns `true` if the pin is configured as active-low.
+        #[inline]
+        pub fn is_active_low(&self) -> bool {
+            self.pin.active_low
+        }
+
+        /// Configures the pin as active-low.
+        ///
+        /// When `active_low` is set to `true`, the pin's logic level is inverted. [`High`]
+        /// corresponds to a physical low voltage and [`Low`] to a physical high voltage,
+        /// and rising and falling edges are swapped. This applies to all methods that read
+        /// or change the logic level, interrupt triggers, and the `embedded-hal` trait
+        /// implementations.
+        ///
+        /// If the [`Backend`] supports it, the pin is inverted through the `gpiochip`
+        /// character device. Otherwise, the pin is inverted in software. In that case,
+        /// any interrupt triggers that were configured before changing `active_low`
+        /// need to be reconfigured. If the `gpiochip` fails to reconfigure the pin, an
+        /// error is returned and the pin's configuration remains unchanged.
+        ///
+        /// Changing `active_low` doesn't change the pin's physical output state.
+        ///
+        /// By default, `active_low` is set to `false`. The pin is reset to active-high
+        /// when it goes out of scope if [`reset_on_drop`] is set to `true` (default).
+        ///
+        /// [`High`]: enum.Level.html#variant.High
+        /// [`Low`]: enum.Level.html#variant.Low
+        /// [`Backend`]: enum.Backend.html
+        /// [`reset_on_drop`]: #method.set_reset_on_drop
+        #[inline]
+        pub fn set_active_low(&mut self, active_low: bool) -> Result<()> {
+            self.pin.set_active_low(active_low)
+        }
+    };
+}
+
+macro_rules! impl_interrupt {
+    () => {
+        /// Configures a synchronous interrupt trigger.
+        ///
+        /// After configuring a synchronous interrupt trigger, call [`poll_interrupt`] or
+        /// [`Gpio::poll_interrupts`] to block while waiting for a trigger event.
+        ///
+        /// Any previously configured (a)synchronous interrupt triggers will be cleared.
+        ///
+        /// `debounce` optionally specifies a debounce period. Any edges that arrive within the
+        /// debounce period are ignored. On kernels that support the `gpiochip` v2 uAPI,
+        /// debouncing is handled by the kernel. On older kernels, RPPAL drops any edges that arrive
+        /// within the debounce period after the last reported event. Set `debounce` to `None` to
+        /// disable debouncing.
+        ///
+        /// Returns [`Error::UnsupportedMode`] if edge detection isn't available in the pin's
+        /// current mode.
+        ///
+        /// [`poll_interrupt`]: #method.poll_interrupt
+        /// [`Gpio::poll_interrupts`]: struct.Gpio.html#method.poll_interrupts
+        /// [`Error::UnsupportedMode`]: enum.Error.html#variant.UnsupportedMode
+        pub fn set_interrupt(
+            &mut self,
+            trigger: Trigger,
+            debounce: Option<Duration>,
+        ) -> Result<()> {
+            self.clear_async_interrupt()?;
+
+            let _restore_mode = RestoreMode::new(&self.pin, self.interrupt_mode()?);
+
+            // Each pin can only be configured for a single trigger type
+            (*self.pin.gpio_state.sync_interrupts.lock().unwrap()).set_interrupt(
+                self.pin(),
+                self.pin.physical_trigger(trigger),
+                debounce,
+            )?;
+            self.pin.sync_interrupt = true;
+
+            Ok(())
+        }
+
+        /// Removes a previously configured synchronous interrupt trigger.
+        pub fn clear_interrupt(&mut self) -> Result<()> {
+            (*self.pin.gpio_state.sync_interrupts.lock().unwrap()).clear_interrupt(self.pin())?;
+            self.pin.sync_interrupt = false;
+
+            Ok(())
+        }
+
+        /// Blocks until an interrupt is triggered on the pin, or a timeout occurs.
+        ///
+        /// This only works after the pin has been configured for synchronous interrupts using
+        /// [`set_interrupt`]. Asynchronous interrupt triggers are automatically polled on a separate thread.
+        ///
+        /// Calling `poll_interrupt` blocks any other calls to `poll_interrupt` (including on other pins) or
+        /// [`Gpio::poll_interrupts`] until it returns. If you need to poll multiple pins simultaneously, use
+        /// [`Gpio::poll_interrupts`] to block while waiting for any of the interrupts to trigger, or switch to
+        /// using asynchronous interrupts with [`set_async_interrupt`].
+        ///
+        /// Setting `reset` to `false` returns any cached interrupt trigger events if available. Setting `reset` to `true`
+        /// clears all cached events before polling for new events.
+        ///
+        /// The `timeout` duration indicates how long the call will block while waiting
+        /// for interrupt trigger events, after which an `Ok(None))` is returned.
+        /// `timeout` can be set to `None` to wait indefinitely.
+        ///
+        /// When an interrupt event is triggered, `poll_interrupt` returns `Ok(Some(`[`Event`]`))`
+        /// containing the trigger edge and the kernel timestamp.
+        ///
+        /// [`set_interrupt`]: #method.set_interrupt
+        /// [`Gpio::poll_interrupts`]: struct.Gpio.html#method.poll_interrupts
+        /// [`set_async_interrupt`]: #method.set_async_interrupt
+        /// [`Event`]: struct.Event.html
+        pub fn poll_interrupt(
+            &mut self,
+            reset: bool,
+            timeout: Option<Duration>,
+        ) -> Result<Option<Event>> {
+            let opt = (*self.pin.gpio_state.sync_interrupts.lock().unwrap()).poll(
+                &[self.pin()],
+                reset,
+                timeout,
+            )?;
+
+            Ok(opt.map(|(_, event)| self.pin.logical_event(event)))
+        }
+
+        /// Configures an asynchronous interrupt trigger, which executes the callback on a
+        /// separate thread when the interrupt is triggered.
+        ///
+        /// The callback closure or function pointer is called with a single [`Event`] argument.
+        ///
+        /// Any previously configured (a)synchronous interrupt triggers for this pin are cleared
+        /// when `set_async_interrupt` is called, or when the pin goes out of scope.
+        ///
+        /// `debounce` optionally specifies a debounce period. More information can be found
+        /// in the documentation for [`set_interrupt`].
+        ///
+        /// By default, each asynchronous interrupt trigger uses its own thread. If the shared
+        /// interrupt dispatcher has been enabled with [`Gpio::set_interrupt_dispatcher`], the
+        /// callback is executed on the dispatcher thread instead, and calling
+        /// `set_async_interrupt` again to change the trigger doesn't spawn a new thread.
+        ///
+        /// [`set_interrupt`]: #method.set_interrupt
+        /// [`Event`]: struct.Event.html
+        /// [`Gpio::set_interrupt_dispatcher`]: struct.Gpio.html#method.set_interrupt_dispatcher
+        pub fn set_async_interrupt<C>(
+            &mut self,
+            trigger: Trigger,
+            debounce: Option<Duration>,
+            callback: C,
+        ) -> Result<()>
+        where
+            C: FnMut(Event) + Send + 'static,
+        {
+            self.clear_interrupt()?;
+            self.clear_async_interrupt()?;
+
+            if self
+                .pin
+                .gpio_state
+                .interrupt_dispatcher
+                .load(Ordering::SeqCst)
+            {
+                let dispatcher = self.pin.gpio_state.dispatcher()?;
+                let interrupt = self.interrupt_fd(trigger, debounce)?;
+                self.async_interrupt = Some(AsyncInterrupt::with_dispatcher(
+                    dispatcher, interrupt, callback,
+                )?);
+
+                return Ok(());
+            }
+
+            let interrupt = self.interrupt_fd(trigger, debounce)?;
+            self.async_interrupt = Some(AsyncInterrupt::new(interrupt, callback)?);
+
+            Ok(())
+        }
+
+        /// Removes a previously configured asynchronous interrupt trigger.
+        pub fn clear_async_interrupt(&mut self) -> Result<()> {
+            if let Some(mut interrupt) = self.async_interrupt.take() {
+                interrupt.stop()?;
+            }
+
+            Ok(())
+        }
+
+        /// Configures an interrupt trigger, and returns an [`InterruptFd`] that can be
+        /// registered with an external event loop.
+        ///
+        /// Any previously configured (a)synchronous interrupt triggers for this pin are cleared.
+        /// The interrupt trigger remains configured until the `InterruptFd` goes out of scope.
+        /// While the `InterruptFd` exists, configuring a different interrupt trigger for this pin
+        /// returns an error.
+        ///
+        /// `debounce` optionally specifies a debounce period. More information can be found
+        /// in the documentation for [`set_interrupt`].
+        ///
+        /// [`InterruptFd`]: struct.InterruptFd.html
+        /// [`set_interrupt`]: #method.set_interrupt
+        pub fn interrupt_fd(
+            &mut self,
+            trigger: Trigger,
+            debounce: Option<Duration>,
+        ) -> Result<InterruptFd> {
+            self.clear_interrupt()?;
+            self.clear_async_interrupt()?;
+
+            let _restore_mode = RestoreMode::new(&self.pin, self.interrupt_mode()?);
+
+            let interrupt = Interrupt::new(
+                self.pin.gpio_state.cdev_fd(),
+                self.pin.gpio_state.gpio_mem.clone(),
+                self.pin(),
+                self.pin.physical_trigger(trigger),
+                debounce,
+            )?;
+
+            Ok(InterruptFd::new(interrupt, self.pin.active_low_emulated))
+        }
+
+        /// Configures an interrupt trigger, and returns an [`InterruptStream`] that
+        /// asynchronously yields the trigger events.
+        ///
+        /// Any previously configured (a)synchronous interrupt triggers for this pin are cleared.
+        /// The interrupt trigger remains configured until the `InterruptStream` goes out of scope.
+        ///
+        /// `debounce` optionally specifies a debounce period. More information can be found
+        /// in the documentation for [`set_interrupt`].
+        ///
+        /// This method is only available when the `async` feature is enabled.
+        ///
+        /// [`InterruptStream`]: struct.InterruptStream.html
+        /// [`set_interrupt`]: #method.set_interrupt
+        #[cfg(feature = "async")]
+        pub fn interrupt_stream(
+            &mut self,
+            trigger: Trigger,
+            debounce: Option<Duration>,
+        ) -> Result<InterruptStream<'_>> {
+            let reactor = self.pin.gpio_state.reactor()?;
+            let interrupt = self.interrupt_fd(trigger, debounce)?;
+
+            Ok(InterruptStream::new(&mut self.pin, interrupt, reactor))
+        }
+
+        /// Waits asynchronously until the specified edge is detected on the pin.
+        ///
+        /// Any previously configured (a)synchronous interrupt triggers for this pin are cleared.
+        /// Edges that occur before `wait_for_edge` is called aren't reported. Use
+        /// [`interrupt_stream`] to make sure no edges are missed between successive calls.
+        ///
+        /// This method is only available when the `async` feature is enabled.
+        ///
+        /// [`interrupt_stream`]: #method.interrupt_stream
+        #[cfg(feature = "async")]
+        pub async fn wait_for_edge(&mut self, trigger: Trigger) -> Result<Event> {
+            self.interrupt_stream(trigger, None)?.next_event().await
+        }
+    };
+}
+
 macro_rules! impl_reset_on_drop {
     () => {
         /// Returns the value of `reset_on_drop`.
@@ -176,8 +571,9 @@ macro_rules! impl_reset_on_drop {
             self.reset_on_drop
         }
 
-        /// When enabled, resets the pin's mode to its original state and disables the
-        /// built-in pull-up/pull-down resistors when the pin goes out of scope.
+        /// When enabled, resets the pin's mode and the built-in pull-up/pull-down resistors
+        /// to their original state when the pin goes out of scope. If the current state of
+        /// the resistors can't be read on this SoC, the resistors are disabled instead.
         /// By default, this is set to `true`.
         ///
         /// ## Note
@@ -196,19 +592,28 @@ macro_rules! impl_reset_on_drop {
 macro_rules! impl_drop {
     ($struct:ident) => {
         impl Drop for $struct {
-            /// Resets the pin's mode and disables the built-in pull-up/pull-down
-            /// resistors if `reset_on_drop` is set to `true` (default).
+            /// Resets the pin's mode and built-in pull-up/pull-down resistors
+            /// if `reset_on_drop` is set to `true` (default).
             fn drop(&mut self) {
                 if !self.reset_on_drop {
                     return;
                 }
 
+                // Errors can't be reported while the pin is dropped
                 if let Some(prev_mode) = self.prev_mode {
-                    self.pin.set_mode(prev_mode);
+                    let _ = self.pin.set_mode(prev_mode);
+                }
+
+                if self.pin.drive != Drive::PushPull {
+                    let _ = self.pin.set_drive(Drive::PushPull);
+                }
+
+                if self.pin.active_low {
+                    let _ = self.pin.set_active_low(false);
                 }
 
-                if self.bias != Bias::Off {
-                    self.pin.set_bias(Bias::Off);
+                if let Some(prev_bias) = self.prev_bias {
+                    let _ = self.pin.set_bias(prev_bias);
                 }
             }
         }
@@ -261,12 +666,30 @@ macro_rules! impl_eq {
 pub struct Pin {
     pub(crate) pin: u8,
     gpio_state: Arc<GpioState>,
+    // Set when a synchronous interrupt trigger is registered for this pin
+    sync_interrupt: bool,
+    // Output drive. Open-drain/open-source outputs are emulated by switching between
+    // input and output mode for backends that don't support configuring the drive.
+    drive: Drive,
+    drive_emulated: bool,
+    // Active-low pins are inverted in software for backends that don't support
+    // inverting the pins through the gpiochip.
+    active_low: bool,
+    active_low_emulated: bool,
 }
 
 impl Pin {
     #[inline]
     pub(crate) fn new(pin: u8, gpio_state: Arc<GpioState>) -> Pin {
-        Pin { pin, gpio_state }
+        Pin {
+            pin,
+            gpio_state,
+            sync_interrupt: false,
+            drive: Drive::PushPull,
+            drive_emulated: false,
+            active_low: false,
+            active_low_emulated: false,
+        }
     }
 
     /// Returns the GPIO pin number.
@@ -283,10 +706,33 @@ impl Pin {
         self.gpio_state.gpio_mem.mode(self.pin)
     }
 
+    /// Returns the pin's built-in pull-up/pull-down resistor state.
+    ///
+    /// Reading the current state is supported on the BCM2711 (Raspberry Pi 4) and
+    /// the Raspberry Pi 5. On earlier models, `bias` returns `Err(`[`Error::NotSupported`]`)`.
+    ///
+    /// [`Error::NotSupported`]: enum.Error.html#variant.NotSupported
+    #[inline]
+    pub fn bias(&self) -> Result<Bias> {
+        self.gpio_state.gpio_mem.bias(self.pin)
+    }
+
+    /// Returns the name of the peripheral signal selected by the pin's current mode.
+    ///
+    /// Returns `None` if the pin is configured as an input or output, or if the
+    /// alternate function can't be identified. See [`function_name`] for more details.
+    ///
+    /// [`function_name`]: fn.function_name.html
+    pub fn function_name(&self) -> Option<&'static str> {
+        let device_info = self.gpio_state.device_info?;
+
+        function_name(device_info.soc(), self.pin, self.mode())
+    }
+
     /// Reads the pin's logic level.
     #[inline]
     pub fn read(&self) -> Level {
-        self.gpio_state.gpio_mem.level(self.pin)
+        self.physical_level(self.gpio_state.gpio_mem.level(self.pin))
     }
 
     /// Consumes the `Pin` and returns an [`InputPin`]. Sets the mode to [`Input`]
@@ -302,8 +748,9 @@ impl Pin {
     /// Consumes the `Pin` and returns an [`InputPin`]. Sets the mode to [`Input`]
     /// and enables the pin's built-in pull-down resistor.
     ///
-    /// The pull-down resistor is disabled when `InputPin` goes out of scope if [`reset_on_drop`]
-    /// is set to `true` (default).
+    /// The pull-up/pull-down resistors are reset to their original state when `InputPin`
+    /// goes out of scope if [`reset_on_drop`] is set to `true` (default). If the current
+    /// state of the resistors can't be read on this SoC, the resistors are disabled instead.
     ///
     /// [`InputPin`]: struct.InputPin.html
     /// [`Input`]: enum.Mode.html#variant.Input
@@ -316,8 +763,9 @@ impl Pin {
     /// Consumes the `Pin` and returns an [`InputPin`]. Sets the mode to [`Input`]
     /// and enables the pin's built-in pull-up resistor.
     ///
-    /// The pull-up resistor is disabled when `InputPin` goes out of scope if [`reset_on_drop`]
-    /// is set to `true` (default).
+    /// The pull-up/pull-down resistors are reset to their original state when `InputPin`
+    /// goes out of scope if [`reset_on_drop`] is set to `true` (default). If the current
+    /// state of the resistors can't be read on this SoC, the resistors are disabled instead.
     ///
     /// [`InputPin`]: struct.InputPin.html
     /// [`Input`]: enum.Mode.html#variant.Input
@@ -338,7 +786,7 @@ impl Pin {
     /// [`Level::Low`] and then sets the mode to [`Mode::Output`].
     #[inline]
     pub fn into_output_low(mut self) -> OutputPin {
-        self.set_low();
+        let _ = self.set_low();
 
         OutputPin::new(self)
     }
@@ -347,11 +795,64 @@ impl Pin {
     /// [`Level::High`] and then sets the mode to [`Mode::Output`].
     #[inline]
     pub fn into_output_high(mut self) -> OutputPin {
-        self.set_high();
+        let _ = self.set_high();
 
         OutputPin::new(self)
     }
 
+    /// Consumes the `Pin` and returns an open-drain [`OutputPin`].
+    ///
+    /// An open-drain output actively drives the pin low when it's set to [`Level::Low`],
+    /// and releases the pin when it's set to [`Level::High`], leaving the logic level to
+    /// an external or built-in pull-up resistor. This allows multiple devices to share a
+    /// single line, such as an I2C bus or a wired-OR interrupt line.
+    ///
+    /// The pin is initially released. If the [`Backend`] supports open-drain outputs
+    /// through the `gpiochip` character device, the output drive is configured in the kernel.
+    /// Otherwise, the open-drain output is emulated by switching the pin's mode between
+    /// [`Mode::Input`] and [`Mode::Output`]. Software-based PWM isn't supported for emulated
+    /// open-drain outputs.
+    ///
+    /// Returns an error if the pin can't be configured. In that case, the pin is reset to
+    /// its original mode.
+    ///
+    /// [`OutputPin`]: struct.OutputPin.html
+    /// [`Backend`]: enum.Backend.html
+    /// [`Level::Low`]: enum.Level.html#variant.Low
+    /// [`Level::High`]: enum.Level.html#variant.High
+    /// [`Mode::Input`]: enum.Mode.html#variant.Input
+    /// [`Mode::Output`]: enum.Mode.html#variant.Output
+    #[inline]
+    pub fn into_output_open_drain(self) -> Result<OutputPin> {
+        OutputPin::with_drive(self, Drive::OpenDrain)
+    }
+
+    /// Consumes the `Pin` and returns an open-source [`OutputPin`].
+    ///
+    /// An open-source output actively drives the pin high when it's set to [`Level::High`],
+    /// and releases the pin when it's set to [`Level::Low`], leaving the logic level to
+    /// an external or built-in pull-down resistor.
+    ///
+    /// The pin is initially released. If the [`Backend`] supports open-source outputs
+    /// through the `gpiochip` character device, the output drive is configured in the kernel.
+    /// Otherwise, the open-source output is emulated by switching the pin's mode between
+    /// [`Mode::Input`] and [`Mode::Output`]. Software-based PWM isn't supported for emulated
+    /// open-source outputs.
+    ///
+    /// Returns an error if the pin can't be configured. In that case, the pin is reset to
+    /// its original mode.
+    ///
+    /// [`OutputPin`]: struct.OutputPin.html
+    /// [`Backend`]: enum.Backend.html
+    /// [`Level::Low`]: enum.Level.html#variant.Low
+    /// [`Level::High`]: enum.Level.html#variant.High
+    /// [`Mode::Input`]: enum.Mode.html#variant.Input
+    /// [`Mode::Output`]: enum.Mode.html#variant.Output
+    #[inline]
+    pub fn into_output_open_source(self) -> Result<OutputPin> {
+        OutputPin::with_drive(self, Drive::OpenSource)
+    }
+
     /// Consumes the `Pin` and returns an [`IoPin`]. Sets the mode to the specified mode.
     ///
     /// [`IoPin`]: struct.IoPin.html
@@ -362,37 +863,254 @@ impl Pin {
     }
 
     #[inline]
-    pub(crate) fn set_mode(&mut self, mode: Mode) {
-        self.gpio_state.gpio_mem.set_mode(self.pin, mode);
+    pub(crate) fn set_mode(&mut self, mode: Mode) -> Result<()> {
+        self.gpio_state.gpio_mem.set_mode(self.pin, mode)
+    }
+
+    // Returns the bias to restore on drop when changing the bias to the specified
+    // value, or None if the bias doesn't change. Falls back to Bias::Off if the
+    // current bias can't be read.
+    pub(crate) fn prev_bias(&self, bias: Bias) -> Option<Bias> {
+        match self.bias() {
+            Ok(prev_bias) if prev_bias == bias => None,
+            Ok(prev_bias) => Some(prev_bias),
+            Err(_) if bias == Bias::Off => None,
+            Err(_) => Some(Bias::Off),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn set_bias(&mut self, bias: Bias) -> Result<()> {
+        self.gpio_state.gpio_mem.set_bias(self.pin, bias)
+    }
+
+    // Configures the output drive, and falls back to emulating open-drain/open-source
+    // outputs if the backend doesn't support it.
+    pub(crate) fn set_drive(&mut self, drive: Drive) -> Result<()> {
+        self.drive_emulated = match self.gpio_state.gpio_mem.set_drive(self.pin, drive) {
+            Ok(()) => false,
+            Err(Error::NotSupported) => drive != Drive::PushPull,
+            Err(e) => return Err(e),
+        };
+
+        self.drive = drive;
+
+        Ok(())
+    }
+
+    #[inline]
+    pub(crate) fn is_drive_emulated(&self) -> bool {
+        self.drive_emulated
+    }
+
+    #[inline]
+    fn emulated_drive(&self) -> Drive {
+        if self.drive_emulated {
+            self.drive
+        } else {
+            Drive::PushPull
+        }
+    }
+
+    // Configures the pin as active-low, and falls back to inverting the pin
+    // in software if the backend doesn't support it.
+    pub(crate) fn set_active_low(&mut self, active_low: bool) -> Result<()> {
+        self.active_low_emulated = match self
+            .gpio_state
+            .gpio_mem
+            .set_active_low(self.pin, active_low)
+        {
+            Ok(()) => false,
+            Err(Error::NotSupported) => active_low,
+            Err(e) => return Err(e),
+        };
+
+        self.active_low = active_low;
+
+        Ok(())
+    }
+
+    // Converts between logical and physical levels for pins that are inverted in software
+    #[inline]
+    pub(crate) fn physical_level(&self, level: Level) -> Level {
+        if self.active_low_emulated {
+            !level
+        } else {
+            level
+        }
+    }
+
+    // Converts between logical and physical edges for pins that are inverted in software
+    #[inline]
+    pub(crate) fn physical_trigger(&self, trigger: Trigger) -> Trigger {
+        if self.active_low_emulated {
+            invert_trigger(trigger)
+        } else {
+            trigger
+        }
     }
 
     #[inline]
-    pub(crate) fn set_bias(&mut self, bias: Bias) {
-        self.gpio_state.gpio_mem.set_bias(self.pin, bias);
+    pub(crate) fn logical_event(&self, mut event: Event) -> Event {
+        event.trigger = self.physical_trigger(event.trigger);
+
+        event
     }
 
     #[inline]
-    pub(crate) fn set_low(&mut self) {
-        self.gpio_state.gpio_mem.set_low(self.pin);
+    pub(crate) fn set_low(&mut self) -> Result<()> {
+        let level = self.physical_level(Level::Low);
+        self.set_physical_level(level)
     }
 
     #[inline]
-    pub(crate) fn set_high(&mut self) {
-        self.gpio_state.gpio_mem.set_high(self.pin);
+    pub(crate) fn set_high(&mut self) -> Result<()> {
+        let level = self.physical_level(Level::High);
+        self.set_physical_level(level)
     }
 
     #[inline]
-    pub(crate) fn write(&mut self, level: Level) {
+    pub(crate) fn write(&mut self, level: Level) -> Result<()> {
         match level {
             Level::Low => self.set_low(),
             Level::High => self.set_high(),
-        };
+        }
+    }
+
+    #[inline]
+    fn set_physical_level(&mut self, level: Level) -> Result<()> {
+        match level {
+            Level::Low => self.set_physical_low(),
+            Level::High => self.set_physical_high(),
+        }
+    }
+
+    #[inline]
+    fn set_physical_low(&mut self) -> Result<()> {
+        match self.emulated_drive() {
+            Drive::PushPull => self.gpio_state.gpio_mem.set_low(self.pin),
+            Drive::OpenDrain => {
+                // Set the output level before enabling the output, so the pin is
+                // never actively driven high.
+                self.gpio_state.gpio_mem.set_low(self.pin)?;
+                self.gpio_state.gpio_mem.set_mode(self.pin, Mode::Output)
+            }
+            Drive::OpenSource => self.gpio_state.gpio_mem.set_mode(self.pin, Mode::Input),
+        }
+    }
+
+    #[inline]
+    fn set_physical_high(&mut self) -> Result<()> {
+        match self.emulated_drive() {
+            Drive::PushPull => self.gpio_state.gpio_mem.set_high(self.pin),
+            Drive::OpenDrain => self.gpio_state.gpio_mem.set_mode(self.pin, Mode::Input),
+            Drive::OpenSource => {
+                // Set the output level before enabling the output, so the pin is
+                // never actively driven low.
+                self.gpio_state.gpio_mem.set_high(self.pin)?;
+                self.gpio_state.gpio_mem.set_mode(self.pin, Mode::Output)
+            }
+        }
+    }
+}
+
+// Restores a pin's mode when it goes out of scope, so the mode is also restored
+// when configuring an interrupt trigger fails
+struct RestoreMode {
+    gpio_mem: Arc<dyn GpioRegisters>,
+    pin: u8,
+    mode: Option<Mode>,
+}
+
+impl RestoreMode {
+    fn new(pin: &Pin, mode: Option<Mode>) -> RestoreMode {
+        RestoreMode {
+            gpio_mem: pin.gpio_state.gpio_mem.clone(),
+            pin: pin.pin,
+            mode,
+        }
     }
 }
 
+impl Drop for RestoreMode {
+    fn drop(&mut self) {
+        if let Some(mode) = self.mode {
+            let _ = self.gpio_mem.set_mode(self.pin, mode);
+        }
+    }
+}
+
+// Swaps rising and falling edges
+pub(crate) fn invert_trigger(trigger: Trigger) -> Trigger {
+    match trigger {
+        Trigger::RisingEdge => Trigger::FallingEdge,
+        Trigger::FallingEdge => Trigger::RisingEdge,
+        _ => trigger,
+    }
+}
+
+/// GPIO pin types that can be polled for synchronous interrupts.
+///
+/// `InterruptPin` is implemented for [`InputPin`], [`IoPin`] and [`OutputPin`], which allows
+/// pins of each type to be polled with [`Gpio::poll_interrupts`] and
+/// [`Gpio::poll_interrupts_batch`]. Pins of different types can be polled simultaneously by
+/// passing them as `&dyn InterruptPin`.
+///
+/// This trait is sealed, and can't be implemented outside of RPPAL.
+///
+/// [`InputPin`]: struct.InputPin.html
+/// [`IoPin`]: struct.IoPin.html
+/// [`OutputPin`]: struct.OutputPin.html
+/// [`Gpio::poll_interrupts`]: struct.Gpio.html#method.poll_interrupts
+/// [`Gpio::poll_interrupts_batch`]: struct.Gpio.html#method.poll_interrupts_batch
+pub trait InterruptPin: private::Sealed {
+    /// Returns the GPIO pin number.
+    ///
+    /// Pins are addressed by their BCM numbers, rather than their physical location.
+    fn pin(&self) -> u8;
+}
+
+mod private {
+    use crate::gpio::Event;
+
+    pub trait Sealed {
+        // Converts a trigger event reported by the kernel to the pin's logic levels
+        fn logical_event(&self, event: Event) -> Event;
+    }
+}
+
+macro_rules! impl_interrupt_pin {
+    ($struct:ident) => {
+        impl InterruptPin for $struct {
+            #[inline]
+            fn pin(&self) -> u8 {
+                self.pin.pin
+            }
+        }
+
+        impl private::Sealed for $struct {
+            #[inline]
+            fn logical_event(&self, event: Event) -> Event {
+                self.pin.logical_event(event)
+            }
+        }
+    };
+}
+
+impl_interrupt_pin!(InputPin);
+impl_interrupt_pin!(IoPin);
+impl_interrupt_pin!(OutputPin);
+
 impl Drop for Pin {
     fn drop(&mut self) {
+        // Close the event request of any remaining synchronous interrupt trigger,
+        // so the line request isn't kept open after the pin is released
+        if self.sync_interrupt {
+            let _ = (*self.gpio_state.sync_interrupts.lock().unwrap()).clear_interrupt(self.pin);
+        }
+
         // Release taken pin
+        self.gpio_state.gpio_mem.release(self.pin);
         self.gpio_state.pins_taken[self.pin as usize].store(false, Ordering::SeqCst);
     }
 }
@@ -424,7 +1142,7 @@ pub struct InputPin {
     prev_mode: Option<Mode>,
     async_interrupt: Option<AsyncInterrupt>,
     reset_on_drop: bool,
-    bias: Bias,
+    prev_bias: Option<Bias>,
 }
 
 impl InputPin {
@@ -434,122 +1152,145 @@ impl InputPin {
         let prev_mode = if prev_mode == Mode::Input {
             None
         } else {
-            pin.set_mode(Mode::Input);
+            let _ = pin.set_mode(Mode::Input);
             Some(prev_mode)
         };
 
-        pin.set_bias(bias);
+        let prev_bias = pin.prev_bias(bias);
+        let _ = pin.set_bias(bias);
 
         InputPin {
             pin,
             prev_mode,
             async_interrupt: None,
             reset_on_drop: true,
-            bias,
+            prev_bias,
         }
     }
 
     impl_pin!();
     impl_input!();
+    impl_active_low!();
+    impl_interrupt!();
+
+    // Interrupts are always supported in input mode
+    fn interrupt_mode(&self) -> Result<Option<Mode>> {
+        Ok(None)
+    }
+
+    impl_reset_on_drop!();
+}
+
+impl_drop!(InputPin);
+impl_eq!(InputPin);
+
+/// Group of GPIO pins configured as input, which can be read simultaneously.
+///
+/// `InputPort`s are constructed from a list of [`InputPin`]s using [`InputPort::new`].
+/// Each pin is assigned a bit in the bitmask returned by [`read`], based on its
+/// position in the list. The first pin corresponds to the least significant bit.
+///
+/// Reading multiple pins through an `InputPort` avoids inconsistent results caused by
+/// the logic levels changing while the pins are read one at a time. When using the
+/// [`Backend::GpioMem`] backend, the logic levels of all pins are retrieved from a single
+/// register read. On the BCM283x and BCM2711, GPIO0-31 and GPIO32-57 are located in
+/// separate registers. On the Raspberry Pi 5, each bank uses a separate register.
+/// Other backends read the pins one at a time.
+///
+/// [`InputPin`]: struct.InputPin.html
+/// [`InputPort::new`]: #method.new
+/// [`read`]: #method.read
+/// [`Backend::GpioMem`]: enum.Backend.html#variant.GpioMem
+#[derive(Debug)]
+pub struct InputPort {
+    pins: Vec<InputPin>,
+}
 
-    /// Configures a synchronous interrupt trigger.
+impl InputPort {
+    /// Constructs a new `InputPort` containing the specified pins.
     ///
-    /// After configuring a synchronous interrupt trigger, call [`poll_interrupt`] or
-    /// [`Gpio::poll_interrupts`] to block while waiting for a trigger event.
+    /// An `InputPort` can contain up to 64 pins.
     ///
-    /// Any previously configured (a)synchronous interrupt triggers will be cleared.
+    /// ## Panics
     ///
-    /// [`poll_interrupt`]: #method.poll_interrupt
-    /// [`Gpio::poll_interrupts`]: struct.Gpio.html#method.poll_interrupts
-    pub fn set_interrupt(&mut self, trigger: Trigger) -> Result<()> {
-        self.clear_async_interrupt()?;
+    /// `new` panics if `pins` contains more than 64 pins.
+    pub fn new(pins: Vec<InputPin>) -> InputPort {
+        assert!(
+            pins.len() <= 64,
+            "InputPort can't contain more than 64 pins"
+        );
+
+        InputPort { pins }
+    }
 
-        // Each pin can only be configured for a single trigger type
-        (*self.pin.gpio_state.sync_interrupts.lock().unwrap()).set_interrupt(self.pin(), trigger)
+    /// Returns the pins contained in the `InputPort`.
+    pub fn pins(&self) -> &[InputPin] {
+        &self.pins
     }
 
-    /// Removes a previously configured synchronous interrupt trigger.
-    pub fn clear_interrupt(&mut self) -> Result<()> {
-        (*self.pin.gpio_state.sync_interrupts.lock().unwrap()).clear_interrupt(self.pin())
+    /// Consumes the `InputPort` and returns the contained pins.
+    pub fn into_pins(self) -> Vec<InputPin> {
+        self.pins
     }
 
-    /// Blocks until an interrupt is triggered on the pin, or a timeout occurs.
-    ///
-    /// This only works after the pin has been configured for synchronous interrupts using
-    /// [`set_interrupt`]. Asynchronous interrupt triggers are automatically polled on a separate thread.
+    /// Reads the logic level of all pins.
     ///
-    /// Calling `poll_interrupt` blocks any other calls to `poll_interrupt` (including on other `InputPin`s) or
-    /// [`Gpio::poll_interrupts`] until it returns. If you need to poll multiple pins simultaneously, use
-    /// [`Gpio::poll_interrupts`] to block while waiting for any of the interrupts to trigger, or switch to
-    /// using asynchronous interrupts with [`set_async_interrupt`].
+    /// Each bit in the returned value is set to `1` if the pin at the corresponding
+    /// position is [`High`], or `0` if it's [`Low`].
     ///
-    /// Setting `reset` to `false` returns any cached interrupt trigger events if available. Setting `reset` to `true`
-    /// clears all cached events before polling for new events.
-    ///
-    /// The `timeout` duration indicates how long the call will block while waiting
-    /// for interrupt trigger events, after which an `Ok(None))` is returned.
-    /// `timeout` can be set to `None` to wait indefinitely.
-    ///
-    /// [`set_interrupt`]: #method.set_interrupt
-    /// [`Gpio::poll_interrupts`]: struct.Gpio.html#method.poll_interrupts
-    /// [`set_async_interrupt`]: #method.set_async_interrupt
-    pub fn poll_interrupt(
-        &mut self,
-        reset: bool,
-        timeout: Option<Duration>,
-    ) -> Result<Option<Level>> {
-        let opt =
-            (*self.pin.gpio_state.sync_interrupts.lock().unwrap()).poll(&[self], reset, timeout)?;
+    /// [`High`]: enum.Level.html#variant.High
+    /// [`Low`]: enum.Level.html#variant.Low
+    pub fn read(&self) -> u64 {
+        // Pins retrieved through Gpio::with_chip may belong to a different GpioState,
+        // so the pins are grouped by GpioState, and stored as a bitmask indexed by
+        // their BCM GPIO number.
+        let mut groups: Vec<(&Arc<GpioState>, u64)> = Vec::new();
+
+        for input_pin in &self.pins {
+            let pin = &input_pin.pin;
+
+            // Pin bitmasks can't contain pins > 63
+            if pin.pin >= 64 {
+                continue;
+            }
 
-        if let Some(trigger) = opt {
-            Ok(Some(trigger.1))
-        } else {
-            Ok(None)
+            if let Some(group) = groups
+                .iter_mut()
+                .find(|group| Arc::ptr_eq(group.0, &pin.gpio_state))
+            {
+                group.1 |= 1 << pin.pin;
+            } else {
+                groups.push((&pin.gpio_state, 1 << pin.pin));
+            }
         }
-    }
 
-    /// Configures an asynchronous interrupt trigger, which executes the callback on a
-    /// separate thread when the interrupt is triggered.
-    ///
-    /// The callback closure or function pointer is called with a single [`Level`] argument.
-    ///
-    /// Any previously configured (a)synchronous interrupt triggers for this pin are cleared
-    /// when `set_async_interrupt` is called, or when `InputPin` goes out of scope.
-    ///
-    /// [`clear_async_interrupt`]: #method.clear_async_interrupt
-    /// [`Level`]: enum.Level.html
-    pub fn set_async_interrupt<C>(&mut self, trigger: Trigger, callback: C) -> Result<()>
-    where
-        C: FnMut(Level) + Send + 'static,
-    {
-        self.clear_interrupt()?;
-        self.clear_async_interrupt()?;
+        let groups: Vec<(&Arc<GpioState>, u64)> = groups
+            .into_iter()
+            .map(|(gpio_state, mask)| (gpio_state, gpio_state.gpio_mem.levels(mask)))
+            .collect();
 
-        self.async_interrupt = Some(AsyncInterrupt::new(
-            self.pin.gpio_state.cdev.as_raw_fd(),
-            self.pin(),
-            trigger,
-            callback,
-        )?);
+        let mut bits = 0;
+        for (index, input_pin) in self.pins.iter().enumerate() {
+            let pin = &input_pin.pin;
 
-        Ok(())
-    }
+            let level = if pin.pin >= 64 {
+                pin.read()
+            } else {
+                let level = groups
+                    .iter()
+                    .find(|group| Arc::ptr_eq(group.0, &pin.gpio_state))
+                    .map_or(0, |group| (group.1 >> pin.pin) & 0x01);
+
+                pin.physical_level(Level::from(level as u8))
+            };
 
-    /// Removes a previously configured asynchronous interrupt trigger.
-    pub fn clear_async_interrupt(&mut self) -> Result<()> {
-        if let Some(mut interrupt) = self.async_interrupt.take() {
-            interrupt.stop()?;
+            bits |= (level as u64) << index;
         }
 
-        Ok(())
+        bits
     }
-
-    impl_reset_on_drop!();
 }
 
-impl_drop!(InputPin);
-impl_eq!(InputPin);
-
 /// GPIO pin configured as output.
 ///
 /// `OutputPin`s are constructed by converting a [`Pin`] using [`Pin::into_output`],
@@ -558,6 +1299,16 @@ impl_eq!(InputPin);
 ///
 /// An `OutputPin` can be used to change a pin's output state.
 ///
+/// When an `OutputPin` is configured as an emulated open-drain or open-source output,
+/// interrupts can be used to detect when an external device pulls the line. Configuring
+/// an interrupt for a push-pull output returns [`Error::UnsupportedMode`]. The `Cdev`
+/// backend configures the output drive through the `gpiochip`, which doesn't support edge
+/// detection on output lines, and returns [`Error::NotSupported`] instead.
+///
+/// The kernel only supports edge detection on lines configured as input. Configuring an
+/// interrupt trigger on an emulated open-drain or open-source output that's currently
+/// driving the line briefly releases the line, until the pin's mode is restored.
+///
 /// The `embedded-hal` [`digital::OutputPin`] and [`PwmPin`] trait implementations for `OutputPin`
 /// can be enabled by specifying the optional `hal` feature in the dependency
 /// declaration for the `rppal` crate.
@@ -573,12 +1324,15 @@ impl_eq!(InputPin);
 /// [`Pwm`]: ../../embedded_hal/trait.Pwm.html
 /// [`digital::OutputPin`]: ../../embedded_hal/digital/trait.OutputPin.html
 /// [`PwmPin`]: ../../embedded_hal/trait.PwmPin.html
+/// [`Error::UnsupportedMode`]: enum.Error.html#variant.UnsupportedMode
+/// [`Error::NotSupported`]: enum.Error.html#variant.NotSupported
 #[derive(Debug)]
 pub struct OutputPin {
     pin: Pin,
     prev_mode: Option<Mode>,
+    async_interrupt: Option<AsyncInterrupt>,
     reset_on_drop: bool,
-    bias: Bias,
+    prev_bias: Option<Bias>,
     pub(crate) soft_pwm: Option<SoftPwm>,
     // Stores the softpwm frequency. Used for embedded_hal::PwmPin.
     #[cfg(feature = "hal")]
@@ -595,21 +1349,60 @@ impl OutputPin {
         let prev_mode = if prev_mode == Mode::Output {
             None
         } else {
-            pin.set_mode(Mode::Output);
+            let _ = pin.set_mode(Mode::Output);
             Some(prev_mode)
         };
 
         OutputPin {
             pin,
             prev_mode,
+            async_interrupt: None,
+            reset_on_drop: true,
+            prev_bias: None,
+            soft_pwm: None,
+            #[cfg(feature = "hal")]
+            frequency: 0.0,
+            #[cfg(feature = "hal")]
+            duty_cycle: 0.0,
+        }
+    }
+
+    pub(crate) fn with_drive(pin: Pin, drive: Drive) -> Result<OutputPin> {
+        let prev_mode = pin.mode();
+
+        // If any of the changes below fail, the pin is reset when the OutputPin is dropped
+        let mut output_pin = OutputPin {
+            pin,
+            prev_mode: Some(prev_mode),
+            async_interrupt: None,
             reset_on_drop: true,
-            bias: Bias::Off,
+            prev_bias: None,
             soft_pwm: None,
             #[cfg(feature = "hal")]
             frequency: 0.0,
             #[cfg(feature = "hal")]
             duty_cycle: 0.0,
+        };
+
+        let pin = &mut output_pin.pin;
+
+        // Release the pin before changing the output drive, so it's never
+        // actively driven while it's being reconfigured.
+        pin.set_mode(Mode::Input)?;
+        pin.set_drive(drive)?;
+
+        if drive == Drive::OpenSource {
+            pin.set_low()?;
+        } else {
+            pin.set_high()?;
+        }
+
+        // Emulated outputs only switch to output mode when actively driven
+        if !pin.is_drive_emulated() {
+            pin.set_mode(Mode::Output)?;
         }
+
+        Ok(output_pin)
     }
 
     impl_pin!();
@@ -631,12 +1424,151 @@ impl OutputPin {
     }
 
     impl_output!();
+    impl_pad!();
+    impl_active_low!();
+    impl_interrupt!();
+
+    // Edge detection requires input mode. Emulated open-drain and open-source outputs
+    // switch to input mode to release the line, which allows interrupts to detect
+    // external pulls. Requesting edge events through the gpiochip also switches the
+    // pin to input mode, which can't be avoided through the uAPI, so the current mode
+    // is restored afterwards, even if the request fails.
+    fn interrupt_mode(&self) -> Result<Option<Mode>> {
+        if self.pin.drive == Drive::PushPull {
+            return Err(Error::UnsupportedMode(self.pin.pin, Mode::Output));
+        }
+
+        // The gpiochip doesn't support edge detection for lines configured as output
+        if !self.pin.is_drive_emulated() {
+            return Err(Error::NotSupported);
+        }
+
+        Ok(Some(self.pin.mode()))
+    }
+
     impl_reset_on_drop!();
 }
 
 impl_drop!(OutputPin);
 impl_eq!(OutputPin);
 
+/// Group of GPIO pins configured as output, which can be changed simultaneously.
+///
+/// `OutputPort`s are constructed from a list of [`OutputPin`]s using [`OutputPort::new`].
+/// Each pin is assigned a bit in the bitmasks used by [`write`] and [`write_mask`], based
+/// on its position in the list. The first pin corresponds to the least significant bit.
+///
+/// Changing multiple pins through an `OutputPort` avoids the glitches caused by updating
+/// the pins one at a time. When using the [`Backend::GpioMem`] backend, all pins that
+/// are set to [`High`] are changed in a single register write, followed by all pins that are
+/// set to [`Low`]. On the BCM283x and BCM2711, GPIO0-31 and GPIO32-57 are located in
+/// separate registers. On the Raspberry Pi 5, each bank uses a separate register.
+/// Other backends, and open-drain or open-source outputs that are emulated, change
+/// the pins one at a time.
+///
+/// [`OutputPin`]: struct.OutputPin.html
+/// [`OutputPort::new`]: #method.new
+/// [`write`]: #method.write
+/// [`write_mask`]: #method.write_mask
+/// [`Backend::GpioMem`]: enum.Backend.html#variant.GpioMem
+/// [`High`]: enum.Level.html#variant.High
+/// [`Low`]: enum.Level.html#variant.Low
+#[derive(Debug)]
+pub struct OutputPort {
+    pins: Vec<OutputPin>,
+}
+
+impl OutputPort {
+    /// Constructs a new `OutputPort` containing the specified pins.
+    ///
+    /// An `OutputPort` can contain up to 64 pins.
+    ///
+    /// ## Panics
+    ///
+    /// `new` panics if `pins` contains more than 64 pins.
+    pub fn new(pins: Vec<OutputPin>) -> OutputPort {
+        assert!(
+            pins.len() <= 64,
+            "OutputPort can't contain more than 64 pins"
+        );
+
+        OutputPort { pins }
+    }
+
+    /// Returns the pins contained in the `OutputPort`.
+    pub fn pins(&self) -> &[OutputPin] {
+        &self.pins
+    }
+
+    /// Consumes the `OutputPort` and returns the contained pins.
+    pub fn into_pins(self) -> Vec<OutputPin> {
+        self.pins
+    }
+
+    /// Changes the output state of all pins.
+    ///
+    /// Each bit in `bits` sets the pin at the corresponding position to either
+    /// [`High`] (`1`) or [`Low`] (`0`).
+    ///
+    /// [`High`]: enum.Level.html#variant.High
+    /// [`Low`]: enum.Level.html#variant.Low
+    #[inline]
+    pub fn write(&mut self, bits: u64) -> Result<()> {
+        self.write_mask(bits, u64::MAX)
+    }
+
+    /// Changes the output state of the pins selected by `mask`.
+    ///
+    /// Each bit in `bits` sets the pin at the corresponding position to either
+    /// [`High`] (`1`) or [`Low`] (`0`), if the same bit is also set in `mask`.
+    /// Pins with a cleared bit in `mask` remain unchanged.
+    ///
+    /// [`High`]: enum.Level.html#variant.High
+    /// [`Low`]: enum.Level.html#variant.Low
+    pub fn write_mask(&mut self, bits: u64, mask: u64) -> Result<()> {
+        // Pins retrieved through Gpio::with_chip may belong to a different GpioState,
+        // so the pins are grouped by GpioState, and stored as a bitmask indexed by
+        // their BCM GPIO number.
+        let mut groups: Vec<(Arc<GpioState>, u64, u64)> = Vec::new();
+
+        for (index, output_pin) in self.pins.iter_mut().enumerate() {
+            if (mask >> index) & 0x01 == 0 {
+                continue;
+            }
+
+            let pin = &mut output_pin.pin;
+            let level = Level::from(((bits >> index) & 0x01) as u8);
+
+            // Emulated outputs need to switch modes, and pin bitmasks can't contain pins > 63
+            if pin.is_drive_emulated() || pin.pin >= 64 {
+                pin.write(level)?;
+                continue;
+            }
+
+            let group = if let Some(position) = groups
+                .iter()
+                .position(|group| Arc::ptr_eq(&group.0, &pin.gpio_state))
+            {
+                &mut groups[position]
+            } else {
+                groups.push((pin.gpio_state.clone(), 0, 0));
+                groups.last_mut().unwrap()
+            };
+
+            match pin.physical_level(level) {
+                Level::High => group.1 |= 1 << pin.pin,
+                Level::Low => group.2 |= 1 << pin.pin,
+            }
+        }
+
+        for (gpio_state, high, low) in groups {
+            gpio_state.gpio_mem.set_levels(high, low)?;
+        }
+
+        Ok(())
+    }
+}
+
 /// GPIO pin that can be (re)configured for any mode or alternate function.
 ///
 /// `IoPin`s are constructed by converting a [`Pin`] using [`Pin::into_io`].
@@ -647,6 +1579,9 @@ impl_eq!(OutputPin);
 /// alters the pin's output state won't cause any changes when the pin's mode is set
 /// to [`Input`].
 ///
+/// Interrupts can only be configured while the pin's mode is set to [`Input`]. Changing
+/// the mode clears any configured interrupt triggers.
+///
 /// The `embedded-hal` [`digital::OutputPin`] and [`PwmPin`] trait implementations for `IoPin`
 /// can be enabled by specifying the optional `hal` feature in the dependency
 /// declaration for the `rppal` crate.
@@ -670,8 +1605,9 @@ pub struct IoPin {
     pin: Pin,
     mode: Mode,
     prev_mode: Option<Mode>,
+    async_interrupt: Option<AsyncInterrupt>,
     reset_on_drop: bool,
-    bias: Bias,
+    prev_bias: Option<Bias>,
     pub(crate) soft_pwm: Option<SoftPwm>,
     // Stores the softpwm frequency. Used for embedded_hal::PwmPin.
     #[cfg(feature = "hal")]
@@ -688,7 +1624,7 @@ impl IoPin {
         let prev_mode = if prev_mode == mode {
             None
         } else {
-            pin.set_mode(mode);
+            let _ = pin.set_mode(mode);
             Some(prev_mode)
         };
 
@@ -696,8 +1632,9 @@ impl IoPin {
             pin,
             mode,
             prev_mode,
+            async_interrupt: None,
             reset_on_drop: true,
-            bias: Bias::Off,
+            prev_bias: None,
             soft_pwm: None,
             #[cfg(feature = "hal")]
             frequency: 0.0,
@@ -715,8 +1652,13 @@ impl IoPin {
     }
 
     /// Sets the pin's mode.
+    ///
+    /// Interrupts are only supported in [`Mode::Input`]. Changing the mode to anything
+    /// else clears any (a)synchronous interrupt triggers configured for this pin.
+    ///
+    /// [`Mode::Input`]: enum.Mode.html#variant.Input
     #[inline]
-    pub fn set_mode(&mut self, mode: Mode) {
+    pub fn set_mode(&mut self, mode: Mode) -> Result<()> {
         // If self.prev_mode is set to None, that means the
         // requested mode during construction was the same as
         // the current mode. Save that mode if we're changing
@@ -725,18 +1667,50 @@ impl IoPin {
             self.prev_mode = Some(self.mode);
         }
 
-        self.pin.set_mode(mode);
+        if mode != Mode::Input {
+            let _ = self.clear_interrupt();
+            let _ = self.clear_async_interrupt();
+        }
+
+        self.pin.set_mode(mode)
+    }
+
+    /// Returns the pin's built-in pull-up/pull-down resistor state.
+    ///
+    /// Reading the current state is supported on the BCM2711 (Raspberry Pi 4) and
+    /// the Raspberry Pi 5. On earlier models, `bias` returns `Err(`[`Error::NotSupported`]`)`.
+    ///
+    /// [`Error::NotSupported`]: enum.Error.html#variant.NotSupported
+    #[inline]
+    pub fn bias(&self) -> Result<Bias> {
+        self.pin.bias()
     }
 
     /// Configures the built-in pull-up/pull-down resistors.
     #[inline]
-    pub fn set_bias(&mut self, bias: Bias) {
-        self.pin.set_bias(bias);
-        self.bias = bias;
+    pub fn set_bias(&mut self, bias: Bias) -> Result<()> {
+        // Save the original bias the first time it changes, so we can reset it on drop.
+        if self.prev_bias.is_none() {
+            self.prev_bias = self.pin.prev_bias(bias);
+        }
+
+        self.pin.set_bias(bias)
     }
 
     impl_input!();
     impl_output!();
+    impl_pad!();
+    impl_active_low!();
+    impl_interrupt!();
+
+    // Edge detection requires input mode
+    fn interrupt_mode(&self) -> Result<Option<Mode>> {
+        match self.pin.mode() {
+            Mode::Input => Ok(None),
+            mode => Err(Error::UnsupportedMode(self.pin.pin, mode)),
+        }
+    }
+
     impl_reset_on_drop!();
 }
 
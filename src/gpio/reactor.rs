@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::task::Waker;
+use std::thread;
+
+use crate::gpio::epoll::{
+    epoll_event, Epoll, EventFd, EPOLLERR, EPOLLET, EPOLLIN, EPOLLONESHOT, EPOLLPRI,
+};
+use crate::gpio::Result;
+
+// Maximum number of readiness events handled per epoll_wait() call
+const MAX_EVENTS: usize = 16;
+
+#[derive(Debug)]
+struct Shared {
+    poll: Epoll,
+    // Registered fds, and the task waiting for each fd to become readable.
+    // An fd is added to the epoll instance when it's first registered, and
+    // removed when it's deregistered.
+    wakers: Mutex<HashMap<i32, Option<Waker>>>,
+}
+
+// Wakes async tasks when their event fds become readable. A single thread waits
+// on all registered fds. Each fd is registered as EPOLLONESHOT, so it's disabled
+// after its task is woken, until the task registers it again.
+#[derive(Debug)]
+pub(crate) struct Reactor {
+    shared: Arc<Shared>,
+    poll_thread: Option<thread::JoinHandle<Result<()>>>,
+    tx: EventFd,
+}
+
+impl Reactor {
+    pub(crate) fn new() -> Result<Reactor> {
+        let shared = Arc::new(Shared {
+            poll: Epoll::new()?,
+            wakers: Mutex::new(HashMap::new()),
+        });
+
+        let tx = EventFd::new()?;
+        let rx = tx.fd();
+
+        // rx becomes readable when the reactor is dropped
+        shared
+            .poll
+            .add(rx, rx as u64, EPOLLERR | EPOLLET | EPOLLIN)?;
+
+        let poll_shared = shared.clone();
+        let poll_thread = thread::spawn(move || -> Result<()> {
+            let mut events = [epoll_event { events: 0, u64: 0 }; MAX_EVENTS];
+            loop {
+                let num_events = poll_shared.poll.wait(&mut events, None)?;
+                for event in &events[0..num_events] {
+                    let fd = event.u64 as i32;
+                    if fd == rx {
+                        return Ok(()); // The reactor is being dropped
+                    }
+
+                    let waker = poll_shared
+                        .wakers
+                        .lock()
+                        .unwrap()
+                        .get_mut(&fd)
+                        .and_then(Option::take);
+
+                    if let Some(waker) = waker {
+                        waker.wake();
+                    }
+                }
+            }
+        });
+
+        Ok(Reactor {
+            shared,
+            poll_thread: Some(poll_thread),
+            tx,
+        })
+    }
+
+    // Wakes the task associated with waker once fd becomes readable. If fd is
+    // already readable, the task is woken immediately.
+    pub(crate) fn register(&self, fd: i32, waker: &Waker) -> Result<()> {
+        let mut wakers = self.shared.wakers.lock().unwrap();
+
+        let event_mask = EPOLLIN | EPOLLPRI | EPOLLONESHOT;
+        if let Some(entry) = wakers.get_mut(&fd) {
+            *entry = Some(waker.clone());
+            self.shared.poll.modify(fd, fd as u64, event_mask)?;
+        } else {
+            wakers.insert(fd, Some(waker.clone()));
+            if let Err(e) = self.shared.poll.add(fd, fd as u64, event_mask) {
+                wakers.remove(&fd);
+                return Err(e.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    // Removes fd from the reactor. This needs to be called before fd is closed.
+    pub(crate) fn deregister(&self, fd: i32) -> Result<()> {
+        if self.shared.wakers.lock().unwrap().remove(&fd).is_some() {
+            self.shared.poll.delete(fd)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Reactor {
+    fn drop(&mut self) {
+        let _ = self.tx.notify();
+
+        // The reactor thread doesn't execute any user code, so it can't block
+        // indefinitely
+        if let Some(poll_thread) = self.poll_thread.take() {
+            let _ = poll_thread.join();
+        }
+    }
+}
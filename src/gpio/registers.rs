@@ -0,0 +1,49 @@
+use std::fmt;
+
+use crate::gpio::{Bias, Error, Level, Mode, Result};
+
+/// Provides register access for a custom GPIO backend.
+///
+/// `Registers` is implemented by types that control the GPIO pins through means other
+/// than the built-in backends, for instance a GPIO controller accessed over a network
+/// connection, or a test double that records every change. A `Gpio` instance using the
+/// custom backend is constructed by [`Gpio::with_registers`]. Pins are retrieved and
+/// configured through the regular [`Pin`], [`InputPin`], [`OutputPin`] and [`IoPin`]
+/// methods, which call the methods below.
+///
+/// Open-drain and open-source outputs, and inverted logic levels, are emulated in
+/// software. Interrupts aren't supported, and return `Err(`[`Error::NotSupported`]`)`.
+///
+/// [`Gpio::with_registers`]: struct.Gpio.html#method.with_registers
+/// [`Pin`]: struct.Pin.html
+/// [`InputPin`]: struct.InputPin.html
+/// [`OutputPin`]: struct.OutputPin.html
+/// [`IoPin`]: struct.IoPin.html
+/// [`Error::NotSupported`]: enum.Error.html#variant.NotSupported
+pub trait Registers: fmt::Debug + Send + Sync {
+    /// Returns the pin's logic level.
+    fn level(&self, pin: u8) -> Level;
+
+    /// Changes the pin's output state.
+    fn set_level(&self, pin: u8, level: Level) -> Result<()>;
+
+    /// Returns the pin's mode.
+    fn mode(&self, pin: u8) -> Mode;
+
+    /// Changes the pin's mode.
+    fn set_mode(&self, pin: u8, mode: Mode) -> Result<()>;
+
+    /// Returns the pin's built-in pull-up/pull-down resistor state.
+    ///
+    /// By default, this returns `Err(`[`Error::NotSupported`]`)`, in which case the
+    /// resistors are disabled when a pin goes out of scope, instead of being reset
+    /// to their original state.
+    ///
+    /// [`Error::NotSupported`]: enum.Error.html#variant.NotSupported
+    fn bias(&self, _pin: u8) -> Result<Bias> {
+        Err(Error::NotSupported)
+    }
+
+    /// Changes the pin's built-in pull-up/pull-down resistor state.
+    fn set_bias(&self, pin: u8, bias: Bias) -> Result<()>;
+}
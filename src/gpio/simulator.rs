@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use crate::gpio::gpiomem::sim::GpioSim;
+use crate::gpio::gpiomem::GpioRegisters;
+use crate::gpio::{Bias, Error, Level, Mode, Result};
+
+/// Controls a simulated GPIO peripheral.
+///
+/// `Simulator`s are retrieved through [`Gpio::simulator`] for `Gpio` instances constructed
+/// by [`Gpio::simulated`]. A `Simulator` acts as the outside world connected to the pins.
+/// It can drive the level of any pin as an external device would, and inspect the
+/// pin's current level, mode and bias as configured through the regular [`Pin`],
+/// [`InputPin`], [`OutputPin`] and [`IoPin`] methods.
+///
+/// Any change in a pin's level is reported as an edge to interrupt triggers configured
+/// on that pin, which allows interrupt handlers to be tested through
+/// [`InputPin::poll_interrupt`], [`InputPin::set_async_interrupt`] and the other
+/// interrupt methods.
+///
+/// [`Gpio::simulator`]: struct.Gpio.html#method.simulator
+/// [`Gpio::simulated`]: struct.Gpio.html#method.simulated
+/// [`Pin`]: struct.Pin.html
+/// [`InputPin`]: struct.InputPin.html
+/// [`OutputPin`]: struct.OutputPin.html
+/// [`IoPin`]: struct.IoPin.html
+/// [`InputPin::poll_interrupt`]: struct.InputPin.html#method.poll_interrupt
+/// [`InputPin::set_async_interrupt`]: struct.InputPin.html#method.set_async_interrupt
+#[derive(Clone, Debug)]
+pub struct Simulator {
+    sim: Arc<GpioSim>,
+}
+
+impl Simulator {
+    pub(crate) fn new(sim: Arc<GpioSim>) -> Simulator {
+        Simulator { sim }
+    }
+
+    fn check_pin(&self, pin: u8) -> Result<()> {
+        if pin >= self.sim.gpio_lines() {
+            Err(Error::PinNotAvailable(pin))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Drives the pin to the specified logic level, as an external device would.
+    ///
+    /// The external level is applied while the pin isn't configured as an output.
+    /// If the level changes, an edge is reported to any interrupt trigger
+    /// configured on the pin.
+    pub fn set_level(&self, pin: u8, level: Level) -> Result<()> {
+        self.check_pin(pin)?;
+        self.sim.set_external_level(pin, Some(level));
+
+        Ok(())
+    }
+
+    /// Stops driving the pin externally.
+    ///
+    /// The pin's level is then determined by its built-in pull-up/pull-down
+    /// resistor. A floating pin reads [`Low`].
+    ///
+    /// [`Low`]: enum.Level.html#variant.Low
+    pub fn clear_level(&self, pin: u8) -> Result<()> {
+        self.check_pin(pin)?;
+        self.sim.set_external_level(pin, None);
+
+        Ok(())
+    }
+
+    /// Returns the pin's current logic level.
+    ///
+    /// For pins configured as an output, this is the output state set through
+    /// [`OutputPin`] or [`IoPin`]. Otherwise, this is the level driven by
+    /// [`set_level`], or the level set by the pin's bias.
+    ///
+    /// [`OutputPin`]: struct.OutputPin.html
+    /// [`IoPin`]: struct.IoPin.html
+    /// [`set_level`]: #method.set_level
+    pub fn level(&self, pin: u8) -> Result<Level> {
+        self.check_pin(pin)?;
+
+        Ok(self.sim.level(pin))
+    }
+
+    /// Returns the pin's current mode.
+    pub fn mode(&self, pin: u8) -> Result<Mode> {
+        self.check_pin(pin)?;
+
+        Ok(self.sim.mode(pin))
+    }
+
+    /// Returns the current state of the pin's built-in pull-up/pull-down resistors.
+    pub fn bias(&self, pin: u8) -> Result<Bias> {
+        self.check_pin(pin)?;
+
+        self.sim.bias(pin)
+    }
+}
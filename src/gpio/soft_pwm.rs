@@ -36,6 +36,8 @@ impl SoftPwm {
     pub(crate) fn new(
         pin: u8,
         gpio_state: Arc<GpioState>,
+        // Inverts the output for pins that are set to active-low in software
+        active_low: bool,
         period: Duration,
         pulse_width: Duration,
     ) -> SoftPwm {
@@ -82,7 +84,11 @@ impl SoftPwm {
             loop {
                 // PWM active
                 if pulse_width_ns > 0 {
-                    gpio_state.gpio_mem.set_high(pin);
+                    if active_low {
+                        gpio_state.gpio_mem.set_low(pin)?;
+                    } else {
+                        gpio_state.gpio_mem.set_high(pin)?;
+                    }
                 }
 
                 // Sleep if we have enough time remaining, while reserving some time
@@ -100,7 +106,11 @@ impl SoftPwm {
                 }
 
                 // PWM inactive
-                gpio_state.gpio_mem.set_low(pin);
+                if active_low {
+                    gpio_state.gpio_mem.set_high(pin)?;
+                } else {
+                    gpio_state.gpio_mem.set_low(pin)?;
+                }
 
                 while let Ok(msg) = receiver.try_recv() {
                     match msg {
@@ -0,0 +1,110 @@
+use std::future::Future;
+use std::os::unix::io::AsRawFd;
+use std::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+
+use crate::gpio::reactor::Reactor;
+use crate::gpio::{Event, InterruptFd, Pin, Result};
+
+/// Stream of interrupt trigger events for a pin.
+///
+/// `InterruptStream`s are constructed by [`InputPin::interrupt_stream`],
+/// [`IoPin::interrupt_stream`] or [`OutputPin::interrupt_stream`]. The interrupt
+/// trigger remains configured until the `InterruptStream` goes out of scope.
+///
+/// Instead of spawning a thread for each pin, all `InterruptStream`s share a single
+/// background thread, which waits for trigger events on the underlying event fds and
+/// wakes the relevant tasks. Events are read on the task that polls the stream, so
+/// `InterruptStream` can be used with any async runtime.
+///
+/// `InterruptStream` implements the [`Stream`] trait from the `futures-core` crate,
+/// so it can be used with the stream combinators provided by `futures` and other
+/// crates. The stream never ends.
+///
+/// [`InputPin::interrupt_stream`]: struct.InputPin.html#method.interrupt_stream
+/// [`IoPin::interrupt_stream`]: struct.IoPin.html#method.interrupt_stream
+/// [`OutputPin::interrupt_stream`]: struct.OutputPin.html#method.interrupt_stream
+/// [`Stream`]: https://docs.rs/futures-core/0.3/futures_core/stream/trait.Stream.html
+#[derive(Debug)]
+pub struct InterruptStream<'a> {
+    // Prevents the pin from being reconfigured while the stream exists
+    #[cfg_attr(not(feature = "hal"), allow(dead_code))]
+    pin: &'a mut Pin,
+    interrupt: InterruptFd,
+    reactor: Arc<Reactor>,
+}
+
+impl<'a> InterruptStream<'a> {
+    pub(crate) fn new(
+        pin: &'a mut Pin,
+        interrupt: InterruptFd,
+        reactor: Arc<Reactor>,
+    ) -> InterruptStream<'a> {
+        InterruptStream {
+            pin,
+            interrupt,
+            reactor,
+        }
+    }
+
+    /// Attempts to retrieve the next trigger event.
+    ///
+    /// Returns `Poll::Ready` if a trigger event is available. Otherwise, returns
+    /// `Poll::Pending`, and arranges for the current task to be woken when the next
+    /// trigger event occurs.
+    pub fn poll_event(&mut self, cx: &mut Context<'_>) -> Poll<Result<Event>> {
+        match self.interrupt.read_event() {
+            Ok(Some(event)) => Poll::Ready(Ok(event)),
+            // If an event arrives in the meantime, the task is woken immediately
+            Ok(None) => match self
+                .reactor
+                .register(self.interrupt.as_raw_fd(), cx.waker())
+            {
+                Ok(()) => Poll::Pending,
+                Err(e) => Poll::Ready(Err(e)),
+            },
+            Err(e) => Poll::Ready(Err(e)),
+        }
+    }
+
+    /// Waits for the next trigger event.
+    pub async fn next_event(&mut self) -> Result<Event> {
+        NextEvent { stream: self }.await
+    }
+
+    // Reads the pin's logic level
+    #[cfg(feature = "hal")]
+    pub(crate) fn read(&self) -> crate::gpio::Level {
+        self.pin.read()
+    }
+}
+
+impl Stream for InterruptStream<'_> {
+    type Item = Result<Event>;
+
+    fn poll_next(mut self: pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_event(cx).map(Some)
+    }
+}
+
+impl Drop for InterruptStream<'_> {
+    fn drop(&mut self) {
+        // The fd needs to be removed from the reactor before it's closed
+        let _ = self.reactor.deregister(self.interrupt.as_raw_fd());
+    }
+}
+
+struct NextEvent<'s, 'a> {
+    stream: &'s mut InterruptStream<'a>,
+}
+
+impl Future for NextEvent<'_, '_> {
+    type Output = Result<Event>;
+
+    fn poll(mut self: pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.stream.poll_event(cx)
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt;
+use std::fs::File;
+use std::os::unix::io::AsRawFd;
+use std::thread;
+use std::time::Duration;
+
+use crate::gpio::epoll::{epoll_event, Epoll, EventFd, EPOLLERR, EPOLLET, EPOLLIN, EPOLLPRI};
+use crate::gpio::ioctl;
+use crate::gpio::{Error, LineInfo, Result};
+
+/// GPIO line change types.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum LineChangeKind {
+    /// The line was requested by a process or a kernel driver.
+    Requested,
+    /// The line was released.
+    Released,
+    /// The line's configuration changed while it was requested.
+    Reconfigured,
+}
+
+impl fmt::Display for LineChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            LineChangeKind::Requested => write!(f, "Requested"),
+            LineChangeKind::Released => write!(f, "Released"),
+            LineChangeKind::Reconfigured => write!(f, "Reconfigured"),
+        }
+    }
+}
+
+/// GPIO line change event.
+///
+/// `LineChange` is returned by [`LineWatcher::poll`], and passed to the callback
+/// specified in [`Gpio::watch_lines_async`].
+///
+/// [`LineWatcher::poll`]: struct.LineWatcher.html#method.poll
+/// [`Gpio::watch_lines_async`]: struct.Gpio.html#method.watch_lines_async
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LineChange {
+    /// Type of change.
+    pub kind: LineChangeKind,
+    /// Line info after the change occurred.
+    pub info: LineInfo,
+    /// Kernel timestamp taken when the change occurred, measured as the elapsed time
+    /// since the system was booted (`CLOCK_MONOTONIC`).
+    pub timestamp: Duration,
+}
+
+impl LineChange {
+    fn new(line_info_changed: &ioctl::LineInfoChanged) -> LineChange {
+        let kind = if line_info_changed.requested() {
+            LineChangeKind::Requested
+        } else if line_info_changed.released() {
+            LineChangeKind::Released
+        } else {
+            LineChangeKind::Reconfigured
+        };
+
+        LineChange {
+            kind,
+            info: LineInfo::new(line_info_changed.info.offset as u8, &line_info_changed.info),
+            timestamp: Duration::from_nanos(line_info_changed.timestamp_ns),
+        }
+    }
+}
+
+/// Watches GPIO lines for configuration changes.
+///
+/// `LineWatcher` reports any changes to the watched lines made by the current process,
+/// other processes or kernel drivers, which includes requesting, releasing or
+/// reconfiguring a line through the `gpiochip`. Changes made by accessing the GPIO
+/// registers directly aren't reported.
+///
+/// `LineWatcher`s are constructed by [`Gpio::watch_lines`]. The lines are watched until
+/// the `LineWatcher` goes out of scope.
+///
+/// [`Gpio::watch_lines`]: struct.Gpio.html#method.watch_lines
+#[derive(Debug)]
+pub struct LineWatcher {
+    cdev: File,
+    poll: Epoll,
+    pins: Vec<u8>,
+}
+
+impl LineWatcher {
+    pub(crate) fn new(cdev: File, pins: &[u8]) -> Result<LineWatcher> {
+        let mut watched_pins: Vec<u8> = Vec::with_capacity(pins.len());
+
+        for &pin in pins {
+            // Watching the same line twice returns EBUSY
+            if !watched_pins.contains(&pin) {
+                ioctl::LineInfo::watch(cdev.as_raw_fd(), u32::from(pin))?;
+                watched_pins.push(pin);
+            }
+        }
+
+        let poll = Epoll::new()?;
+        poll.add(
+            cdev.as_raw_fd(),
+            cdev.as_raw_fd() as u64,
+            EPOLLIN | EPOLLPRI,
+        )?;
+
+        Ok(LineWatcher {
+            cdev,
+            poll,
+            pins: watched_pins,
+        })
+    }
+
+    /// Returns the GPIO pin numbers of the watched lines.
+    pub fn pins(&self) -> &[u8] {
+        &self.pins
+    }
+
+    /// Blocks until a change is detected on any of the watched lines, or until
+    /// a timeout occurs.
+    ///
+    /// Changes that occurred since the `LineWatcher` was constructed or since the
+    /// previous call to `poll` are queued by the kernel, and returned in order.
+    ///
+    /// Setting `timeout` to `None` disables the timeout, and `poll` blocks until a
+    /// change is detected. Returns `Ok(None)` if a timeout occurred.
+    pub fn poll(&mut self, timeout: Option<Duration>) -> Result<Option<LineChange>> {
+        let mut events = [epoll_event { events: 0, u64: 0 }; 1];
+
+        // No events means a timeout occurred
+        if self.poll.wait(&mut events, timeout)? == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(self.line_change()?))
+    }
+
+    fn line_change(&self) -> Result<LineChange> {
+        let line_info_changed = ioctl::LineInfoChanged::new(self.cdev.as_raw_fd())?;
+
+        Ok(LineChange::new(&line_info_changed))
+    }
+}
+
+impl Drop for LineWatcher {
+    fn drop(&mut self) {
+        for &pin in &self.pins {
+            let _ = ioctl::LineInfo::unwatch(self.cdev.as_raw_fd(), u32::from(pin));
+        }
+    }
+}
+
+/// Watches GPIO lines for configuration changes on a separate thread.
+///
+/// `AsyncLineWatcher`s are constructed by [`Gpio::watch_lines_async`]. The lines are
+/// watched until [`stop`] is called, or the `AsyncLineWatcher` goes out of scope.
+///
+/// [`Gpio::watch_lines_async`]: struct.Gpio.html#method.watch_lines_async
+/// [`stop`]: #method.stop
+#[derive(Debug)]
+pub struct AsyncLineWatcher {
+    poll_thread: Option<thread::JoinHandle<Result<()>>>,
+    tx: EventFd,
+}
+
+impl AsyncLineWatcher {
+    pub(crate) fn new<C>(line_watcher: LineWatcher, mut callback: C) -> Result<AsyncLineWatcher>
+    where
+        C: FnMut(LineChange) + Send + 'static,
+    {
+        let tx = EventFd::new()?;
+        let rx = tx.fd();
+
+        // rx becomes readable when the main thread calls notify()
+        line_watcher
+            .poll
+            .add(rx, rx as u64, EPOLLERR | EPOLLET | EPOLLIN)?;
+
+        let poll_thread = thread::spawn(move || -> Result<()> {
+            let cdev_fd = line_watcher.cdev.as_raw_fd();
+
+            let mut events = [epoll_event { events: 0, u64: 0 }; 2];
+            loop {
+                let num_events = line_watcher.poll.wait(&mut events, None)?;
+                for event in &events[0..num_events] {
+                    let fd = event.u64 as i32;
+                    if fd == rx {
+                        return Ok(()); // The main thread asked us to stop
+                    } else if fd == cdev_fd {
+                        callback(line_watcher.line_change()?);
+                    }
+                }
+            }
+        });
+
+        Ok(AsyncLineWatcher {
+            poll_thread: Some(poll_thread),
+            tx,
+        })
+    }
+
+    /// Stops watching the lines, and waits for the watcher thread to exit.
+    ///
+    /// If the watcher thread returned an error, or panicked, the error is returned here.
+    pub fn stop(&mut self) -> Result<()> {
+        self.tx.notify()?;
+
+        if let Some(poll_thread) = self.poll_thread.take() {
+            match poll_thread.join() {
+                Ok(r) => return r,
+                Err(_) => return Err(Error::ThreadPanic),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for AsyncLineWatcher {
+    fn drop(&mut self) {
+        // Don't wait for the poll thread to exit if the main thread is panicking,
+        // because we could potentially block indefinitely while unwinding if the
+        // poll thread is executing a callback that doesn't return.
+        if !thread::panicking() {
+            let _ = self.stop();
+        }
+    }
+}
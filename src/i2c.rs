@@ -93,6 +93,42 @@
 //! A possible workaround for slave devices that require clock stretching at other points during the transfer is
 //! to use a bit-banged software I2C bus by configuring the `i2c-gpio` device tree overlay as described in `/boot/overlays/README`.
 //!
+//! ## Testing
+//!
+//! [`I2c::with_mock`] constructs an `I2c` instance that runs against a [`Mock`] bus instead
+//! of the I2C peripheral, which allows device drivers to be tested without any hardware.
+//! A `Mock` contains a script of expected [`Transaction`]s, with the outgoing data that
+//! should be sent and the incoming data that's returned to the caller. Any transaction that
+//! deviates from the script returns an [`Error::Mismatch`].
+//!
+//! ## Recording
+//!
+//! [`I2c::start_recording`] writes every I2C and SMBus transaction to a file, until
+//! [`I2c::stop_recording`] is called. Recordings can be loaded with
+//! [`Mock::from_recording`] to replay the bus traffic without any hardware.
+//!
+//! Recordings are plain text files. The first line contains the header
+//! `rppal-recording 1 i2c <start>`, where `<start>` is the time the recording was
+//! started in microseconds since the Unix epoch. Every following line contains a
+//! single transaction, consisting of space-separated fields. The first field is the
+//! number of microseconds elapsed since the start of the recording when the
+//! transaction completed, followed by the transaction name and the fields of the
+//! corresponding [`Transaction`] variant in the order they're declared. Transaction
+//! names are the [`Transaction`] variant names in snake case, such as `write_read`
+//! or `smbus_read_byte`.
+//!
+//! Integers are written in decimal notation, and booleans as `0` or `1`. Data is
+//! written in hexadecimal notation with two digits per byte, or `-` when empty.
+//! Failed transactions are added as comments starting with `#`, which are ignored
+//! when the recording is replayed.
+//!
+//! ```text
+//! rppal-recording 1 i2c 1700000000000000
+//! 1042 write 72 0a
+//! 1388 smbus_read_word 72 5 4660
+//! 2107 write_read 72 0f 1f2e
+//! ```
+//!
 //! ## Troubleshooting
 //!
 //! ### Permission denied
@@ -109,28 +145,39 @@
 //! [`new`]: struct.I2c.html#method.new
 //! [`with_bus`]: struct.I2c.html#method.with_bus
 //! [`set_timeout`]: struct.I2c.html#method.set_timeout
+//! [`I2c::with_mock`]: struct.I2c.html#method.with_mock
+//! [`Mock`]: struct.Mock.html
+//! [`Transaction`]: enum.Transaction.html
+//! [`Error::Mismatch`]: enum.Error.html#variant.Mismatch
+//! [`I2c::start_recording`]: struct.I2c.html#method.start_recording
+//! [`I2c::stop_recording`]: struct.I2c.html#method.stop_recording
+//! [`Mock::from_recording`]: struct.Mock.html#method.from_recording
 
 #![allow(dead_code)]
 
 use std::error;
 use std::fmt;
-use std::fs::{File, OpenOptions};
 use std::io;
-use std::io::{Read, Write};
 use std::marker::PhantomData;
-use std::os::unix::io::AsRawFd;
+use std::path::Path;
 use std::result;
 
-use libc::c_ulong;
-
 use crate::system;
 use crate::system::{DeviceInfo, Model};
 
 #[cfg(feature = "hal")]
 mod hal;
 mod ioctl;
+mod mock;
+mod record;
+mod transport;
 
 pub use self::ioctl::Capabilities;
+pub use self::mock::{Mock, Transaction};
+
+use self::mock::MockTransport;
+use self::record::RecordingTransport;
+use self::transport::{I2cDev, Transport};
 
 /// Errors that can occur when accessing the I2C peripheral.
 #[derive(Debug)]
@@ -157,6 +204,13 @@ pub enum Error {
     /// doesn't provide any of the common user-accessible system files
     /// that are used to identify the model and SoC.
     UnknownModel,
+    /// Transaction mismatch.
+    ///
+    /// The transaction doesn't match the script of a [`Mock`]. Contains a
+    /// description of the expected and the actual transaction.
+    ///
+    /// [`Mock`]: struct.Mock.html
+    Mismatch(String),
 }
 
 impl fmt::Display for Error {
@@ -166,6 +220,7 @@ impl fmt::Display for Error {
             Error::InvalidSlaveAddress(address) => write!(f, "Invalid slave address: {}", address),
             Error::FeatureNotSupported => write!(f, "I2C/SMBus feature not supported"),
             Error::UnknownModel => write!(f, "Unknown Raspberry Pi model"),
+            Error::Mismatch(ref description) => write!(f, "Transaction mismatch: {}", description),
         }
     }
 }
@@ -212,7 +267,7 @@ pub type Result<T> = result::Result<T, Error>;
 pub struct I2c {
     bus: u8,
     funcs: Capabilities,
-    i2cdev: File,
+    transport: RecordingTransport,
     addr_10bit: bool,
     address: u16,
     // The not_sync field is a workaround to force !Sync. I2c isn't safe for
@@ -257,27 +312,40 @@ impl I2c {
     pub fn with_bus(bus: u8) -> Result<I2c> {
         // bus is a u8, because any 8-bit bus ID could potentially
         // be configured for bit banging I2C using i2c-gpio.
-        let i2cdev = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .open(format!("/dev/i2c-{}", bus))?;
+        I2c::with_transport(bus, Box::new(I2cDev::open(bus)?))
+    }
 
-        let capabilities = ioctl::funcs(i2cdev.as_raw_fd())?;
+    /// Constructs a new `I2c` that runs against a scripted [`Mock`] bus instead
+    /// of an I2C peripheral.
+    ///
+    /// Every transaction is checked against the next expected transaction in the
+    /// script. More information can be found in the [`Mock`] documentation.
+    ///
+    /// [`bus`] returns `0` for mocked instances.
+    ///
+    /// [`Mock`]: struct.Mock.html
+    /// [`bus`]: #method.bus
+    pub fn with_mock(mock: &Mock) -> Result<I2c> {
+        I2c::with_transport(0, Box::new(MockTransport::new(mock.clone())))
+    }
+
+    fn with_transport(bus: u8, mut transport: Box<dyn Transport + Send>) -> Result<I2c> {
+        let capabilities = transport.funcs()?;
 
         // Disable 10-bit addressing if it's supported
         if capabilities.addr_10bit() {
-            ioctl::set_addr_10bit(i2cdev.as_raw_fd(), 0)?;
+            transport.set_addr_10bit(false)?;
         }
 
         // Disable PEC if it's supported
         if capabilities.smbus_pec() {
-            ioctl::set_pec(i2cdev.as_raw_fd(), 0)?;
+            transport.set_pec(false)?;
         }
 
         Ok(I2c {
             bus,
             funcs: capabilities,
-            i2cdev,
+            transport: RecordingTransport::new(transport),
             addr_10bit: false,
             address: 0,
             not_sync: PhantomData,
@@ -301,18 +369,7 @@ impl I2c {
 
     /// Returns the clock frequency in hertz (Hz).
     pub fn clock_speed(&self) -> Result<u32> {
-        let mut buffer = [0u8; 4];
-
-        File::open(format!(
-            "/sys/class/i2c-adapter/i2c-{}/of_node/clock-frequency",
-            self.bus
-        ))?
-        .read_exact(&mut buffer)?;
-
-        Ok(u32::from(buffer[3])
-            | (u32::from(buffer[2]) << 8)
-            | (u32::from(buffer[1]) << 16)
-            | (u32::from(buffer[0]) << 24))
+        self.transport.clock_speed()
     }
 
     /// Sets a 7-bit or 10-bit slave address.
@@ -335,7 +392,7 @@ impl I2c {
             return Err(Error::InvalidSlaveAddress(slave_address));
         }
 
-        ioctl::set_slave_address(self.i2cdev.as_raw_fd(), c_ulong::from(slave_address))?;
+        self.transport.set_slave_address(slave_address)?;
 
         self.address = slave_address;
 
@@ -351,14 +408,14 @@ impl I2c {
     pub fn set_timeout(&self, timeout: u32) -> Result<()> {
         // Contrary to the i2cdev documentation, this seems to
         // be used as a timeout for (part of?) the I2C transaction.
-        ioctl::set_timeout(self.i2cdev.as_raw_fd(), timeout as c_ulong)?;
+        self.transport.set_timeout(timeout)?;
 
         Ok(())
     }
 
     fn set_retries(&self, retries: u32) -> Result<()> {
         // Set to private. While i2cdev implements retries, the underlying drivers don't.
-        ioctl::set_retries(self.i2cdev.as_raw_fd(), retries as c_ulong)?;
+        self.transport.set_retries(retries)?;
 
         Ok(())
     }
@@ -376,7 +433,7 @@ impl I2c {
             return Err(Error::FeatureNotSupported);
         }
 
-        ioctl::set_addr_10bit(self.i2cdev.as_raw_fd(), addr_10bit as c_ulong)?;
+        self.transport.set_addr_10bit(addr_10bit)?;
 
         self.addr_10bit = addr_10bit;
 
@@ -391,7 +448,7 @@ impl I2c {
     ///
     /// Returns how many bytes were read.
     pub fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
-        Ok(self.i2cdev.read(buffer)?)
+        self.transport.read(buffer)
     }
 
     /// Sends the outgoing data contained in `buffer` to the slave device.
@@ -400,7 +457,7 @@ impl I2c {
     ///
     /// Returns how many bytes were written.
     pub fn write(&mut self, buffer: &[u8]) -> Result<usize> {
-        Ok(self.i2cdev.write(buffer)?)
+        self.transport.write(buffer)
     }
 
     /// Sends the outgoing data contained in `write_buffer` to the slave device, and
@@ -419,15 +476,13 @@ impl I2c {
     /// [`write`]: #method.write
     /// [`read`]: #method.read
     pub fn write_read(&self, write_buffer: &[u8], read_buffer: &mut [u8]) -> Result<()> {
-        ioctl::i2c_write_read(
-            self.i2cdev.as_raw_fd(),
-            self.address,
-            self.addr_10bit,
-            write_buffer,
-            read_buffer,
-        )?;
+        // 0 length buffers may cause issues
+        if write_buffer.is_empty() || read_buffer.is_empty() {
+            return Ok(());
+        }
 
-        Ok(())
+        self.transport
+            .write_read(self.address, self.addr_10bit, write_buffer, read_buffer)
     }
 
     /// Sends an 8-bit `command`, and then fills a multi-byte `buffer` with
@@ -445,9 +500,7 @@ impl I2c {
     ///
     /// [`smbus_block_read`]: #method.smbus_block_read
     pub fn block_read(&self, command: u8, buffer: &mut [u8]) -> Result<()> {
-        ioctl::i2c_block_read(self.i2cdev.as_raw_fd(), command, buffer)?;
-
-        Ok(())
+        self.transport.block_read(command, buffer)
     }
 
     /// Sends an 8-bit `command` followed by a multi-byte `buffer`.
@@ -464,9 +517,7 @@ impl I2c {
     ///
     /// [`smbus_block_write`]: #method.smbus_block_write
     pub fn block_write(&self, command: u8, buffer: &[u8]) -> Result<()> {
-        ioctl::i2c_block_write(self.i2cdev.as_raw_fd(), command, buffer)?;
-
-        Ok(())
+        self.transport.block_write(command, buffer)
     }
 
     // Note: smbus_read/write_32/64 could theoretically be emulated using block_read/write
@@ -476,25 +527,21 @@ impl I2c {
     ///
     /// Sequence: START → Address + Command Bit → STOP
     pub fn smbus_quick_command(&self, command: bool) -> Result<()> {
-        ioctl::smbus_quick_command(self.i2cdev.as_raw_fd(), command)?;
-
-        Ok(())
+        self.transport.smbus_quick_command(command)
     }
 
     /// Receives an 8-bit value.
     ///
     /// Sequence: START → Address + Read Bit → Incoming Byte → STOP
     pub fn smbus_receive_byte(&self) -> Result<u8> {
-        Ok(ioctl::smbus_receive_byte(self.i2cdev.as_raw_fd())?)
+        self.transport.smbus_receive_byte()
     }
 
     /// Sends an 8-bit `value`.
     ///
     /// Sequence: START → Address + Write Bit → Outgoing Byte → STOP
     pub fn smbus_send_byte(&self, value: u8) -> Result<()> {
-        ioctl::smbus_send_byte(self.i2cdev.as_raw_fd(), value)?;
-
-        Ok(())
+        self.transport.smbus_send_byte(value)
     }
 
     /// Sends an 8-bit `command`, and receives an 8-bit value.
@@ -502,16 +549,14 @@ impl I2c {
     /// Sequence: START → Address + Write Bit → Command → Repeated START
     /// → Address + Read Bit → Incoming Byte → STOP
     pub fn smbus_read_byte(&self, command: u8) -> Result<u8> {
-        Ok(ioctl::smbus_read_byte(self.i2cdev.as_raw_fd(), command)?)
+        self.transport.smbus_read_byte(command)
     }
 
     /// Sends an 8-bit `command` and an 8-bit `value`.
     ///
     /// Sequence: START → Address + Write Bit → Command → Outgoing Byte → STOP
     pub fn smbus_write_byte(&self, command: u8, value: u8) -> Result<()> {
-        ioctl::smbus_write_byte(self.i2cdev.as_raw_fd(), command, value)?;
-
-        Ok(())
+        self.transport.smbus_write_byte(command, value)
     }
 
     /// Sends an 8-bit `command`, and receives a 16-bit value.
@@ -526,7 +571,7 @@ impl I2c {
     ///
     /// [`smbus_read_word_swapped`]: #method.smbus_read_word_swapped
     pub fn smbus_read_word(&self, command: u8) -> Result<u16> {
-        Ok(ioctl::smbus_read_word(self.i2cdev.as_raw_fd(), command)?)
+        self.transport.smbus_read_word(command)
     }
 
     /// Sends an 8-bit `command`, and receives a 16-bit `value` in a non-standard swapped byte order.
@@ -540,7 +585,7 @@ impl I2c {
     ///
     /// [`smbus_read_word`]: #method.smbus_read_word
     pub fn smbus_read_word_swapped(&self, command: u8) -> Result<u16> {
-        let value = ioctl::smbus_read_word(self.i2cdev.as_raw_fd(), command)?;
+        let value = self.transport.smbus_read_word(command)?;
 
         Ok(((value & 0xFF00) >> 8) | ((value & 0xFF) << 8))
     }
@@ -556,9 +601,7 @@ impl I2c {
     ///
     /// [`smbus_write_word_swapped`]: #method.smbus_write_word_swapped
     pub fn smbus_write_word(&self, command: u8, value: u16) -> Result<()> {
-        ioctl::smbus_write_word(self.i2cdev.as_raw_fd(), command, value)?;
-
-        Ok(())
+        self.transport.smbus_write_word(command, value)
     }
 
     /// Sends an 8-bit `command` and a 16-bit `value` in a non-standard swapped byte order.
@@ -570,13 +613,8 @@ impl I2c {
     ///
     /// [`smbus_write_word`]: #method.smbus_write_word
     pub fn smbus_write_word_swapped(&self, command: u8, value: u16) -> Result<()> {
-        ioctl::smbus_write_word(
-            self.i2cdev.as_raw_fd(),
-            command,
-            ((value & 0xFF00) >> 8) | ((value & 0xFF) << 8),
-        )?;
-
-        Ok(())
+        self.transport
+            .smbus_write_word(command, ((value & 0xFF00) >> 8) | ((value & 0xFF) << 8))
     }
 
     /// Sends an 8-bit `command` and a 16-bit `value`, and then receives a 16-bit value in response.
@@ -592,11 +630,7 @@ impl I2c {
     ///
     /// [`smbus_process_call_swapped`]: #method.smbus_process_call_swapped
     pub fn smbus_process_call(&self, command: u8, value: u16) -> Result<u16> {
-        Ok(ioctl::smbus_process_call(
-            self.i2cdev.as_raw_fd(),
-            command,
-            value,
-        )?)
+        self.transport.smbus_process_call(command, value)
     }
 
     /// Sends an 8-bit `command` and a 16-bit `value`, and then receives a 16-bit value in response, in
@@ -612,11 +646,9 @@ impl I2c {
     ///
     /// [`smbus_process_call`]: #method.smbus_process_call
     pub fn smbus_process_call_swapped(&self, command: u8, value: u16) -> Result<u16> {
-        let response = ioctl::smbus_process_call(
-            self.i2cdev.as_raw_fd(),
-            command,
-            ((value & 0xFF00) >> 8) | ((value & 0xFF) << 8),
-        )?;
+        let response = self
+            .transport
+            .smbus_process_call(command, ((value & 0xFF00) >> 8) | ((value & 0xFF) << 8))?;
 
         Ok(((response & 0xFF00) >> 8) | ((response & 0xFF) << 8))
     }
@@ -647,11 +679,7 @@ impl I2c {
             return Err(Error::FeatureNotSupported);
         }
 
-        Ok(ioctl::smbus_block_read(
-            self.i2cdev.as_raw_fd(),
-            command,
-            buffer,
-        )?)
+        self.transport.smbus_block_read(command, buffer)
     }
 
     /// Sends an 8-bit `command` and an 8-bit byte count along with a multi-byte `buffer`.
@@ -662,9 +690,7 @@ impl I2c {
     /// Sequence: START → Address + Write Bit → Command → Outgoing Byte Count
     /// → Outgoing Bytes → STOP
     pub fn smbus_block_write(&self, command: u8, buffer: &[u8]) -> Result<()> {
-        ioctl::smbus_block_write(self.i2cdev.as_raw_fd(), command, buffer)?;
-
-        Ok(())
+        self.transport.smbus_block_write(command, buffer)
     }
 
     /// Enables or disables SMBus Packet Error Checking.
@@ -676,9 +702,26 @@ impl I2c {
     ///
     /// By default, `pec` is set to `false`.
     pub fn set_smbus_pec(&self, pec: bool) -> Result<()> {
-        ioctl::set_pec(self.i2cdev.as_raw_fd(), pec as c_ulong)?;
+        self.transport.set_pec(pec)
+    }
 
-        Ok(())
+    /// Starts recording all I2C and SMBus transactions to the specified file.
+    ///
+    /// If the file already exists, it's overwritten. Any active recording is
+    /// stopped first. The recording can be replayed using [`Mock::from_recording`].
+    /// More information on the file format can be found [here].
+    ///
+    /// [`Mock::from_recording`]: struct.Mock.html#method.from_recording
+    /// [here]: index.html#recording
+    pub fn start_recording<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        Ok(self.transport.start(path.as_ref())?)
+    }
+
+    /// Stops the active recording, if any.
+    ///
+    /// Returns an error if any transaction couldn't be written to the file.
+    pub fn stop_recording(&mut self) -> Result<()> {
+        Ok(self.transport.stop()?)
     }
 }
 
@@ -52,6 +52,11 @@ impl Capabilities {
         Capabilities { funcs }
     }
 
+    // Used by transports that aren't limited by any underlying drivers
+    pub(crate) fn all() -> Capabilities {
+        Capabilities::new(c_ulong::MAX)
+    }
+
     pub(crate) fn i2c(self) -> bool {
         (self.funcs & FUNC_I2C) > 0
     }
@@ -0,0 +1,573 @@
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::i2c::ioctl::Capabilities;
+use crate::i2c::record;
+use crate::i2c::transport::Transport;
+use crate::i2c::{Error, Result};
+use crate::mock::Script;
+
+// Maximum bytes per block transfer
+pub(crate) const BLOCK_MAX: usize = 32;
+
+/// An I2C or SMBus transaction expected by a [`Mock`].
+///
+/// `address` contains the slave address that was set through
+/// [`I2c::set_slave_address`] at the time of the transaction. For write
+/// transactions, the outgoing data has to match exactly. For read transactions,
+/// the incoming data is returned to the caller.
+///
+/// 16-bit values are listed in the SMBus byte order, before the bytes are swapped
+/// by any of the `_swapped` methods.
+///
+/// [`Mock`]: struct.Mock.html
+/// [`I2c::set_slave_address`]: struct.I2c.html#method.set_slave_address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transaction {
+    /// [`I2c::read`]. `data` needs to contain as many bytes as the read buffer.
+    ///
+    /// [`I2c::read`]: struct.I2c.html#method.read
+    Read { address: u16, data: Vec<u8> },
+    /// [`I2c::write`].
+    ///
+    /// [`I2c::write`]: struct.I2c.html#method.write
+    Write { address: u16, data: Vec<u8> },
+    /// [`I2c::write_read`]. `read` needs to contain as many bytes as the read buffer.
+    ///
+    /// [`I2c::write_read`]: struct.I2c.html#method.write_read
+    WriteRead {
+        address: u16,
+        write: Vec<u8>,
+        read: Vec<u8>,
+    },
+    /// [`I2c::block_read`]. `data` needs to contain as many bytes as the read buffer,
+    /// up to a maximum of 32 bytes.
+    ///
+    /// [`I2c::block_read`]: struct.I2c.html#method.block_read
+    BlockRead {
+        address: u16,
+        command: u8,
+        data: Vec<u8>,
+    },
+    /// [`I2c::block_write`]. `data` contains up to 32 bytes.
+    ///
+    /// [`I2c::block_write`]: struct.I2c.html#method.block_write
+    BlockWrite {
+        address: u16,
+        command: u8,
+        data: Vec<u8>,
+    },
+    /// [`I2c::smbus_quick_command`].
+    ///
+    /// [`I2c::smbus_quick_command`]: struct.I2c.html#method.smbus_quick_command
+    SmbusQuickCommand { address: u16, command: bool },
+    /// [`I2c::smbus_receive_byte`].
+    ///
+    /// [`I2c::smbus_receive_byte`]: struct.I2c.html#method.smbus_receive_byte
+    SmbusReceiveByte { address: u16, value: u8 },
+    /// [`I2c::smbus_send_byte`].
+    ///
+    /// [`I2c::smbus_send_byte`]: struct.I2c.html#method.smbus_send_byte
+    SmbusSendByte { address: u16, value: u8 },
+    /// [`I2c::smbus_read_byte`].
+    ///
+    /// [`I2c::smbus_read_byte`]: struct.I2c.html#method.smbus_read_byte
+    SmbusReadByte {
+        address: u16,
+        command: u8,
+        value: u8,
+    },
+    /// [`I2c::smbus_write_byte`].
+    ///
+    /// [`I2c::smbus_write_byte`]: struct.I2c.html#method.smbus_write_byte
+    SmbusWriteByte {
+        address: u16,
+        command: u8,
+        value: u8,
+    },
+    /// [`I2c::smbus_read_word`] or [`I2c::smbus_read_word_swapped`].
+    ///
+    /// [`I2c::smbus_read_word`]: struct.I2c.html#method.smbus_read_word
+    /// [`I2c::smbus_read_word_swapped`]: struct.I2c.html#method.smbus_read_word_swapped
+    SmbusReadWord {
+        address: u16,
+        command: u8,
+        value: u16,
+    },
+    /// [`I2c::smbus_write_word`] or [`I2c::smbus_write_word_swapped`].
+    ///
+    /// [`I2c::smbus_write_word`]: struct.I2c.html#method.smbus_write_word
+    /// [`I2c::smbus_write_word_swapped`]: struct.I2c.html#method.smbus_write_word_swapped
+    SmbusWriteWord {
+        address: u16,
+        command: u8,
+        value: u16,
+    },
+    /// [`I2c::smbus_process_call`] or [`I2c::smbus_process_call_swapped`].
+    ///
+    /// [`I2c::smbus_process_call`]: struct.I2c.html#method.smbus_process_call
+    /// [`I2c::smbus_process_call_swapped`]: struct.I2c.html#method.smbus_process_call_swapped
+    SmbusProcessCall {
+        address: u16,
+        command: u8,
+        value: u16,
+        response: u16,
+    },
+    /// [`I2c::smbus_block_read`]. `data` contains up to 32 bytes, and is truncated
+    /// if it doesn't fit in the read buffer.
+    ///
+    /// [`I2c::smbus_block_read`]: struct.I2c.html#method.smbus_block_read
+    SmbusBlockRead {
+        address: u16,
+        command: u8,
+        data: Vec<u8>,
+    },
+    /// [`I2c::smbus_block_write`]. `data` contains up to 32 bytes.
+    ///
+    /// [`I2c::smbus_block_write`]: struct.I2c.html#method.smbus_block_write
+    SmbusBlockWrite {
+        address: u16,
+        command: u8,
+        data: Vec<u8>,
+    },
+}
+
+/// A scripted I2C bus, used to test code that depends on [`I2c`] without
+/// accessing any hardware.
+///
+/// `Mock` contains a sequence of expected [`Transaction`]s. An `I2c` instance
+/// constructed by [`I2c::with_mock`] compares every transaction to the next
+/// expected transaction in the script. Matching read transactions return the
+/// scripted incoming data. If a transaction doesn't match, or the script has
+/// already been completed, the `I2c` method returns
+/// `Err(`[`Error::Mismatch`]`)`, which describes both the expected and the
+/// actual transaction.
+///
+/// Configuration methods such as [`I2c::set_slave_address`] and [`I2c::set_timeout`]
+/// aren't part of the script. All I2C and SMBus features are reported as
+/// available by [`I2c::capabilities`]. [`I2c::clock_speed`] returns
+/// `Err(`[`Error::FeatureNotSupported`]`)`.
+///
+/// `Mock` can be cloned to keep a handle to the script after it's been passed to
+/// `I2c`. Call [`done`] at the end of a test to verify every expected transaction
+/// was completed.
+///
+/// [`I2c`]: struct.I2c.html
+/// [`Transaction`]: enum.Transaction.html
+/// [`I2c::with_mock`]: struct.I2c.html#method.with_mock
+/// [`Error::Mismatch`]: enum.Error.html#variant.Mismatch
+/// [`I2c::set_slave_address`]: struct.I2c.html#method.set_slave_address
+/// [`I2c::set_timeout`]: struct.I2c.html#method.set_timeout
+/// [`I2c::capabilities`]: struct.I2c.html#method.capabilities
+/// [`I2c::clock_speed`]: struct.I2c.html#method.clock_speed
+/// [`Error::FeatureNotSupported`]: enum.Error.html#variant.FeatureNotSupported
+/// [`done`]: #method.done
+#[derive(Clone, Debug)]
+pub struct Mock {
+    script: Arc<Mutex<Script<Transaction>>>,
+    // Set for mocks constructed from a recording, which stores short reads and
+    // writes with the number of bytes that were actually transferred
+    replay: bool,
+}
+
+impl Mock {
+    /// Constructs a new `Mock` that expects the specified sequence of transactions.
+    pub fn new<I>(transactions: I) -> Mock
+    where
+        I: IntoIterator<Item = Transaction>,
+    {
+        Mock {
+            script: Arc::new(Mutex::new(Script::new(transactions))),
+            replay: false,
+        }
+    }
+
+    /// Constructs a new `Mock` that expects the transactions stored in a recording.
+    ///
+    /// Recordings are created by [`I2c::start_recording`]. Timestamps and failed
+    /// transactions are ignored. Reads and writes that transferred fewer bytes than
+    /// requested match any buffer that's at least as long as the recorded data, and
+    /// return the recorded length.
+    ///
+    /// Returns `Err(`[`Error::Io`]`)` if the file can't be read or isn't a valid
+    /// I2C recording.
+    ///
+    /// [`I2c::start_recording`]: struct.I2c.html#method.start_recording
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub fn from_recording<P: AsRef<Path>>(path: P) -> Result<Mock> {
+        Ok(Mock {
+            replay: true,
+            ..Mock::new(record::parse(path.as_ref())?)
+        })
+    }
+
+    /// Appends a transaction to the end of the script.
+    pub fn expect(&self, transaction: Transaction) {
+        self.script.lock().unwrap().push(transaction);
+    }
+
+    /// Verifies all expected transactions have been completed.
+    ///
+    /// Returns `Err(`[`Error::Mismatch`]`)` if any transaction didn't match the
+    /// script, or if any expected transactions remain.
+    ///
+    /// [`Error::Mismatch`]: enum.Error.html#variant.Mismatch
+    pub fn done(&self) -> Result<()> {
+        self.script.lock().unwrap().done().map_err(Error::Mismatch)
+    }
+
+    fn next<R, F>(&self, actual: fmt::Arguments<'_>, check: F) -> Result<R>
+    where
+        F: FnOnce(&Transaction) -> Option<R>,
+    {
+        self.script
+            .lock()
+            .unwrap()
+            .next(actual, check)
+            .map_err(Error::Mismatch)
+    }
+}
+
+// Transport used by an I2c instance constructed with I2c::with_mock. Keeps
+// track of the slave address, which is included in every transaction.
+#[derive(Debug)]
+pub(crate) struct MockTransport {
+    mock: Mock,
+    address: u16,
+}
+
+impl MockTransport {
+    pub(crate) fn new(mock: Mock) -> MockTransport {
+        MockTransport { mock, address: 0 }
+    }
+}
+
+impl Transport for MockTransport {
+    fn funcs(&self) -> Result<Capabilities> {
+        Ok(Capabilities::all())
+    }
+
+    fn clock_speed(&self) -> Result<u32> {
+        Err(Error::FeatureNotSupported)
+    }
+
+    fn set_slave_address(&mut self, address: u16) -> Result<()> {
+        self.address = address;
+
+        Ok(())
+    }
+
+    fn set_addr_10bit(&mut self, _addr_10bit: bool) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_pec(&self, _pec: bool) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_timeout(&self, _timeout: u32) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_retries(&self, _retries: u32) -> Result<()> {
+        Ok(())
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        let address = self.address;
+        let len = buffer.len();
+        let replay = self.mock.replay;
+
+        self.mock.next(
+            format_args!("Read {{ address: {}, len: {} }}", address, len),
+            |expected| match *expected {
+                Transaction::Read {
+                    address: a,
+                    ref data,
+                } if a == address && (data.len() == len || (replay && data.len() < len)) => {
+                    buffer[..data.len()].copy_from_slice(data);
+                    Some(data.len())
+                }
+                _ => None,
+            },
+        )
+    }
+
+    fn write(&mut self, buffer: &[u8]) -> Result<usize> {
+        let address = self.address;
+        let replay = self.mock.replay;
+
+        self.mock.next(
+            format_args!("Write {{ address: {}, data: {:?} }}", address, buffer),
+            |expected| match *expected {
+                Transaction::Write {
+                    address: a,
+                    ref data,
+                } if a == address
+                    && (data[..] == *buffer || (replay && buffer.starts_with(data))) =>
+                {
+                    Some(data.len())
+                }
+                _ => None,
+            },
+        )
+    }
+
+    fn write_read(
+        &self,
+        address: u16,
+        _addr_10bit: bool,
+        write_buffer: &[u8],
+        read_buffer: &mut [u8],
+    ) -> Result<()> {
+        let len = read_buffer.len();
+
+        self.mock.next(
+            format_args!(
+                "WriteRead {{ address: {}, write: {:?}, len: {} }}",
+                address, write_buffer, len
+            ),
+            |expected| match *expected {
+                Transaction::WriteRead {
+                    address: a,
+                    ref write,
+                    ref read,
+                } if a == address && write[..] == *write_buffer && read.len() == len => {
+                    read_buffer.copy_from_slice(read);
+                    Some(())
+                }
+                _ => None,
+            },
+        )
+    }
+
+    fn block_read(&self, command: u8, buffer: &mut [u8]) -> Result<()> {
+        let address = self.address;
+        let len = buffer.len().min(BLOCK_MAX);
+
+        self.mock.next(
+            format_args!(
+                "BlockRead {{ address: {}, command: {}, len: {} }}",
+                address, command, len
+            ),
+            |expected| match *expected {
+                Transaction::BlockRead {
+                    address: a,
+                    command: c,
+                    ref data,
+                } if a == address && c == command && data.len() == len => {
+                    buffer[..len].copy_from_slice(data);
+                    Some(())
+                }
+                _ => None,
+            },
+        )
+    }
+
+    fn block_write(&self, command: u8, buffer: &[u8]) -> Result<()> {
+        let address = self.address;
+        let buffer = &buffer[..buffer.len().min(BLOCK_MAX)];
+
+        self.mock.next(
+            format_args!(
+                "BlockWrite {{ address: {}, command: {}, data: {:?} }}",
+                address, command, buffer
+            ),
+            |expected| match *expected {
+                Transaction::BlockWrite {
+                    address: a,
+                    command: c,
+                    ref data,
+                } if a == address && c == command && data[..] == *buffer => Some(()),
+                _ => None,
+            },
+        )
+    }
+
+    fn smbus_quick_command(&self, command: bool) -> Result<()> {
+        let address = self.address;
+
+        self.mock.next(
+            format_args!(
+                "SmbusQuickCommand {{ address: {}, command: {} }}",
+                address, command
+            ),
+            |expected| match *expected {
+                Transaction::SmbusQuickCommand {
+                    address: a,
+                    command: c,
+                } if a == address && c == command => Some(()),
+                _ => None,
+            },
+        )
+    }
+
+    fn smbus_receive_byte(&self) -> Result<u8> {
+        let address = self.address;
+
+        self.mock.next(
+            format_args!("SmbusReceiveByte {{ address: {} }}", address),
+            |expected| match *expected {
+                Transaction::SmbusReceiveByte { address: a, value } if a == address => Some(value),
+                _ => None,
+            },
+        )
+    }
+
+    fn smbus_send_byte(&self, value: u8) -> Result<()> {
+        let address = self.address;
+
+        self.mock.next(
+            format_args!("SmbusSendByte {{ address: {}, value: {} }}", address, value),
+            |expected| match *expected {
+                Transaction::SmbusSendByte {
+                    address: a,
+                    value: v,
+                } if a == address && v == value => Some(()),
+                _ => None,
+            },
+        )
+    }
+
+    fn smbus_read_byte(&self, command: u8) -> Result<u8> {
+        let address = self.address;
+
+        self.mock.next(
+            format_args!(
+                "SmbusReadByte {{ address: {}, command: {} }}",
+                address, command
+            ),
+            |expected| match *expected {
+                Transaction::SmbusReadByte {
+                    address: a,
+                    command: c,
+                    value,
+                } if a == address && c == command => Some(value),
+                _ => None,
+            },
+        )
+    }
+
+    fn smbus_write_byte(&self, command: u8, value: u8) -> Result<()> {
+        let address = self.address;
+
+        self.mock.next(
+            format_args!(
+                "SmbusWriteByte {{ address: {}, command: {}, value: {} }}",
+                address, command, value
+            ),
+            |expected| match *expected {
+                Transaction::SmbusWriteByte {
+                    address: a,
+                    command: c,
+                    value: v,
+                } if a == address && c == command && v == value => Some(()),
+                _ => None,
+            },
+        )
+    }
+
+    fn smbus_read_word(&self, command: u8) -> Result<u16> {
+        let address = self.address;
+
+        self.mock.next(
+            format_args!(
+                "SmbusReadWord {{ address: {}, command: {} }}",
+                address, command
+            ),
+            |expected| match *expected {
+                Transaction::SmbusReadWord {
+                    address: a,
+                    command: c,
+                    value,
+                } if a == address && c == command => Some(value),
+                _ => None,
+            },
+        )
+    }
+
+    fn smbus_write_word(&self, command: u8, value: u16) -> Result<()> {
+        let address = self.address;
+
+        self.mock.next(
+            format_args!(
+                "SmbusWriteWord {{ address: {}, command: {}, value: {} }}",
+                address, command, value
+            ),
+            |expected| match *expected {
+                Transaction::SmbusWriteWord {
+                    address: a,
+                    command: c,
+                    value: v,
+                } if a == address && c == command && v == value => Some(()),
+                _ => None,
+            },
+        )
+    }
+
+    fn smbus_process_call(&self, command: u8, value: u16) -> Result<u16> {
+        let address = self.address;
+
+        self.mock.next(
+            format_args!(
+                "SmbusProcessCall {{ address: {}, command: {}, value: {} }}",
+                address, command, value
+            ),
+            |expected| match *expected {
+                Transaction::SmbusProcessCall {
+                    address: a,
+                    command: c,
+                    value: v,
+                    response,
+                } if a == address && c == command && v == value => Some(response),
+                _ => None,
+            },
+        )
+    }
+
+    fn smbus_block_read(&self, command: u8, buffer: &mut [u8]) -> Result<usize> {
+        let address = self.address;
+
+        self.mock.next(
+            format_args!(
+                "SmbusBlockRead {{ address: {}, command: {} }}",
+                address, command
+            ),
+            |expected| match *expected {
+                Transaction::SmbusBlockRead {
+                    address: a,
+                    command: c,
+                    ref data,
+                } if a == address && c == command => {
+                    let data = &data[..data.len().min(BLOCK_MAX)];
+                    let len = data.len().min(buffer.len());
+                    buffer[..len].copy_from_slice(&data[..len]);
+
+                    Some(data.len())
+                }
+                _ => None,
+            },
+        )
+    }
+
+    fn smbus_block_write(&self, command: u8, buffer: &[u8]) -> Result<()> {
+        let address = self.address;
+        let buffer = &buffer[..buffer.len().min(BLOCK_MAX)];
+
+        self.mock.next(
+            format_args!(
+                "SmbusBlockWrite {{ address: {}, command: {}, data: {:?} }}",
+                address, command, buffer
+            ),
+            |expected| match *expected {
+                Transaction::SmbusBlockWrite {
+                    address: a,
+                    command: c,
+                    ref data,
+                } if a == address && c == command && data[..] == *buffer => Some(()),
+                _ => None,
+            },
+        )
+    }
+}
@@ -0,0 +1,444 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use crate::i2c::ioctl::Capabilities;
+use crate::i2c::mock::{Transaction, BLOCK_MAX};
+use crate::i2c::transport::Transport;
+use crate::i2c::Result;
+use crate::record::{self, Hex, Recording};
+
+const BUS: &str = "i2c";
+
+// Forwards all operations to the underlying transport, and adds every transaction
+// to the active recording, if any.
+#[derive(Debug)]
+pub(crate) struct RecordingTransport {
+    transport: Box<dyn Transport + Send>,
+    recording: RefCell<Option<Recording>>,
+    address: u16,
+}
+
+impl RecordingTransport {
+    pub(crate) fn new(transport: Box<dyn Transport + Send>) -> RecordingTransport {
+        RecordingTransport {
+            transport,
+            recording: RefCell::new(None),
+            address: 0,
+        }
+    }
+
+    pub(crate) fn start(&mut self, path: &Path) -> io::Result<()> {
+        self.stop()?;
+        *self.recording.get_mut() = Some(Recording::create(path, BUS)?);
+
+        Ok(())
+    }
+
+    pub(crate) fn stop(&mut self) -> io::Result<()> {
+        match self.recording.get_mut().take() {
+            Some(recording) => recording.finish(),
+            None => Ok(()),
+        }
+    }
+
+    fn record<T, F>(&self, name: &str, result: &Result<T>, transaction: F)
+    where
+        F: FnOnce(&T) -> Transaction,
+    {
+        if let Some(recording) = self.recording.borrow_mut().as_mut() {
+            match result {
+                Ok(value) => recording.record(&Line(&transaction(value))),
+                Err(e) => recording.record_error(name, e),
+            }
+        }
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn funcs(&self) -> Result<Capabilities> {
+        self.transport.funcs()
+    }
+
+    fn clock_speed(&self) -> Result<u32> {
+        self.transport.clock_speed()
+    }
+
+    fn set_slave_address(&mut self, address: u16) -> Result<()> {
+        self.transport.set_slave_address(address)?;
+        self.address = address;
+
+        Ok(())
+    }
+
+    fn set_addr_10bit(&mut self, addr_10bit: bool) -> Result<()> {
+        self.transport.set_addr_10bit(addr_10bit)
+    }
+
+    fn set_pec(&self, pec: bool) -> Result<()> {
+        self.transport.set_pec(pec)
+    }
+
+    fn set_timeout(&self, timeout: u32) -> Result<()> {
+        self.transport.set_timeout(timeout)
+    }
+
+    fn set_retries(&self, retries: u32) -> Result<()> {
+        self.transport.set_retries(retries)
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        let result = self.transport.read(buffer);
+        self.record("read", &result, |&len| Transaction::Read {
+            address: self.address,
+            data: buffer[..len].to_vec(),
+        });
+
+        result
+    }
+
+    fn write(&mut self, buffer: &[u8]) -> Result<usize> {
+        let result = self.transport.write(buffer);
+        self.record("write", &result, |&len| Transaction::Write {
+            address: self.address,
+            data: buffer[..len].to_vec(),
+        });
+
+        result
+    }
+
+    fn write_read(
+        &self,
+        address: u16,
+        addr_10bit: bool,
+        write_buffer: &[u8],
+        read_buffer: &mut [u8],
+    ) -> Result<()> {
+        let result = self
+            .transport
+            .write_read(address, addr_10bit, write_buffer, read_buffer);
+        self.record("write_read", &result, |_| Transaction::WriteRead {
+            address,
+            write: write_buffer.to_vec(),
+            read: read_buffer.to_vec(),
+        });
+
+        result
+    }
+
+    fn block_read(&self, command: u8, buffer: &mut [u8]) -> Result<()> {
+        let result = self.transport.block_read(command, buffer);
+        // Only the first 32 bytes are transferred
+        self.record("block_read", &result, |_| Transaction::BlockRead {
+            address: self.address,
+            command,
+            data: buffer[..buffer.len().min(BLOCK_MAX)].to_vec(),
+        });
+
+        result
+    }
+
+    fn block_write(&self, command: u8, buffer: &[u8]) -> Result<()> {
+        let result = self.transport.block_write(command, buffer);
+        // Only the first 32 bytes are transferred
+        self.record("block_write", &result, |_| Transaction::BlockWrite {
+            address: self.address,
+            command,
+            data: buffer[..buffer.len().min(BLOCK_MAX)].to_vec(),
+        });
+
+        result
+    }
+
+    fn smbus_quick_command(&self, command: bool) -> Result<()> {
+        let result = self.transport.smbus_quick_command(command);
+        self.record("smbus_quick_command", &result, |_| {
+            Transaction::SmbusQuickCommand {
+                address: self.address,
+                command,
+            }
+        });
+
+        result
+    }
+
+    fn smbus_receive_byte(&self) -> Result<u8> {
+        let result = self.transport.smbus_receive_byte();
+        self.record("smbus_receive_byte", &result, |&value| {
+            Transaction::SmbusReceiveByte {
+                address: self.address,
+                value,
+            }
+        });
+
+        result
+    }
+
+    fn smbus_send_byte(&self, value: u8) -> Result<()> {
+        let result = self.transport.smbus_send_byte(value);
+        self.record("smbus_send_byte", &result, |_| Transaction::SmbusSendByte {
+            address: self.address,
+            value,
+        });
+
+        result
+    }
+
+    fn smbus_read_byte(&self, command: u8) -> Result<u8> {
+        let result = self.transport.smbus_read_byte(command);
+        self.record("smbus_read_byte", &result, |&value| {
+            Transaction::SmbusReadByte {
+                address: self.address,
+                command,
+                value,
+            }
+        });
+
+        result
+    }
+
+    fn smbus_write_byte(&self, command: u8, value: u8) -> Result<()> {
+        let result = self.transport.smbus_write_byte(command, value);
+        self.record("smbus_write_byte", &result, |_| {
+            Transaction::SmbusWriteByte {
+                address: self.address,
+                command,
+                value,
+            }
+        });
+
+        result
+    }
+
+    fn smbus_read_word(&self, command: u8) -> Result<u16> {
+        let result = self.transport.smbus_read_word(command);
+        self.record("smbus_read_word", &result, |&value| {
+            Transaction::SmbusReadWord {
+                address: self.address,
+                command,
+                value,
+            }
+        });
+
+        result
+    }
+
+    fn smbus_write_word(&self, command: u8, value: u16) -> Result<()> {
+        let result = self.transport.smbus_write_word(command, value);
+        self.record("smbus_write_word", &result, |_| {
+            Transaction::SmbusWriteWord {
+                address: self.address,
+                command,
+                value,
+            }
+        });
+
+        result
+    }
+
+    fn smbus_process_call(&self, command: u8, value: u16) -> Result<u16> {
+        let result = self.transport.smbus_process_call(command, value);
+        self.record("smbus_process_call", &result, |&response| {
+            Transaction::SmbusProcessCall {
+                address: self.address,
+                command,
+                value,
+                response,
+            }
+        });
+
+        result
+    }
+
+    fn smbus_block_read(&self, command: u8, buffer: &mut [u8]) -> Result<usize> {
+        let result = self.transport.smbus_block_read(command, buffer);
+        // The returned length is reported by the slave device, and can exceed the
+        // size of the buffer, in which case the remaining bytes are discarded
+        self.record("smbus_block_read", &result, |&len| {
+            Transaction::SmbusBlockRead {
+                address: self.address,
+                command,
+                data: buffer[..len.min(buffer.len())].to_vec(),
+            }
+        });
+
+        result
+    }
+
+    fn smbus_block_write(&self, command: u8, buffer: &[u8]) -> Result<()> {
+        let result = self.transport.smbus_block_write(command, buffer);
+        self.record("smbus_block_write", &result, |_| {
+            Transaction::SmbusBlockWrite {
+                address: self.address,
+                command,
+                // Only the first 32 bytes are transferred
+                data: buffer[..buffer.len().min(BLOCK_MAX)].to_vec(),
+            }
+        });
+
+        result
+    }
+}
+
+// Formats a transaction as a single line of a recording, without the timestamp
+struct Line<'a>(&'a Transaction);
+
+impl fmt::Display for Line<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self.0 {
+            Transaction::Read { address, ref data } => {
+                write!(f, "read {} {}", address, Hex(data))
+            }
+            Transaction::Write { address, ref data } => {
+                write!(f, "write {} {}", address, Hex(data))
+            }
+            Transaction::WriteRead {
+                address,
+                ref write,
+                ref read,
+            } => write!(f, "write_read {} {} {}", address, Hex(write), Hex(read)),
+            Transaction::BlockRead {
+                address,
+                command,
+                ref data,
+            } => write!(f, "block_read {} {} {}", address, command, Hex(data)),
+            Transaction::BlockWrite {
+                address,
+                command,
+                ref data,
+            } => write!(f, "block_write {} {} {}", address, command, Hex(data)),
+            Transaction::SmbusQuickCommand { address, command } => {
+                write!(f, "smbus_quick_command {} {}", address, command as u8)
+            }
+            Transaction::SmbusReceiveByte { address, value } => {
+                write!(f, "smbus_receive_byte {} {}", address, value)
+            }
+            Transaction::SmbusSendByte { address, value } => {
+                write!(f, "smbus_send_byte {} {}", address, value)
+            }
+            Transaction::SmbusReadByte {
+                address,
+                command,
+                value,
+            } => write!(f, "smbus_read_byte {} {} {}", address, command, value),
+            Transaction::SmbusWriteByte {
+                address,
+                command,
+                value,
+            } => write!(f, "smbus_write_byte {} {} {}", address, command, value),
+            Transaction::SmbusReadWord {
+                address,
+                command,
+                value,
+            } => write!(f, "smbus_read_word {} {} {}", address, command, value),
+            Transaction::SmbusWriteWord {
+                address,
+                command,
+                value,
+            } => write!(f, "smbus_write_word {} {} {}", address, command, value),
+            Transaction::SmbusProcessCall {
+                address,
+                command,
+                value,
+                response,
+            } => write!(
+                f,
+                "smbus_process_call {} {} {} {}",
+                address, command, value, response
+            ),
+            Transaction::SmbusBlockRead {
+                address,
+                command,
+                ref data,
+            } => write!(f, "smbus_block_read {} {} {}", address, command, Hex(data)),
+            Transaction::SmbusBlockWrite {
+                address,
+                command,
+                ref data,
+            } => write!(f, "smbus_block_write {} {} {}", address, command, Hex(data)),
+        }
+    }
+}
+
+// Reads all transactions from the recording at path
+pub(crate) fn parse(path: &Path) -> io::Result<Vec<Transaction>> {
+    record::parse(path, BUS, |name, fields| {
+        let address = fields.next()?;
+
+        Ok(match name {
+            "read" => Transaction::Read {
+                address,
+                data: fields.next_data()?,
+            },
+            "write" => Transaction::Write {
+                address,
+                data: fields.next_data()?,
+            },
+            "write_read" => Transaction::WriteRead {
+                address,
+                write: fields.next_data()?,
+                read: fields.next_data()?,
+            },
+            "block_read" => Transaction::BlockRead {
+                address,
+                command: fields.next()?,
+                data: fields.next_data()?,
+            },
+            "block_write" => Transaction::BlockWrite {
+                address,
+                command: fields.next()?,
+                data: fields.next_data()?,
+            },
+            "smbus_quick_command" => Transaction::SmbusQuickCommand {
+                address,
+                command: fields.next_bool()?,
+            },
+            "smbus_receive_byte" => Transaction::SmbusReceiveByte {
+                address,
+                value: fields.next()?,
+            },
+            "smbus_send_byte" => Transaction::SmbusSendByte {
+                address,
+                value: fields.next()?,
+            },
+            "smbus_read_byte" => Transaction::SmbusReadByte {
+                address,
+                command: fields.next()?,
+                value: fields.next()?,
+            },
+            "smbus_write_byte" => Transaction::SmbusWriteByte {
+                address,
+                command: fields.next()?,
+                value: fields.next()?,
+            },
+            "smbus_read_word" => Transaction::SmbusReadWord {
+                address,
+                command: fields.next()?,
+                value: fields.next()?,
+            },
+            "smbus_write_word" => Transaction::SmbusWriteWord {
+                address,
+                command: fields.next()?,
+                value: fields.next()?,
+            },
+            "smbus_process_call" => Transaction::SmbusProcessCall {
+                address,
+                command: fields.next()?,
+                value: fields.next()?,
+                response: fields.next()?,
+            },
+            "smbus_block_read" => Transaction::SmbusBlockRead {
+                address,
+                command: fields.next()?,
+                data: fields.next_data()?,
+            },
+            "smbus_block_write" => Transaction::SmbusBlockWrite {
+                address,
+                command: fields.next()?,
+                data: fields.next_data()?,
+            },
+            _ => return Err(record::unknown_transaction(name)),
+        })
+    })
+}
@@ -0,0 +1,215 @@
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::os::unix::io::AsRawFd;
+
+use libc::c_ulong;
+
+use crate::i2c::ioctl::{self, Capabilities};
+use crate::i2c::Result;
+
+// Operations performed by I2c on the underlying bus. I2cDev forwards them to
+// i2cdev, while Mock checks them against a script of expected transactions.
+pub(crate) trait Transport: fmt::Debug {
+    fn funcs(&self) -> Result<Capabilities>;
+    fn clock_speed(&self) -> Result<u32>;
+    fn set_slave_address(&mut self, address: u16) -> Result<()>;
+    fn set_addr_10bit(&mut self, addr_10bit: bool) -> Result<()>;
+    fn set_pec(&self, pec: bool) -> Result<()>;
+    fn set_timeout(&self, timeout: u32) -> Result<()>;
+    fn set_retries(&self, retries: u32) -> Result<()>;
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize>;
+    fn write(&mut self, buffer: &[u8]) -> Result<usize>;
+    fn write_read(
+        &self,
+        address: u16,
+        addr_10bit: bool,
+        write_buffer: &[u8],
+        read_buffer: &mut [u8],
+    ) -> Result<()>;
+    fn block_read(&self, command: u8, buffer: &mut [u8]) -> Result<()>;
+    fn block_write(&self, command: u8, buffer: &[u8]) -> Result<()>;
+    fn smbus_quick_command(&self, command: bool) -> Result<()>;
+    fn smbus_receive_byte(&self) -> Result<u8>;
+    fn smbus_send_byte(&self, value: u8) -> Result<()>;
+    fn smbus_read_byte(&self, command: u8) -> Result<u8>;
+    fn smbus_write_byte(&self, command: u8, value: u8) -> Result<()>;
+    fn smbus_read_word(&self, command: u8) -> Result<u16>;
+    fn smbus_write_word(&self, command: u8, value: u16) -> Result<()>;
+    fn smbus_process_call(&self, command: u8, value: u16) -> Result<u16>;
+    fn smbus_block_read(&self, command: u8, buffer: &mut [u8]) -> Result<usize>;
+    fn smbus_block_write(&self, command: u8, buffer: &[u8]) -> Result<()>;
+}
+
+#[derive(Debug)]
+pub(crate) struct I2cDev {
+    bus: u8,
+    i2cdev: File,
+}
+
+impl I2cDev {
+    pub(crate) fn open(bus: u8) -> Result<I2cDev> {
+        let i2cdev = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(format!("/dev/i2c-{}", bus))?;
+
+        Ok(I2cDev { bus, i2cdev })
+    }
+}
+
+impl Transport for I2cDev {
+    fn funcs(&self) -> Result<Capabilities> {
+        Ok(ioctl::funcs(self.i2cdev.as_raw_fd())?)
+    }
+
+    fn clock_speed(&self) -> Result<u32> {
+        let mut buffer = [0u8; 4];
+
+        File::open(format!(
+            "/sys/class/i2c-adapter/i2c-{}/of_node/clock-frequency",
+            self.bus
+        ))?
+        .read_exact(&mut buffer)?;
+
+        Ok(u32::from(buffer[3])
+            | (u32::from(buffer[2]) << 8)
+            | (u32::from(buffer[1]) << 16)
+            | (u32::from(buffer[0]) << 24))
+    }
+
+    fn set_slave_address(&mut self, address: u16) -> Result<()> {
+        Ok(ioctl::set_slave_address(
+            self.i2cdev.as_raw_fd(),
+            c_ulong::from(address),
+        )?)
+    }
+
+    fn set_addr_10bit(&mut self, addr_10bit: bool) -> Result<()> {
+        Ok(ioctl::set_addr_10bit(
+            self.i2cdev.as_raw_fd(),
+            addr_10bit as c_ulong,
+        )?)
+    }
+
+    fn set_pec(&self, pec: bool) -> Result<()> {
+        Ok(ioctl::set_pec(self.i2cdev.as_raw_fd(), pec as c_ulong)?)
+    }
+
+    fn set_timeout(&self, timeout: u32) -> Result<()> {
+        Ok(ioctl::set_timeout(
+            self.i2cdev.as_raw_fd(),
+            timeout as c_ulong,
+        )?)
+    }
+
+    fn set_retries(&self, retries: u32) -> Result<()> {
+        Ok(ioctl::set_retries(
+            self.i2cdev.as_raw_fd(),
+            retries as c_ulong,
+        )?)
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        Ok(self.i2cdev.read(buffer)?)
+    }
+
+    fn write(&mut self, buffer: &[u8]) -> Result<usize> {
+        Ok(self.i2cdev.write(buffer)?)
+    }
+
+    fn write_read(
+        &self,
+        address: u16,
+        addr_10bit: bool,
+        write_buffer: &[u8],
+        read_buffer: &mut [u8],
+    ) -> Result<()> {
+        Ok(ioctl::i2c_write_read(
+            self.i2cdev.as_raw_fd(),
+            address,
+            addr_10bit,
+            write_buffer,
+            read_buffer,
+        )?)
+    }
+
+    fn block_read(&self, command: u8, buffer: &mut [u8]) -> Result<()> {
+        Ok(ioctl::i2c_block_read(
+            self.i2cdev.as_raw_fd(),
+            command,
+            buffer,
+        )?)
+    }
+
+    fn block_write(&self, command: u8, buffer: &[u8]) -> Result<()> {
+        Ok(ioctl::i2c_block_write(
+            self.i2cdev.as_raw_fd(),
+            command,
+            buffer,
+        )?)
+    }
+
+    fn smbus_quick_command(&self, command: bool) -> Result<()> {
+        Ok(ioctl::smbus_quick_command(
+            self.i2cdev.as_raw_fd(),
+            command,
+        )?)
+    }
+
+    fn smbus_receive_byte(&self) -> Result<u8> {
+        Ok(ioctl::smbus_receive_byte(self.i2cdev.as_raw_fd())?)
+    }
+
+    fn smbus_send_byte(&self, value: u8) -> Result<()> {
+        Ok(ioctl::smbus_send_byte(self.i2cdev.as_raw_fd(), value)?)
+    }
+
+    fn smbus_read_byte(&self, command: u8) -> Result<u8> {
+        Ok(ioctl::smbus_read_byte(self.i2cdev.as_raw_fd(), command)?)
+    }
+
+    fn smbus_write_byte(&self, command: u8, value: u8) -> Result<()> {
+        Ok(ioctl::smbus_write_byte(
+            self.i2cdev.as_raw_fd(),
+            command,
+            value,
+        )?)
+    }
+
+    fn smbus_read_word(&self, command: u8) -> Result<u16> {
+        Ok(ioctl::smbus_read_word(self.i2cdev.as_raw_fd(), command)?)
+    }
+
+    fn smbus_write_word(&self, command: u8, value: u16) -> Result<()> {
+        Ok(ioctl::smbus_write_word(
+            self.i2cdev.as_raw_fd(),
+            command,
+            value,
+        )?)
+    }
+
+    fn smbus_process_call(&self, command: u8, value: u16) -> Result<u16> {
+        Ok(ioctl::smbus_process_call(
+            self.i2cdev.as_raw_fd(),
+            command,
+            value,
+        )?)
+    }
+
+    fn smbus_block_read(&self, command: u8, buffer: &mut [u8]) -> Result<usize> {
+        Ok(ioctl::smbus_block_read(
+            self.i2cdev.as_raw_fd(),
+            command,
+            buffer,
+        )?)
+    }
+
+    fn smbus_block_write(&self, command: u8, buffer: &[u8]) -> Result<()> {
+        Ok(ioctl::smbus_block_write(
+            self.i2cdev.as_raw_fd(),
+            command,
+            buffer,
+        )?)
+    }
+}
@@ -0,0 +1,96 @@
+// Scripted sequence of expected transactions, shared by the mock transports
+// for I2C, SPI and UART.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+#[derive(Debug)]
+pub(crate) struct Script<T> {
+    expected: VecDeque<T>,
+    // Number of transactions that matched the script so far
+    completed: usize,
+    // The first mismatch is stored so it can be reported again by done(), in case
+    // the driver under test discarded the error
+    mismatch: Option<String>,
+}
+
+impl<T: fmt::Debug> Script<T> {
+    pub(crate) fn new<I>(transactions: I) -> Script<T>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        Script {
+            expected: transactions.into_iter().collect(),
+            completed: 0,
+            mismatch: None,
+        }
+    }
+
+    pub(crate) fn push(&mut self, transaction: T) {
+        self.expected.push_back(transaction);
+    }
+
+    // Returns the next expected transaction
+    pub(crate) fn expected(&mut self) -> Option<&mut T> {
+        self.expected.front_mut()
+    }
+
+    // Removes the next expected transaction after it's been completed
+    pub(crate) fn complete(&mut self) {
+        if self.expected.pop_front().is_some() {
+            self.completed += 1;
+        }
+    }
+
+    // Returns a description of the mismatch between the next expected transaction
+    // and the actual transaction
+    pub(crate) fn mismatch(&mut self, actual: fmt::Arguments<'_>) -> String {
+        let mismatch = match self.expected.front() {
+            Some(expected) => format!(
+                "transaction {}: expected {:?}, got {}",
+                self.completed, expected, actual
+            ),
+            None => format!(
+                "transaction {}: expected end of script, got {}",
+                self.completed, actual
+            ),
+        };
+
+        if self.mismatch.is_none() {
+            self.mismatch = Some(mismatch.clone());
+        }
+
+        mismatch
+    }
+
+    // Compares the actual transaction to the next expected transaction. check returns
+    // Some if they match, in which case the expected transaction is completed.
+    pub(crate) fn next<R, F>(&mut self, actual: fmt::Arguments<'_>, check: F) -> Result<R, String>
+    where
+        F: FnOnce(&T) -> Option<R>,
+    {
+        if let Some(result) = self.expected.front().and_then(check) {
+            self.complete();
+
+            Ok(result)
+        } else {
+            Err(self.mismatch(actual))
+        }
+    }
+
+    // Verifies all expected transactions were completed without any mismatches
+    pub(crate) fn done(&self) -> Result<(), String> {
+        if let Some(ref mismatch) = self.mismatch {
+            return Err(mismatch.clone());
+        }
+
+        if let Some(expected) = self.expected.front() {
+            return Err(format!(
+                "transaction {}: expected {:?}, got end of script",
+                self.completed, expected
+            ));
+        }
+
+        Ok(())
+    }
+}
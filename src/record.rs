@@ -0,0 +1,254 @@
+// Recording file format, shared by the recording transports and Mock::from_recording
+// for I2C, SPI and UART.
+//
+// A recording starts with the header "rppal-recording 1 <bus> <start>", where start
+// is the time the recording was started in microseconds since the Unix epoch. Each
+// following line contains a single transaction, starting with the number of
+// microseconds elapsed since the start of the recording, followed by the transaction
+// name and its fields separated by spaces. Failed transactions are written as comments
+// starting with "#", which are skipped during replay.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, LineWriter, Write};
+use std::path::Path;
+use std::str::{FromStr, SplitWhitespace};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+const MAGIC: &str = "rppal-recording";
+const VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub(crate) struct Recording {
+    // Lines are flushed as soon as they're written, so the recording is complete up
+    // to the last transaction if the process is terminated
+    writer: LineWriter<File>,
+    start: Instant,
+    // The first write error is stored and reported by finish(), so a failing recording
+    // doesn't affect the result of the transaction itself
+    error: Option<io::Error>,
+}
+
+impl Recording {
+    pub(crate) fn create(path: &Path, bus: &str) -> io::Result<Recording> {
+        let mut writer = LineWriter::new(File::create(path)?);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_micros())
+            .unwrap_or(0);
+
+        writeln!(writer, "{} {} {} {}", MAGIC, VERSION, bus, timestamp)?;
+
+        Ok(Recording {
+            writer,
+            start: Instant::now(),
+            error: None,
+        })
+    }
+
+    // Adds a completed transaction
+    pub(crate) fn record(&mut self, transaction: &dyn fmt::Display) {
+        let elapsed = self.start.elapsed().as_micros();
+        let result = writeln!(self.writer, "{} {}", elapsed, transaction);
+
+        self.store_error(result);
+    }
+
+    // Adds a failed transaction as a comment
+    pub(crate) fn record_error(&mut self, name: &str, error: &dyn fmt::Display) {
+        let elapsed = self.start.elapsed().as_micros();
+        // Keep multi-line error messages on a single line
+        let error = error.to_string().replace('\n', " ");
+        let result = writeln!(self.writer, "# {} {} failed: {}", elapsed, name, error);
+
+        self.store_error(result);
+    }
+
+    pub(crate) fn finish(mut self) -> io::Result<()> {
+        self.writer.flush()?;
+
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    fn store_error(&mut self, result: io::Result<()>) {
+        if let Err(e) = result {
+            if self.error.is_none() {
+                self.error = Some(e);
+            }
+        }
+    }
+}
+
+// Reads the recording at path, and calls parse for every transaction with the
+// transaction name and its remaining fields
+pub(crate) fn parse<T, F>(path: &Path, bus: &str, mut parse: F) -> io::Result<Vec<T>>
+where
+    F: FnMut(&str, &mut Fields<'_>) -> io::Result<T>,
+{
+    let mut lines = BufReader::new(File::open(path)?).lines();
+
+    let header = lines.next().transpose()?.unwrap_or_default();
+    let mut fields = Fields::new(&header);
+    if fields.next_str().ok() != Some(MAGIC) {
+        return Err(invalid_data(1, "not a recording"));
+    }
+
+    let version: u32 = fields.next().map_err(|e| invalid_data(1, e))?;
+    if version != VERSION {
+        return Err(invalid_data(1, format!("unsupported version {}", version)));
+    }
+
+    match fields.next_str() {
+        Ok(name) if name == bus => (),
+        Ok(name) => {
+            return Err(invalid_data(
+                1,
+                format!("expected {} recording, got {}", bus, name),
+            ))
+        }
+        Err(e) => return Err(invalid_data(1, e)),
+    }
+
+    let mut transactions = Vec::new();
+    for (index, line) in lines.enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = Fields::new(line);
+        let transaction = fields
+            .next::<u128>()
+            .and_then(|_| fields.next_str())
+            .and_then(|name| parse(name, &mut fields))
+            .and_then(|transaction| fields.end().map(|_| transaction))
+            .map_err(|e| invalid_data(index + 2, e))?;
+
+        transactions.push(transaction);
+    }
+
+    Ok(transactions)
+}
+
+fn invalid_data<E: fmt::Display>(line: usize, error: E) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("line {}: {}", line, error),
+    )
+}
+
+// Space-separated fields of a single line
+pub(crate) struct Fields<'a> {
+    fields: SplitWhitespace<'a>,
+}
+
+impl<'a> Fields<'a> {
+    fn new(line: &'a str) -> Fields<'a> {
+        Fields {
+            fields: line.split_whitespace(),
+        }
+    }
+
+    pub(crate) fn next_str(&mut self) -> io::Result<&'a str> {
+        self.fields
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing field"))
+    }
+
+    // Decimal integer
+    pub(crate) fn next<T: FromStr>(&mut self) -> io::Result<T> {
+        let field = self.next_str()?;
+
+        field
+            .parse()
+            .map_err(|_| invalid_field("invalid number", field))
+    }
+
+    // 0 or 1
+    pub(crate) fn next_bool(&mut self) -> io::Result<bool> {
+        match self.next_str()? {
+            "0" => Ok(false),
+            "1" => Ok(true),
+            field => Err(invalid_field("invalid boolean", field)),
+        }
+    }
+
+    // Hexadecimal bytes, or - if empty
+    pub(crate) fn next_data(&mut self) -> io::Result<Vec<u8>> {
+        let field = self.next_str()?;
+        if field == "-" {
+            return Ok(Vec::new());
+        }
+
+        if field.len() % 2 != 0 || !field.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid_field("invalid data", field));
+        }
+
+        Ok((0..field.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&field[i..i + 2], 16).unwrap_or_default())
+            .collect())
+    }
+
+    // Hexadecimal bytes, or * if there's no buffer
+    pub(crate) fn next_optional_data(&mut self) -> io::Result<Option<Vec<u8>>> {
+        if self.fields.clone().next() == Some("*") {
+            self.fields.next();
+
+            Ok(None)
+        } else {
+            self.next_data().map(Some)
+        }
+    }
+
+    fn end(&mut self) -> io::Result<()> {
+        match self.fields.next() {
+            Some(field) => Err(invalid_field("unexpected field", field)),
+            None => Ok(()),
+        }
+    }
+}
+
+fn invalid_field(message: &str, field: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{} \"{}\"", message, field),
+    )
+}
+
+pub(crate) fn unknown_transaction(name: &str) -> io::Error {
+    invalid_field("unknown transaction", name)
+}
+
+// Formats a byte slice as hexadecimal bytes, or - if it's empty
+pub(crate) struct Hex<'a>(pub(crate) &'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return f.write_str("-");
+        }
+
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Formats an optional buffer as hexadecimal bytes, or * if it's None
+pub(crate) struct OptionHex<'a>(pub(crate) Option<&'a [u8]>);
+
+impl fmt::Display for OptionHex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(data) => Hex(data).fmt(f),
+            None => f.write_str("*"),
+        }
+    }
+}
@@ -126,31 +126,86 @@
 //! slave device to any other available GPIO pin on the Pi, and manually
 //! changing it to high and low as needed.
 //!
+//! ## Testing
+//!
+//! [`Spi::with_mock`] constructs an `Spi` instance that runs against a [`Mock`] bus instead
+//! of the SPI peripheral, which allows device drivers to be tested without any hardware.
+//! A `Mock` contains a script of expected [`Transaction`]s, including multi-segment
+//! transfers, with the outgoing data that should be sent and the incoming data that's
+//! returned to the caller. Any transaction that deviates from the script returns an
+//! [`Error::Mismatch`].
+//!
+//! ## Recording
+//!
+//! [`Spi::start_recording`] writes every transfer, including multi-segment
+//! transfers, to a file, until [`Spi::stop_recording`] is called. Recordings can be
+//! loaded with [`Mock::from_recording`] to replay the bus traffic without any
+//! hardware.
+//!
+//! Recordings are plain text files. The first line contains the header
+//! `rppal-recording 1 spi <start>`, where `<start>` is the time the recording was
+//! started in microseconds since the Unix epoch. Every following line contains a
+//! single transaction, consisting of space-separated fields. The first field is the
+//! number of microseconds elapsed since the start of the recording when the
+//! transaction completed, followed by one of these transaction names and its fields.
+//!
+//! * `read <data>`
+//! * `write <data>`
+//! * `transfer <write> <read>`
+//! * `transfer_segments <count>`, followed by `<write> <read> <clock_speed> <delay>
+//!   <bits_per_word> <ss_change>` for each segment, similar to [`SegmentTransfer`]
+//!
+//! Integers are written in decimal notation, and booleans as `0` or `1`. Data is
+//! written in hexadecimal notation with two digits per byte, or `-` when empty. A
+//! segment without a read or write buffer is marked with `*`. Failed transactions
+//! are added as comments starting with `#`, which are ignored when the recording is
+//! replayed.
+//!
+//! ```text
+//! rppal-recording 1 spi 1700000000000000
+//! 527 transfer 0180 03ff
+//! 1311 transfer_segments 2 9f * 0 0 0 0 * 0a0b0c 0 10 0 1
+//! ```
+//!
 //! [`Ss0`]: enum.SlaveSelect.html
 //! [`Ss1`]: enum.SlaveSelect.html
 //! [`Ss2`]: enum.SlaveSelect.html
 //! [`Mode1`]: enum.Mode.html
 //! [`Mode3`]: enum.Mode.html
 //! [`reverse_bits`]: fn.reverse_bits.html
+//! [`Spi::with_mock`]: struct.Spi.html#method.with_mock
+//! [`Mock`]: struct.Mock.html
+//! [`Transaction`]: enum.Transaction.html
+//! [`Error::Mismatch`]: enum.Error.html#variant.Mismatch
+//! [`Spi::start_recording`]: struct.Spi.html#method.start_recording
+//! [`Spi::stop_recording`]: struct.Spi.html#method.stop_recording
+//! [`Mock::from_recording`]: struct.Mock.html#method.from_recording
+//! [`SegmentTransfer`]: struct.SegmentTransfer.html
 
 use std::error;
 use std::fmt;
-use std::fs::{File, OpenOptions};
 use std::io;
-use std::io::{Read, Write};
 use std::marker::PhantomData;
-use std::os::unix::io::AsRawFd;
+use std::path::Path;
 use std::result;
 
 #[cfg(feature = "hal")]
 mod hal;
 mod ioctl;
+mod mock;
+mod record;
 mod segment;
+mod transport;
 
+pub use self::mock::{Mock, SegmentTransfer, Transaction};
 pub use self::segment::Segment;
 #[cfg(feature = "hal")]
 pub use hal::SimpleHalSpiDevice;
 
+use self::mock::MockTransport;
+use self::record::RecordingTransport;
+use self::transport::{SpiDev, Transport};
+
 /// Errors that can occur when accessing the SPI peripheral.
 #[derive(Debug)]
 pub enum Error {
@@ -179,6 +234,13 @@ pub enum Error {
     ModeNotSupported(Mode),
     /// The specified Slave Select polarity is not supported.
     PolarityNotSupported(Polarity),
+    /// Transaction mismatch.
+    ///
+    /// The transaction doesn't match the script of a [`Mock`]. Contains a
+    /// description of the expected and the actual transaction.
+    ///
+    /// [`Mock`]: struct.Mock.html
+    Mismatch(String),
 }
 
 impl fmt::Display for Error {
@@ -198,6 +260,7 @@ impl fmt::Display for Error {
             Error::PolarityNotSupported(polarity) => {
                 write!(f, "Polarity value not supported: {:?}", polarity)
             }
+            Error::Mismatch(ref description) => write!(f, "Transaction mismatch: {}", description),
         }
     }
 }
@@ -412,7 +475,7 @@ impl fmt::Display for BitOrder {
 /// [`blocking::spi::Write<u8>`]: ../../embedded_hal/blocking/spi/trait.Write.html
 /// [`spi::FullDuplex<u8>`]: ../../embedded_hal/spi/trait.FullDuplex.html
 pub struct Spi {
-    spidev: File,
+    transport: RecordingTransport,
     // Stores the last read value. Used for embedded_hal::spi::FullDuplex.
     #[cfg(feature = "hal")]
     last_read: Option<u8>,
@@ -443,13 +506,40 @@ impl Spi {
         // TX_DUAL/TX_QUAD/RX_DUAL/RX_QUAD - Not supported by BCM283x
         // bits per word - any value other than 0 or 8 returns EINVAL when set
 
-        let spidev = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .open(format!("/dev/spidev{}.{}", bus as u8, slave_select as u8))?;
+        Spi::with_transport(
+            Box::new(SpiDev::open(bus, slave_select)?),
+            clock_speed,
+            mode,
+        )
+    }
 
+    /// Constructs a new `Spi` that runs against a scripted [`Mock`] bus instead
+    /// of an SPI peripheral.
+    ///
+    /// Every transaction is checked against the next expected transaction in the
+    /// script. More information can be found in the [`Mock`] documentation.
+    ///
+    /// `clock_speed` and `mode` are stored, and can be retrieved through
+    /// [`clock_speed`] and [`mode`].
+    ///
+    /// [`Mock`]: struct.Mock.html
+    /// [`clock_speed`]: #method.clock_speed
+    /// [`mode`]: #method.mode
+    pub fn with_mock(mock: &Mock, clock_speed: u32, mode: Mode) -> Result<Spi> {
+        Spi::with_transport(
+            Box::new(MockTransport::new(mock.clone())),
+            clock_speed,
+            mode,
+        )
+    }
+
+    fn with_transport(
+        transport: Box<dyn Transport + Send>,
+        clock_speed: u32,
+        mode: Mode,
+    ) -> Result<Spi> {
         // Reset all mode flags
-        if let Err(e) = ioctl::set_mode32(spidev.as_raw_fd(), mode as u32) {
+        if let Err(e) = transport.set_mode32(mode as u32) {
             if e.kind() == io::ErrorKind::InvalidInput {
                 return Err(Error::ModeNotSupported(mode));
             } else {
@@ -458,7 +548,7 @@ impl Spi {
         }
 
         let spi = Spi {
-            spidev,
+            transport: RecordingTransport::new(transport),
             #[cfg(feature = "hal")]
             last_read: None,
             not_sync: PhantomData,
@@ -473,10 +563,7 @@ impl Spi {
 
     /// Gets the bit order.
     pub fn bit_order(&self) -> Result<BitOrder> {
-        let mut bit_order: u8 = 0;
-        ioctl::lsb_first(self.spidev.as_raw_fd(), &mut bit_order)?;
-
-        Ok(match bit_order {
+        Ok(match self.transport.lsb_first()? {
             0 => BitOrder::MsbFirst,
             _ => BitOrder::LsbFirst,
         })
@@ -496,7 +583,7 @@ impl Spi {
     /// [`LsbFirst`]: enum.BitOrder.html
     /// [`reverse_bits`]: fn.reverse_bits.html
     pub fn set_bit_order(&self, bit_order: BitOrder) -> Result<()> {
-        match ioctl::set_lsb_first(self.spidev.as_raw_fd(), bit_order as u8) {
+        match self.transport.set_lsb_first(bit_order as u8) {
             Ok(_) => Ok(()),
             Err(ref e) if e.kind() == io::ErrorKind::InvalidInput => {
                 Err(Error::BitOrderNotSupported(bit_order))
@@ -507,10 +594,7 @@ impl Spi {
 
     /// Gets the number of bits per word.
     pub fn bits_per_word(&self) -> Result<u8> {
-        let mut bits_per_word: u8 = 0;
-        ioctl::bits_per_word(self.spidev.as_raw_fd(), &mut bits_per_word)?;
-
-        Ok(bits_per_word)
+        Ok(self.transport.bits_per_word()?)
     }
 
     /// Sets the number of bits per word.
@@ -519,7 +603,7 @@ impl Spi {
     ///
     /// By default, `bits_per_word` is set to 8.
     pub fn set_bits_per_word(&self, bits_per_word: u8) -> Result<()> {
-        match ioctl::set_bits_per_word(self.spidev.as_raw_fd(), bits_per_word) {
+        match self.transport.set_bits_per_word(bits_per_word) {
             Ok(_) => Ok(()),
             Err(ref e) if e.kind() == io::ErrorKind::InvalidInput => {
                 Err(Error::BitsPerWordNotSupported(bits_per_word))
@@ -530,17 +614,14 @@ impl Spi {
 
     /// Gets the clock frequency in hertz (Hz).
     pub fn clock_speed(&self) -> Result<u32> {
-        let mut clock_speed: u32 = 0;
-        ioctl::clock_speed(self.spidev.as_raw_fd(), &mut clock_speed)?;
-
-        Ok(clock_speed)
+        Ok(self.transport.clock_speed()?)
     }
 
     /// Sets the clock frequency in hertz (Hz).
     ///
     /// The SPI driver will automatically round down to the closest valid frequency.
     pub fn set_clock_speed(&self, clock_speed: u32) -> Result<()> {
-        match ioctl::set_clock_speed(self.spidev.as_raw_fd(), clock_speed) {
+        match self.transport.set_clock_speed(clock_speed) {
             Ok(_) => Ok(()),
             Err(ref e) if e.kind() == io::ErrorKind::InvalidInput => {
                 Err(Error::ClockSpeedNotSupported(clock_speed))
@@ -551,10 +632,7 @@ impl Spi {
 
     /// Gets the SPI mode.
     pub fn mode(&self) -> Result<Mode> {
-        let mut mode: u8 = 0;
-        ioctl::mode(self.spidev.as_raw_fd(), &mut mode)?;
-
-        Ok(match mode & 0x03 {
+        Ok(match self.transport.mode()? & 0x03 {
             0x01 => Mode::Mode1,
             0x02 => Mode::Mode2,
             0x03 => Mode::Mode3,
@@ -567,13 +645,12 @@ impl Spi {
     /// The SPI mode indicates the serial clock polarity and phase. Some modes
     /// may not be available depending on the SPI bus that's used.
     pub fn set_mode(&self, mode: Mode) -> Result<()> {
-        let mut new_mode: u8 = 0;
-        ioctl::mode(self.spidev.as_raw_fd(), &mut new_mode)?;
+        let mut new_mode = self.transport.mode()?;
 
         // Make sure we only replace the CPOL/CPHA bits
         new_mode = (new_mode & !0x03) | (mode as u8);
 
-        match ioctl::set_mode(self.spidev.as_raw_fd(), new_mode) {
+        match self.transport.set_mode(new_mode) {
             Ok(_) => Ok(()),
             Err(ref e) if e.kind() == io::ErrorKind::InvalidInput => {
                 Err(Error::ModeNotSupported(mode))
@@ -584,8 +661,7 @@ impl Spi {
 
     /// Gets the Slave Select polarity.
     pub fn ss_polarity(&self) -> Result<Polarity> {
-        let mut mode: u8 = 0;
-        ioctl::mode(self.spidev.as_raw_fd(), &mut mode)?;
+        let mode = self.transport.mode()?;
 
         if (mode & ioctl::MODE_CS_HIGH) == 0 {
             Ok(Polarity::ActiveLow)
@@ -598,8 +674,7 @@ impl Spi {
     ///
     /// By default, the Slave Select polarity is set to `ActiveLow`.
     pub fn set_ss_polarity(&self, polarity: Polarity) -> Result<()> {
-        let mut new_mode: u8 = 0;
-        ioctl::mode(self.spidev.as_raw_fd(), &mut new_mode)?;
+        let mut new_mode = self.transport.mode()?;
 
         if polarity == Polarity::ActiveHigh {
             new_mode |= ioctl::MODE_CS_HIGH;
@@ -607,7 +682,7 @@ impl Spi {
             new_mode &= !ioctl::MODE_CS_HIGH;
         }
 
-        match ioctl::set_mode(self.spidev.as_raw_fd(), new_mode) {
+        match self.transport.set_mode(new_mode) {
             Ok(_) => Ok(()),
             Err(ref e) if e.kind() == io::ErrorKind::InvalidInput => {
                 Err(Error::PolarityNotSupported(polarity))
@@ -630,7 +705,7 @@ impl Spi {
     ///
     /// Returns how many bytes were read.
     pub fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
-        Ok(self.spidev.read(buffer)?)
+        self.transport.read(buffer)
     }
 
     /// Sends the outgoing data contained in `buffer` to the slave device.
@@ -642,7 +717,7 @@ impl Spi {
     ///
     /// Returns how many bytes were written.
     pub fn write(&mut self, buffer: &[u8]) -> Result<usize> {
-        Ok(self.spidev.write(buffer)?)
+        self.transport.write(buffer)
     }
 
     /// Sends and receives data at the same time.
@@ -660,11 +735,7 @@ impl Spi {
     ///
     /// Returns how many bytes were transferred.
     pub fn transfer(&self, read_buffer: &mut [u8], write_buffer: &[u8]) -> Result<usize> {
-        let segment = Segment::new(read_buffer, write_buffer);
-
-        ioctl::transfer(self.spidev.as_raw_fd(), &[segment])?;
-
-        Ok(segment.len())
+        self.transport.transfer(read_buffer, write_buffer)
     }
 
     /// Transfers multiple half-duplex or full-duplex segments.
@@ -680,9 +751,26 @@ impl Spi {
     /// [`Segment`]: struct.Segment.html
     /// [`Segment::set_ss_change`]: struct.Segment.html#method.set_ss_change
     pub fn transfer_segments(&self, segments: &[Segment<'_, '_>]) -> Result<()> {
-        ioctl::transfer(self.spidev.as_raw_fd(), segments)?;
+        self.transport.transfer_segments(segments)
+    }
 
-        Ok(())
+    /// Starts recording all transfers to the specified file.
+    ///
+    /// If the file already exists, it's overwritten. Any active recording is
+    /// stopped first. The recording can be replayed using [`Mock::from_recording`].
+    /// More information on the file format can be found [here].
+    ///
+    /// [`Mock::from_recording`]: struct.Mock.html#method.from_recording
+    /// [here]: index.html#recording
+    pub fn start_recording<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        Ok(self.transport.start(path.as_ref())?)
+    }
+
+    /// Stops the active recording, if any.
+    ///
+    /// Returns an error if any transfer couldn't be written to the file.
+    pub fn stop_recording(&mut self) -> Result<()> {
+        Ok(self.transport.stop()?)
     }
 }
 
@@ -692,6 +780,8 @@ unsafe impl Send for Spi {}
 
 impl fmt::Debug for Spi {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Spi").field("spidev", &self.spidev).finish()
+        f.debug_struct("Spi")
+            .field("transport", &self.transport)
+            .finish()
     }
 }
@@ -0,0 +1,385 @@
+use std::cell::Cell;
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::mock::Script;
+use crate::spi::record;
+use crate::spi::transport::Transport;
+use crate::spi::{Error, Result, Segment};
+
+/// A segment of a multi-segment transfer expected by a [`Mock`].
+///
+/// `SegmentTransfer` corresponds to a [`Segment`] passed to
+/// [`Spi::transfer_segments`]. For segments with a write buffer, `write` has to
+/// match the outgoing data. For segments with a read buffer, `read` contains the
+/// incoming data that's written to the buffer.
+///
+/// [`Mock`]: struct.Mock.html
+/// [`Segment`]: struct.Segment.html
+/// [`Spi::transfer_segments`]: struct.Spi.html#method.transfer_segments
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SegmentTransfer {
+    /// Outgoing data, or `None` if the segment doesn't have a write buffer.
+    pub write: Option<Vec<u8>>,
+    /// Incoming data, or `None` if the segment doesn't have a read buffer.
+    ///
+    /// `read` needs to contain as many bytes as the segment transfers.
+    pub read: Option<Vec<u8>>,
+    /// Custom clock speed in hertz (Hz), or `0` for the [`Spi`] setting.
+    ///
+    /// [`Spi`]: struct.Spi.html
+    pub clock_speed: u32,
+    /// Delay in microseconds (µs).
+    pub delay: u16,
+    /// Custom number of bits per word, or `0` for the [`Spi`] setting.
+    ///
+    /// [`Spi`]: struct.Spi.html
+    pub bits_per_word: u8,
+    /// Slave Select behavior after the segment.
+    pub ss_change: bool,
+}
+
+impl SegmentTransfer {
+    /// Constructs a new `SegmentTransfer` with the default settings for a
+    /// simultaneous (full-duplex) read/write transfer.
+    pub fn new(read: Vec<u8>, write: Vec<u8>) -> SegmentTransfer {
+        SegmentTransfer {
+            write: Some(write),
+            read: Some(read),
+            ..SegmentTransfer::default()
+        }
+    }
+
+    /// Constructs a new `SegmentTransfer` with the default settings for a
+    /// read operation.
+    pub fn with_read(read: Vec<u8>) -> SegmentTransfer {
+        SegmentTransfer {
+            read: Some(read),
+            ..SegmentTransfer::default()
+        }
+    }
+
+    /// Constructs a new `SegmentTransfer` with the default settings for a
+    /// write operation.
+    pub fn with_write(write: Vec<u8>) -> SegmentTransfer {
+        SegmentTransfer {
+            write: Some(write),
+            ..SegmentTransfer::default()
+        }
+    }
+
+    fn matches(&self, segment: &Segment<'_, '_>) -> bool {
+        self.write.as_deref() == segment.write_buffer()
+            && self.read.as_ref().map(Vec::len) == segment_read_len(segment)
+            && self.clock_speed == segment.clock_speed()
+            && self.delay == segment.delay()
+            && self.bits_per_word == segment.bits_per_word()
+            && self.ss_change == segment.ss_change()
+    }
+}
+
+/// An SPI transaction expected by a [`Mock`].
+///
+/// For write transactions, the outgoing data has to match exactly. For read
+/// transactions, the incoming data is returned to the caller.
+///
+/// [`Mock`]: struct.Mock.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transaction {
+    /// [`Spi::read`]. `data` needs to contain as many bytes as the read buffer.
+    ///
+    /// [`Spi::read`]: struct.Spi.html#method.read
+    Read { data: Vec<u8> },
+    /// [`Spi::write`].
+    ///
+    /// [`Spi::write`]: struct.Spi.html#method.write
+    Write { data: Vec<u8> },
+    /// [`Spi::transfer`]. `write` and `read` need to contain as many bytes as
+    /// the shortest of the two buffers.
+    ///
+    /// [`Spi::transfer`]: struct.Spi.html#method.transfer
+    Transfer { write: Vec<u8>, read: Vec<u8> },
+    /// [`Spi::transfer_segments`].
+    ///
+    /// [`Spi::transfer_segments`]: struct.Spi.html#method.transfer_segments
+    TransferSegments(Vec<SegmentTransfer>),
+}
+
+/// A scripted SPI bus, used to test code that depends on [`Spi`] without
+/// accessing any hardware.
+///
+/// `Mock` contains a sequence of expected [`Transaction`]s. An `Spi` instance
+/// constructed by [`Spi::with_mock`] compares every transaction to the next
+/// expected transaction in the script. Matching read transactions return the
+/// scripted incoming data. If a transaction doesn't match, or the script has
+/// already been completed, the `Spi` method returns
+/// `Err(`[`Error::Mismatch`]`)`, which describes both the expected and the
+/// actual transaction.
+///
+/// Configuration methods such as [`Spi::set_clock_speed`] and [`Spi::set_mode`]
+/// aren't part of the script. Any value is accepted, and returned by the
+/// corresponding getter.
+///
+/// `Mock` can be cloned to keep a handle to the script after it's been passed to
+/// `Spi`. Call [`done`] at the end of a test to verify every expected transaction
+/// was completed.
+///
+/// [`Spi`]: struct.Spi.html
+/// [`Transaction`]: enum.Transaction.html
+/// [`Spi::with_mock`]: struct.Spi.html#method.with_mock
+/// [`Error::Mismatch`]: enum.Error.html#variant.Mismatch
+/// [`Spi::set_clock_speed`]: struct.Spi.html#method.set_clock_speed
+/// [`Spi::set_mode`]: struct.Spi.html#method.set_mode
+/// [`done`]: #method.done
+#[derive(Clone, Debug)]
+pub struct Mock {
+    script: Arc<Mutex<Script<Transaction>>>,
+    // Set for mocks constructed from a recording, which stores short reads and
+    // writes with the number of bytes that were actually transferred
+    replay: bool,
+}
+
+impl Mock {
+    /// Constructs a new `Mock` that expects the specified sequence of transactions.
+    pub fn new<I>(transactions: I) -> Mock
+    where
+        I: IntoIterator<Item = Transaction>,
+    {
+        Mock {
+            script: Arc::new(Mutex::new(Script::new(transactions))),
+            replay: false,
+        }
+    }
+
+    /// Constructs a new `Mock` that expects the transactions stored in a recording.
+    ///
+    /// Recordings are created by [`Spi::start_recording`]. Timestamps and failed
+    /// transactions are ignored. Reads and writes that transferred fewer bytes than
+    /// requested match any buffer that's at least as long as the recorded data, and
+    /// return the recorded length.
+    ///
+    /// Returns `Err(`[`Error::Io`]`)` if the file can't be read or isn't a valid
+    /// SPI recording.
+    ///
+    /// [`Spi::start_recording`]: struct.Spi.html#method.start_recording
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub fn from_recording<P: AsRef<Path>>(path: P) -> Result<Mock> {
+        Ok(Mock {
+            replay: true,
+            ..Mock::new(record::parse(path.as_ref())?)
+        })
+    }
+
+    /// Appends a transaction to the end of the script.
+    pub fn expect(&self, transaction: Transaction) {
+        self.script.lock().unwrap().push(transaction);
+    }
+
+    /// Verifies all expected transactions have been completed.
+    ///
+    /// Returns `Err(`[`Error::Mismatch`]`)` if any transaction didn't match the
+    /// script, or if any expected transactions remain.
+    ///
+    /// [`Error::Mismatch`]: enum.Error.html#variant.Mismatch
+    pub fn done(&self) -> Result<()> {
+        self.script.lock().unwrap().done().map_err(Error::Mismatch)
+    }
+
+    fn next<R, F>(&self, actual: fmt::Arguments<'_>, check: F) -> Result<R>
+    where
+        F: FnOnce(&Transaction) -> Option<R>,
+    {
+        self.script
+            .lock()
+            .unwrap()
+            .next(actual, check)
+            .map_err(Error::Mismatch)
+    }
+}
+
+// Transport used by an Spi instance constructed with Spi::with_mock. Stores
+// the bus configuration in memory.
+#[derive(Debug)]
+pub(crate) struct MockTransport {
+    mock: Mock,
+    mode: Cell<u32>,
+    lsb_first: Cell<u8>,
+    bits_per_word: Cell<u8>,
+    clock_speed: Cell<u32>,
+}
+
+impl MockTransport {
+    pub(crate) fn new(mock: Mock) -> MockTransport {
+        MockTransport {
+            mock,
+            mode: Cell::new(0),
+            lsb_first: Cell::new(0),
+            bits_per_word: Cell::new(0),
+            clock_speed: Cell::new(0),
+        }
+    }
+}
+
+impl Transport for MockTransport {
+    fn mode(&self) -> io::Result<u8> {
+        Ok(self.mode.get() as u8)
+    }
+
+    fn set_mode(&self, mode: u8) -> io::Result<()> {
+        self.mode.set((self.mode.get() & !0xFF) | u32::from(mode));
+
+        Ok(())
+    }
+
+    fn set_mode32(&self, mode: u32) -> io::Result<()> {
+        self.mode.set(mode);
+
+        Ok(())
+    }
+
+    fn lsb_first(&self) -> io::Result<u8> {
+        Ok(self.lsb_first.get())
+    }
+
+    fn set_lsb_first(&self, lsb_first: u8) -> io::Result<()> {
+        self.lsb_first.set(lsb_first);
+
+        Ok(())
+    }
+
+    fn bits_per_word(&self) -> io::Result<u8> {
+        Ok(self.bits_per_word.get())
+    }
+
+    fn set_bits_per_word(&self, bits_per_word: u8) -> io::Result<()> {
+        self.bits_per_word.set(bits_per_word);
+
+        Ok(())
+    }
+
+    fn clock_speed(&self) -> io::Result<u32> {
+        Ok(self.clock_speed.get())
+    }
+
+    fn set_clock_speed(&self, clock_speed: u32) -> io::Result<()> {
+        self.clock_speed.set(clock_speed);
+
+        Ok(())
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        let len = buffer.len();
+        let replay = self.mock.replay;
+
+        self.mock.next(
+            format_args!("Read {{ len: {} }}", len),
+            |expected| match *expected {
+                Transaction::Read { ref data }
+                    if data.len() == len || (replay && data.len() < len) =>
+                {
+                    buffer[..data.len()].copy_from_slice(data);
+                    Some(data.len())
+                }
+                _ => None,
+            },
+        )
+    }
+
+    fn write(&mut self, buffer: &[u8]) -> Result<usize> {
+        let replay = self.mock.replay;
+
+        self.mock.next(
+            format_args!("Write {{ data: {:?} }}", buffer),
+            |expected| match *expected {
+                Transaction::Write { ref data }
+                    if data[..] == *buffer || (replay && buffer.starts_with(data)) =>
+                {
+                    Some(data.len())
+                }
+                _ => None,
+            },
+        )
+    }
+
+    fn transfer(&self, read_buffer: &mut [u8], write_buffer: &[u8]) -> Result<usize> {
+        let len = read_buffer.len().min(write_buffer.len());
+        let write_buffer = &write_buffer[..len];
+
+        self.mock.next(
+            format_args!("Transfer {{ write: {:?}, len: {} }}", write_buffer, len),
+            |expected| match *expected {
+                Transaction::Transfer {
+                    ref write,
+                    ref read,
+                } if write[..] == *write_buffer && read.len() == len => {
+                    read_buffer[..len].copy_from_slice(read);
+                    Some(len)
+                }
+                _ => None,
+            },
+        )
+    }
+
+    fn transfer_segments(&self, segments: &[Segment<'_, '_>]) -> Result<()> {
+        self.mock.next(
+            format_args!("TransferSegments({:?})", ActualSegments(segments)),
+            |expected| match *expected {
+                Transaction::TransferSegments(ref transfers)
+                    if transfers.len() == segments.len()
+                        && transfers
+                            .iter()
+                            .zip(segments)
+                            .all(|(transfer, segment)| transfer.matches(segment)) =>
+                {
+                    for (transfer, segment) in transfers.iter().zip(segments) {
+                        // Safe because the read buffers are mutably borrowed by the segments
+                        if let (Some(read), Some(buffer)) =
+                            (transfer.read.as_ref(), unsafe { segment.read_buffer() })
+                        {
+                            buffer.copy_from_slice(read);
+                        }
+                    }
+
+                    Some(())
+                }
+                _ => None,
+            },
+        )
+    }
+}
+
+fn segment_read_len(segment: &Segment<'_, '_>) -> Option<usize> {
+    if segment.has_read_buffer() {
+        Some(segment.len())
+    } else {
+        None
+    }
+}
+
+// Describes the segments of an actual transfer in a similar format as SegmentTransfer
+struct ActualSegments<'s, 'a, 'b>(&'s [Segment<'a, 'b>]);
+
+impl fmt::Debug for ActualSegments<'_, '_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(ActualSegment))
+            .finish()
+    }
+}
+
+struct ActualSegment<'s, 'a, 'b>(&'s Segment<'a, 'b>);
+
+impl fmt::Debug for ActualSegment<'_, '_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SegmentTransfer")
+            .field("write", &self.0.write_buffer())
+            .field("read_len", &segment_read_len(self.0))
+            .field("clock_speed", &self.0.clock_speed())
+            .field("delay", &self.0.delay())
+            .field("bits_per_word", &self.0.bits_per_word())
+            .field("ss_change", &self.0.ss_change())
+            .finish()
+    }
+}
@@ -0,0 +1,212 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use crate::record::{self, Hex, OptionHex, Recording};
+use crate::spi::mock::{SegmentTransfer, Transaction};
+use crate::spi::transport::Transport;
+use crate::spi::{Result, Segment};
+
+const BUS: &str = "spi";
+
+// Forwards all operations to the underlying transport, and adds every transaction
+// to the active recording, if any.
+#[derive(Debug)]
+pub(crate) struct RecordingTransport {
+    transport: Box<dyn Transport + Send>,
+    recording: RefCell<Option<Recording>>,
+}
+
+impl RecordingTransport {
+    pub(crate) fn new(transport: Box<dyn Transport + Send>) -> RecordingTransport {
+        RecordingTransport {
+            transport,
+            recording: RefCell::new(None),
+        }
+    }
+
+    pub(crate) fn start(&mut self, path: &Path) -> io::Result<()> {
+        self.stop()?;
+        *self.recording.get_mut() = Some(Recording::create(path, BUS)?);
+
+        Ok(())
+    }
+
+    pub(crate) fn stop(&mut self) -> io::Result<()> {
+        match self.recording.get_mut().take() {
+            Some(recording) => recording.finish(),
+            None => Ok(()),
+        }
+    }
+
+    fn record<T, F>(&self, name: &str, result: &Result<T>, transaction: F)
+    where
+        F: FnOnce(&T) -> Transaction,
+    {
+        if let Some(recording) = self.recording.borrow_mut().as_mut() {
+            match result {
+                Ok(value) => recording.record(&Line(&transaction(value))),
+                Err(e) => recording.record_error(name, e),
+            }
+        }
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn mode(&self) -> io::Result<u8> {
+        self.transport.mode()
+    }
+
+    fn set_mode(&self, mode: u8) -> io::Result<()> {
+        self.transport.set_mode(mode)
+    }
+
+    fn set_mode32(&self, mode: u32) -> io::Result<()> {
+        self.transport.set_mode32(mode)
+    }
+
+    fn lsb_first(&self) -> io::Result<u8> {
+        self.transport.lsb_first()
+    }
+
+    fn set_lsb_first(&self, lsb_first: u8) -> io::Result<()> {
+        self.transport.set_lsb_first(lsb_first)
+    }
+
+    fn bits_per_word(&self) -> io::Result<u8> {
+        self.transport.bits_per_word()
+    }
+
+    fn set_bits_per_word(&self, bits_per_word: u8) -> io::Result<()> {
+        self.transport.set_bits_per_word(bits_per_word)
+    }
+
+    fn clock_speed(&self) -> io::Result<u32> {
+        self.transport.clock_speed()
+    }
+
+    fn set_clock_speed(&self, clock_speed: u32) -> io::Result<()> {
+        self.transport.set_clock_speed(clock_speed)
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        let result = self.transport.read(buffer);
+        self.record("read", &result, |&len| Transaction::Read {
+            data: buffer[..len].to_vec(),
+        });
+
+        result
+    }
+
+    fn write(&mut self, buffer: &[u8]) -> Result<usize> {
+        let result = self.transport.write(buffer);
+        self.record("write", &result, |&len| Transaction::Write {
+            data: buffer[..len].to_vec(),
+        });
+
+        result
+    }
+
+    fn transfer(&self, read_buffer: &mut [u8], write_buffer: &[u8]) -> Result<usize> {
+        let result = self.transport.transfer(read_buffer, write_buffer);
+        self.record("transfer", &result, |&len| Transaction::Transfer {
+            write: write_buffer[..len].to_vec(),
+            read: read_buffer[..len].to_vec(),
+        });
+
+        result
+    }
+
+    fn transfer_segments(&self, segments: &[Segment<'_, '_>]) -> Result<()> {
+        let result = self.transport.transfer_segments(segments);
+        self.record("transfer_segments", &result, |_| {
+            Transaction::TransferSegments(
+                segments
+                    .iter()
+                    .map(|segment| SegmentTransfer {
+                        write: segment.write_buffer().map(<[u8]>::to_vec),
+                        // Safe because the read buffers are mutably borrowed by the segments
+                        read: unsafe { segment.read_buffer() }.map(|buffer| buffer.to_vec()),
+                        clock_speed: segment.clock_speed(),
+                        delay: segment.delay(),
+                        bits_per_word: segment.bits_per_word(),
+                        ss_change: segment.ss_change(),
+                    })
+                    .collect(),
+            )
+        });
+
+        result
+    }
+}
+
+// Formats a transaction as a single line of a recording, without the timestamp
+struct Line<'a>(&'a Transaction);
+
+impl fmt::Display for Line<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self.0 {
+            Transaction::Read { ref data } => write!(f, "read {}", Hex(data)),
+            Transaction::Write { ref data } => write!(f, "write {}", Hex(data)),
+            Transaction::Transfer {
+                ref write,
+                ref read,
+            } => write!(f, "transfer {} {}", Hex(write), Hex(read)),
+            Transaction::TransferSegments(ref transfers) => {
+                write!(f, "transfer_segments {}", transfers.len())?;
+
+                for transfer in transfers {
+                    write!(
+                        f,
+                        " {} {} {} {} {} {}",
+                        OptionHex(transfer.write.as_deref()),
+                        OptionHex(transfer.read.as_deref()),
+                        transfer.clock_speed,
+                        transfer.delay,
+                        transfer.bits_per_word,
+                        transfer.ss_change as u8
+                    )?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+// Reads all transactions from the recording at path
+pub(crate) fn parse(path: &Path) -> io::Result<Vec<Transaction>> {
+    record::parse(path, BUS, |name, fields| {
+        Ok(match name {
+            "read" => Transaction::Read {
+                data: fields.next_data()?,
+            },
+            "write" => Transaction::Write {
+                data: fields.next_data()?,
+            },
+            "transfer" => Transaction::Transfer {
+                write: fields.next_data()?,
+                read: fields.next_data()?,
+            },
+            "transfer_segments" => {
+                let len: usize = fields.next()?;
+                let mut transfers = Vec::new();
+
+                for _ in 0..len {
+                    transfers.push(SegmentTransfer {
+                        write: fields.next_optional_data()?,
+                        read: fields.next_optional_data()?,
+                        clock_speed: fields.next()?,
+                        delay: fields.next()?,
+                        bits_per_word: fields.next()?,
+                        ss_change: fields.next_bool()?,
+                    });
+                }
+
+                Transaction::TransferSegments(transfers)
+            }
+            _ => return Err(record::unknown_transaction(name)),
+        })
+    })
+}
@@ -1,5 +1,6 @@
 use std::fmt;
 use std::marker;
+use std::slice;
 
 /// Part of a multi-segment transfer.
 ///
@@ -241,6 +242,36 @@ impl<'a, 'b> Segment<'a, 'b> {
     pub fn set_ss_change(&mut self, ss_change: bool) {
         self.cs_change = ss_change as u8;
     }
+
+    // Returns the outgoing data, or None if no write buffer was specified.
+    pub(crate) fn write_buffer(&self) -> Option<&[u8]> {
+        if self.tx_buf == 0 {
+            None
+        } else {
+            Some(unsafe { slice::from_raw_parts(self.tx_buf as *const u8, self.len as usize) })
+        }
+    }
+
+    pub(crate) fn has_read_buffer(&self) -> bool {
+        self.rx_buf != 0
+    }
+
+    // Returns the buffer for incoming data, or None if no read buffer was
+    // specified. This is used to emulate the kernel writing incoming data
+    // during a transfer.
+    //
+    // Safety: The caller must ensure no other reference to the read buffer exists.
+    #[allow(clippy::mut_from_ref)]
+    pub(crate) unsafe fn read_buffer(&self) -> Option<&mut [u8]> {
+        if self.rx_buf == 0 {
+            None
+        } else {
+            Some(slice::from_raw_parts_mut(
+                self.rx_buf as *mut u8,
+                self.len as usize,
+            ))
+        }
+    }
 }
 
 impl<'a, 'b> fmt::Debug for Segment<'a, 'b> {
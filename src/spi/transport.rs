@@ -0,0 +1,125 @@
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::unix::io::AsRawFd;
+
+use crate::spi::ioctl;
+use crate::spi::{Bus, Result, Segment, SlaveSelect};
+
+// Operations performed by Spi on the underlying bus. SpiDev forwards them to
+// spidev, while Mock checks them against a script of expected transactions.
+// Configuration methods return io::Error, so Spi can map unsupported values
+// to the relevant Error variant.
+pub(crate) trait Transport: fmt::Debug {
+    fn mode(&self) -> io::Result<u8>;
+    fn set_mode(&self, mode: u8) -> io::Result<()>;
+    fn set_mode32(&self, mode: u32) -> io::Result<()>;
+    fn lsb_first(&self) -> io::Result<u8>;
+    fn set_lsb_first(&self, lsb_first: u8) -> io::Result<()>;
+    fn bits_per_word(&self) -> io::Result<u8>;
+    fn set_bits_per_word(&self, bits_per_word: u8) -> io::Result<()>;
+    fn clock_speed(&self) -> io::Result<u32>;
+    fn set_clock_speed(&self, clock_speed: u32) -> io::Result<()>;
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize>;
+    fn write(&mut self, buffer: &[u8]) -> Result<usize>;
+    fn transfer(&self, read_buffer: &mut [u8], write_buffer: &[u8]) -> Result<usize>;
+    fn transfer_segments(&self, segments: &[Segment<'_, '_>]) -> Result<()>;
+}
+
+#[derive(Debug)]
+pub(crate) struct SpiDev {
+    spidev: File,
+}
+
+impl SpiDev {
+    pub(crate) fn open(bus: Bus, slave_select: SlaveSelect) -> Result<SpiDev> {
+        let spidev = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(format!("/dev/spidev{}.{}", bus as u8, slave_select as u8))?;
+
+        Ok(SpiDev { spidev })
+    }
+}
+
+impl Transport for SpiDev {
+    fn mode(&self) -> io::Result<u8> {
+        let mut mode: u8 = 0;
+        ioctl::mode(self.spidev.as_raw_fd(), &mut mode)?;
+
+        Ok(mode)
+    }
+
+    fn set_mode(&self, mode: u8) -> io::Result<()> {
+        ioctl::set_mode(self.spidev.as_raw_fd(), mode)?;
+
+        Ok(())
+    }
+
+    fn set_mode32(&self, mode: u32) -> io::Result<()> {
+        ioctl::set_mode32(self.spidev.as_raw_fd(), mode)?;
+
+        Ok(())
+    }
+
+    fn lsb_first(&self) -> io::Result<u8> {
+        let mut lsb_first: u8 = 0;
+        ioctl::lsb_first(self.spidev.as_raw_fd(), &mut lsb_first)?;
+
+        Ok(lsb_first)
+    }
+
+    fn set_lsb_first(&self, lsb_first: u8) -> io::Result<()> {
+        ioctl::set_lsb_first(self.spidev.as_raw_fd(), lsb_first)?;
+
+        Ok(())
+    }
+
+    fn bits_per_word(&self) -> io::Result<u8> {
+        let mut bits_per_word: u8 = 0;
+        ioctl::bits_per_word(self.spidev.as_raw_fd(), &mut bits_per_word)?;
+
+        Ok(bits_per_word)
+    }
+
+    fn set_bits_per_word(&self, bits_per_word: u8) -> io::Result<()> {
+        ioctl::set_bits_per_word(self.spidev.as_raw_fd(), bits_per_word)?;
+
+        Ok(())
+    }
+
+    fn clock_speed(&self) -> io::Result<u32> {
+        let mut clock_speed: u32 = 0;
+        ioctl::clock_speed(self.spidev.as_raw_fd(), &mut clock_speed)?;
+
+        Ok(clock_speed)
+    }
+
+    fn set_clock_speed(&self, clock_speed: u32) -> io::Result<()> {
+        ioctl::set_clock_speed(self.spidev.as_raw_fd(), clock_speed)?;
+
+        Ok(())
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        Ok(self.spidev.read(buffer)?)
+    }
+
+    fn write(&mut self, buffer: &[u8]) -> Result<usize> {
+        Ok(self.spidev.write(buffer)?)
+    }
+
+    fn transfer(&self, read_buffer: &mut [u8], write_buffer: &[u8]) -> Result<usize> {
+        let segment = Segment::new(read_buffer, write_buffer);
+
+        ioctl::transfer(self.spidev.as_raw_fd(), &[segment])?;
+
+        Ok(segment.len())
+    }
+
+    fn transfer_segments(&self, segments: &[Segment<'_, '_>]) -> Result<()> {
+        ioctl::transfer(self.spidev.as_raw_fd(), segments)?;
+
+        Ok(())
+    }
+}
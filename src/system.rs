@@ -2,7 +2,11 @@
 //!
 //! Use [`DeviceInfo`] to identify the Raspberry Pi's model and SoC.
 //!
+//! Use [`Header`] to look up the pinout of the Raspberry Pi's GPIO header,
+//! and translate between physical pin numbers and BCM GPIO numbers.
+//!
 //! [`DeviceInfo`]: struct.DeviceInfo.html
+//! [`Header`]: struct.Header.html
 
 use std::error;
 use std::fmt;
@@ -11,6 +15,10 @@ use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::result;
 
+mod header;
+
+pub use self::header::{Header, PinType};
+
 // Peripheral base address
 const PERIPHERAL_BASE_RPI: u32 = 0x2000_0000;
 const PERIPHERAL_BASE_RPI2: u32 = 0x3f00_0000;
@@ -25,8 +33,9 @@ const GPIO_OFFSET_RP1: u32 = 0x0d_0000;
 const GPIO_LINES_BCM283X: u8 = 54;
 const GPIO_LINES_BCM2711: u8 = 58;
 // The RP1 actually has 54 GPIOs across 3 banks, but the last two banks are currently
-// specified as internal-use only, so we'll ignore those.
+// specified as internal-use only, so those are only available on request.
 const GPIO_LINES_RP1: u8 = 28;
+const GPIO_LINES_RP1_EXTENDED: u8 = 54;
 
 /// Errors that can occur when trying to identify the Raspberry Pi hardware.
 #[derive(Debug)]
@@ -356,7 +365,11 @@ impl DeviceInfo {
         let model = parse_proc_cpuinfo()
             .or_else(|_| parse_base_compatible().or_else(|_| parse_base_model()))?;
 
-        // Set SoC and memory offsets based on model
+        Ok(DeviceInfo::with_model(model))
+    }
+
+    // Sets the SoC and memory offsets based on the model
+    pub(crate) fn with_model(model: Model) -> DeviceInfo {
         match model {
             Model::RaspberryPiA
             | Model::RaspberryPiAPlus
@@ -365,61 +378,61 @@ impl DeviceInfo {
             | Model::RaspberryPiBPlus
             | Model::RaspberryPiComputeModule
             | Model::RaspberryPiZero
-            | Model::RaspberryPiZeroW => Ok(DeviceInfo {
+            | Model::RaspberryPiZeroW => DeviceInfo {
                 model,
                 soc: SoC::Bcm2835,
                 peripheral_base: PERIPHERAL_BASE_RPI,
                 gpio_offset: GPIO_OFFSET,
                 gpio_lines: GPIO_LINES_BCM283X,
                 gpio_interface: GpioInterface::Bcm,
-            }),
-            Model::RaspberryPi2B => Ok(DeviceInfo {
+            },
+            Model::RaspberryPi2B => DeviceInfo {
                 model,
                 soc: SoC::Bcm2836,
                 peripheral_base: PERIPHERAL_BASE_RPI2,
                 gpio_offset: GPIO_OFFSET,
                 gpio_lines: GPIO_LINES_BCM283X,
                 gpio_interface: GpioInterface::Bcm,
-            }),
+            },
             Model::RaspberryPi3B | Model::RaspberryPiComputeModule3 | Model::RaspberryPiZero2W => {
-                Ok(DeviceInfo {
+                DeviceInfo {
                     model,
                     soc: SoC::Bcm2837A1,
                     peripheral_base: PERIPHERAL_BASE_RPI2,
                     gpio_offset: GPIO_OFFSET,
                     gpio_lines: GPIO_LINES_BCM283X,
                     gpio_interface: GpioInterface::Bcm,
-                })
+                }
             }
             Model::RaspberryPi3BPlus
             | Model::RaspberryPi3APlus
-            | Model::RaspberryPiComputeModule3Plus => Ok(DeviceInfo {
+            | Model::RaspberryPiComputeModule3Plus => DeviceInfo {
                 model,
                 soc: SoC::Bcm2837B0,
                 peripheral_base: PERIPHERAL_BASE_RPI2,
                 gpio_offset: GPIO_OFFSET,
                 gpio_lines: GPIO_LINES_BCM283X,
                 gpio_interface: GpioInterface::Bcm,
-            }),
+            },
             Model::RaspberryPi4B
             | Model::RaspberryPi400
             | Model::RaspberryPiComputeModule4
-            | Model::RaspberryPiComputeModule4S => Ok(DeviceInfo {
+            | Model::RaspberryPiComputeModule4S => DeviceInfo {
                 model,
                 soc: SoC::Bcm2711,
                 peripheral_base: PERIPHERAL_BASE_RPI4,
                 gpio_offset: GPIO_OFFSET,
                 gpio_lines: GPIO_LINES_BCM2711,
                 gpio_interface: GpioInterface::Bcm,
-            }),
-            Model::RaspberryPi5 => Ok(DeviceInfo {
+            },
+            Model::RaspberryPi5 => DeviceInfo {
                 model,
                 soc: SoC::Bcm2712,
                 peripheral_base: PERIPHERAL_BASE_RP1,
                 gpio_offset: GPIO_OFFSET_RP1,
                 gpio_lines: GPIO_LINES_RP1,
                 gpio_interface: GpioInterface::Rp1,
-            }),
+            },
         }
     }
 
@@ -433,6 +446,13 @@ impl DeviceInfo {
         self.soc
     }
 
+    /// Returns the pinout of the Raspberry Pi's GPIO header.
+    ///
+    /// Returns `None` if the model doesn't have a GPIO header.
+    pub fn header(&self) -> Option<Header> {
+        Header::new(self.model)
+    }
+
     /// Returns the peripheral base memory address.
     pub(crate) fn peripheral_base(&self) -> u32 {
         self.peripheral_base
@@ -448,6 +468,15 @@ impl DeviceInfo {
         self.gpio_lines
     }
 
+    /// Returns the number of GPIO lines available for this SoC, including any lines
+    /// that are reserved for internal use.
+    pub(crate) fn gpio_lines_extended(&self) -> u8 {
+        match self.gpio_interface {
+            GpioInterface::Rp1 => GPIO_LINES_RP1_EXTENDED,
+            GpioInterface::Bcm => self.gpio_lines,
+        }
+    }
+
     /// Returns the GPIO interface type for this model.
     pub(crate) fn gpio_interface(&self) -> GpioInterface {
         self.gpio_interface
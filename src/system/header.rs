@@ -0,0 +1,205 @@
+use std::fmt;
+
+use super::Model;
+
+// Number of pins on the 26-pin and 40-pin GPIO headers
+const HEADER_PINS_SHORT: usize = 26;
+const HEADER_PINS_LONG: usize = 40;
+
+/// Pin types on the GPIO header.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum PinType {
+    /// GPIO pin, containing its BCM GPIO number.
+    Gpio(u8),
+    /// GPIO pin reserved for the HAT ID EEPROM, containing its BCM GPIO number.
+    ///
+    /// `ID_SD` is connected to GPIO0, and `ID_SC` to GPIO1.
+    IdEeprom(u8),
+    /// Ground.
+    Ground,
+    /// 3.3 V power.
+    Power3v3,
+    /// 5 V power.
+    Power5v,
+}
+
+impl PinType {
+    /// Returns the BCM GPIO number if the pin is a GPIO pin, including the
+    /// pins reserved for the HAT ID EEPROM.
+    pub fn gpio(&self) -> Option<u8> {
+        match *self {
+            PinType::Gpio(pin) | PinType::IdEeprom(pin) => Some(pin),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PinType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            PinType::Gpio(pin) => f.pad(&format!("GPIO{}", pin)),
+            PinType::IdEeprom(0) => f.pad("ID_SD"),
+            PinType::IdEeprom(1) => f.pad("ID_SC"),
+            PinType::IdEeprom(pin) => f.pad(&format!("GPIO{}", pin)),
+            PinType::Ground => f.pad("GND"),
+            PinType::Power3v3 => f.pad("3.3 V"),
+            PinType::Power5v => f.pad("5 V"),
+        }
+    }
+}
+
+// 40-pin header. The 26-pin header on the Pi A and Pi B Rev 2 is
+// identical to the first 26 pins.
+const HEADER: [PinType; HEADER_PINS_LONG] = [
+    PinType::Power3v3,    // Physical pin 1
+    PinType::Power5v,     // Physical pin 2
+    PinType::Gpio(2),     // Physical pin 3
+    PinType::Power5v,     // Physical pin 4
+    PinType::Gpio(3),     // Physical pin 5
+    PinType::Ground,      // Physical pin 6
+    PinType::Gpio(4),     // Physical pin 7
+    PinType::Gpio(14),    // Physical pin 8
+    PinType::Ground,      // Physical pin 9
+    PinType::Gpio(15),    // Physical pin 10
+    PinType::Gpio(17),    // Physical pin 11
+    PinType::Gpio(18),    // Physical pin 12
+    PinType::Gpio(27),    // Physical pin 13
+    PinType::Ground,      // Physical pin 14
+    PinType::Gpio(22),    // Physical pin 15
+    PinType::Gpio(23),    // Physical pin 16
+    PinType::Power3v3,    // Physical pin 17
+    PinType::Gpio(24),    // Physical pin 18
+    PinType::Gpio(10),    // Physical pin 19
+    PinType::Ground,      // Physical pin 20
+    PinType::Gpio(9),     // Physical pin 21
+    PinType::Gpio(25),    // Physical pin 22
+    PinType::Gpio(11),    // Physical pin 23
+    PinType::Gpio(8),     // Physical pin 24
+    PinType::Ground,      // Physical pin 25
+    PinType::Gpio(7),     // Physical pin 26
+    PinType::IdEeprom(0), // Physical pin 27
+    PinType::IdEeprom(1), // Physical pin 28
+    PinType::Gpio(5),     // Physical pin 29
+    PinType::Ground,      // Physical pin 30
+    PinType::Gpio(6),     // Physical pin 31
+    PinType::Gpio(12),    // Physical pin 32
+    PinType::Gpio(13),    // Physical pin 33
+    PinType::Ground,      // Physical pin 34
+    PinType::Gpio(19),    // Physical pin 35
+    PinType::Gpio(16),    // Physical pin 36
+    PinType::Gpio(26),    // Physical pin 37
+    PinType::Gpio(20),    // Physical pin 38
+    PinType::Ground,      // Physical pin 39
+    PinType::Gpio(21),    // Physical pin 40
+];
+
+// 26-pin header on the Pi B Rev 1. A few pins are switched compared to later models.
+const HEADER_REV1: [PinType; HEADER_PINS_SHORT] = [
+    PinType::Power3v3, // Physical pin 1
+    PinType::Power5v,  // Physical pin 2
+    PinType::Gpio(0),  // Physical pin 3
+    PinType::Power5v,  // Physical pin 4
+    PinType::Gpio(1),  // Physical pin 5
+    PinType::Ground,   // Physical pin 6
+    PinType::Gpio(4),  // Physical pin 7
+    PinType::Gpio(14), // Physical pin 8
+    PinType::Ground,   // Physical pin 9
+    PinType::Gpio(15), // Physical pin 10
+    PinType::Gpio(17), // Physical pin 11
+    PinType::Gpio(18), // Physical pin 12
+    PinType::Gpio(21), // Physical pin 13
+    PinType::Ground,   // Physical pin 14
+    PinType::Gpio(22), // Physical pin 15
+    PinType::Gpio(23), // Physical pin 16
+    PinType::Power3v3, // Physical pin 17
+    PinType::Gpio(24), // Physical pin 18
+    PinType::Gpio(10), // Physical pin 19
+    PinType::Ground,   // Physical pin 20
+    PinType::Gpio(9),  // Physical pin 21
+    PinType::Gpio(25), // Physical pin 22
+    PinType::Gpio(11), // Physical pin 23
+    PinType::Gpio(8),  // Physical pin 24
+    PinType::Ground,   // Physical pin 25
+    PinType::Gpio(7),  // Physical pin 26
+];
+
+/// GPIO header pinout.
+///
+/// `Header` lists the pins on the Raspberry Pi's 26-pin or 40-pin GPIO header,
+/// and translates between physical pin numbers and BCM GPIO numbers.
+///
+/// Physical pin numbers start at 1, and follow the header's numbering scheme,
+/// where odd-numbered pins are located on the inside row, and even-numbered pins
+/// on the outside row.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Header {
+    pins: &'static [PinType],
+}
+
+impl Header {
+    /// Constructs a new `Header` for the specified model.
+    ///
+    /// Returns `None` if the model doesn't have a GPIO header, which is the case
+    /// for the Compute Modules.
+    pub fn new(model: Model) -> Option<Header> {
+        match model {
+            Model::RaspberryPiBRev1 => Some(Header { pins: &HEADER_REV1 }),
+            Model::RaspberryPiA | Model::RaspberryPiBRev2 => Some(Header {
+                pins: &HEADER[..HEADER_PINS_SHORT],
+            }),
+            Model::RaspberryPiAPlus
+            | Model::RaspberryPiBPlus
+            | Model::RaspberryPi2B
+            | Model::RaspberryPi3APlus
+            | Model::RaspberryPi3B
+            | Model::RaspberryPi3BPlus
+            | Model::RaspberryPi4B
+            | Model::RaspberryPi400
+            | Model::RaspberryPi5
+            | Model::RaspberryPiZero
+            | Model::RaspberryPiZeroW
+            | Model::RaspberryPiZero2W => Some(Header { pins: &HEADER }),
+            Model::RaspberryPiComputeModule
+            | Model::RaspberryPiComputeModule3
+            | Model::RaspberryPiComputeModule3Plus
+            | Model::RaspberryPiComputeModule4
+            | Model::RaspberryPiComputeModule4S => None,
+        }
+    }
+
+    /// Returns all pins on the header, ordered by physical pin number.
+    ///
+    /// The first element corresponds to physical pin 1.
+    pub fn pins(&self) -> &'static [PinType] {
+        self.pins
+    }
+
+    /// Returns the pin type for the specified physical pin number.
+    ///
+    /// Returns `None` if the header doesn't contain the specified pin.
+    pub fn pin(&self, physical: u8) -> Option<PinType> {
+        if physical == 0 {
+            return None;
+        }
+
+        self.pins().get(physical as usize - 1).copied()
+    }
+
+    /// Returns the BCM GPIO number for the specified physical pin number.
+    ///
+    /// Returns `None` if the header doesn't contain the specified pin, or if
+    /// the pin isn't a GPIO pin.
+    pub fn gpio(&self, physical: u8) -> Option<u8> {
+        self.pin(physical).and_then(|pin_type| pin_type.gpio())
+    }
+
+    /// Returns the physical pin number for the specified BCM GPIO number.
+    ///
+    /// Returns `None` if the GPIO pin isn't available on the header.
+    pub fn physical(&self, gpio: u8) -> Option<u8> {
+        self.pins()
+            .iter()
+            .position(|pin_type| pin_type.gpio() == Some(gpio))
+            .map(|index| (index + 1) as u8)
+    }
+}
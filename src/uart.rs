@@ -115,6 +115,36 @@
 //! from resetting the pins. You can catch those using crates such as
 //! [`simple_signal`].
 //!
+//! ## Testing
+//!
+//! [`Uart::with_mock`] constructs a `Uart` instance that runs against a [`Mock`] device
+//! instead of a serial character device, which allows device drivers to be tested without
+//! any hardware. A `Mock` contains a script of expected [`Transaction`]s, with the outgoing
+//! data that should be sent and the incoming data that's returned to the caller. Any write
+//! that deviates from the script, or any blocking read while no incoming data is
+//! expected, returns an [`Error::Mismatch`].
+//!
+//! ## Recording
+//!
+//! [`Uart::start_recording`] writes all incoming and outgoing data to a file, until
+//! [`Uart::stop_recording`] is called. Recordings can be loaded with
+//! [`Mock::from_recording`] to replay the data without any hardware.
+//!
+//! Recordings are plain text files. The first line contains the header
+//! `rppal-recording 1 uart <start>`, where `<start>` is the time the recording was
+//! started in microseconds since the Unix epoch. Every following line contains a
+//! single call to [`read`] or [`write`] that transferred at least one byte, formatted
+//! as the number of microseconds elapsed since the start of the recording when the
+//! call completed, followed by `read` or `write` and the data in hexadecimal notation
+//! with two digits per byte. Failed calls are added as comments starting with `#`,
+//! which are ignored when the recording is replayed.
+//!
+//! ```text
+//! rppal-recording 1 uart 1700000000000000
+//! 310 write 41540d
+//! 5826 read 4f4b0d0a
+//! ```
+//!
 //! ## Troubleshooting
 //!
 //! ### Permission denied
@@ -129,19 +159,25 @@
 //! [`Uart`]: struct.Uart.html
 //! [`new`]: struct.Uart.html#method.new
 //! [`with_path`]: struct.Uart.html#method.with_path
+//! [`Uart::with_mock`]: struct.Uart.html#method.with_mock
+//! [`Mock`]: struct.Mock.html
+//! [`Transaction`]: enum.Transaction.html
+//! [`Error::Mismatch`]: enum.Error.html#variant.Mismatch
+//! [`Uart::start_recording`]: struct.Uart.html#method.start_recording
+//! [`Uart::stop_recording`]: struct.Uart.html#method.stop_recording
+//! [`Mock::from_recording`]: struct.Mock.html#method.from_recording
+//! [`read`]: struct.Uart.html#method.read
+//! [`write`]: struct.Uart.html#method.write
 
 use std::error;
 use std::fmt;
-use std::fs::{self, File, OpenOptions};
+use std::fs;
 use std::io;
-use std::io::{Read, Write};
-use std::os::unix::fs::OpenOptionsExt;
-use std::os::unix::io::{AsRawFd, RawFd};
 use std::path::Path;
 use std::result;
 use std::time::Duration;
 
-use libc::{c_int, O_NOCTTY, O_NONBLOCK};
+use libc::c_int;
 use libc::{TIOCM_CAR, TIOCM_CTS, TIOCM_DSR, TIOCM_DTR, TIOCM_RNG, TIOCM_RTS};
 
 use crate::gpio::{self, Gpio, IoPin, Mode};
@@ -149,7 +185,16 @@ use crate::system::{self, DeviceInfo, Model};
 
 #[cfg(feature = "hal")]
 mod hal;
+mod mock;
+mod record;
 mod termios;
+mod transport;
+
+pub use self::mock::{Mock, Transaction};
+
+use self::mock::MockTransport;
+use self::record::RecordingTransport;
+use self::transport::{Transport, UartDev};
 
 const GPIO_RTS: u8 = 17;
 const GPIO_CTS: u8 = 16;
@@ -172,6 +217,13 @@ pub enum Error {
     Gpio(gpio::Error),
     /// Invalid or unsupported value.
     InvalidValue,
+    /// Transaction mismatch.
+    ///
+    /// The transaction doesn't match the script of a [`Mock`]. Contains a
+    /// description of the expected and the actual transaction.
+    ///
+    /// [`Mock`]: struct.Mock.html
+    Mismatch(String),
 }
 
 impl fmt::Display for Error {
@@ -180,6 +232,7 @@ impl fmt::Display for Error {
             Error::Io(ref err) => write!(f, "I/O error: {}", err),
             Error::Gpio(ref err) => write!(f, "GPIO error: {}", err),
             Error::InvalidValue => write!(f, "Invalid or unsupported value"),
+            Error::Mismatch(ref description) => write!(f, "Transaction mismatch: {}", description),
         }
     }
 }
@@ -382,8 +435,7 @@ impl fmt::Debug for Status {
 
 #[derive(Debug)]
 struct UartInner {
-    device: File,
-    fd: RawFd,
+    transport: RecordingTransport,
     rtscts_mode: Option<(Mode, Mode)>,
     rtscts_pins: Option<(IoPin, IoPin)>,
     blocking_read: bool,
@@ -456,48 +508,68 @@ impl Uart {
             None
         };
 
-        let device = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .custom_flags(O_NOCTTY | O_NONBLOCK)
-            .open(path)?;
-
-        let fd = device.as_raw_fd();
-
-        // Enables character input mode, disables echoing and any special
-        // processing
-        termios::set_raw_mode(fd)?;
-
-        // Non-blocking reads
-        termios::set_read_mode(fd, 0, Duration::default())?;
-
-        // Ignore modem control lines (CLOCAL)
-        termios::ignore_carrier_detect(fd)?;
+        Uart::with_transport(
+            Box::new(UartDev::open(&path)?),
+            rtscts_mode,
+            baud_rate,
+            parity,
+            data_bits,
+            stop_bits,
+        )
+    }
 
-        // Enable receiver (CREAD)
-        termios::enable_read(fd)?;
+    /// Constructs a new `Uart` that runs against a scripted [`Mock`] device
+    /// instead of a serial character device.
+    ///
+    /// Every read and write is checked against the next expected transaction
+    /// in the script. More information can be found in the [`Mock`] documentation.
+    ///
+    /// [`Mock`]: struct.Mock.html
+    pub fn with_mock(
+        mock: &Mock,
+        baud_rate: u32,
+        parity: Parity,
+        data_bits: u8,
+        stop_bits: u8,
+    ) -> Result<Uart> {
+        Uart::with_transport(
+            Box::new(MockTransport::new(mock.clone())),
+            None,
+            baud_rate,
+            parity,
+            data_bits,
+            stop_bits,
+        )
+    }
 
+    fn with_transport(
+        transport: Box<dyn Transport + Send>,
+        rtscts_mode: Option<(Mode, Mode)>,
+        baud_rate: u32,
+        parity: Parity,
+        data_bits: u8,
+        stop_bits: u8,
+    ) -> Result<Uart> {
         // Disable software flow control (XON/XOFF)
-        termios::set_software_flow_control(fd, false, false)?;
+        transport.set_software_flow_control(false)?;
 
         // Disable hardware flow control (RTS/CTS)
-        termios::set_hardware_flow_control(fd, false)?;
+        transport.set_hardware_flow_control(false)?;
 
-        termios::set_line_speed(fd, baud_rate)?;
-        termios::set_parity(fd, parity)?;
-        termios::set_data_bits(fd, data_bits)?;
-        termios::set_stop_bits(fd, stop_bits)?;
+        transport.set_line_speed(baud_rate)?;
+        transport.set_parity(parity)?;
+        transport.set_data_bits(data_bits)?;
+        transport.set_stop_bits(stop_bits)?;
 
         // Pass through parity errors unfiltered
-        termios::set_parity_check(fd, ParityCheck::None)?;
+        transport.set_parity_check(ParityCheck::None)?;
 
         // Flush the input and output queue
-        termios::flush(fd, Queue::Both)?;
+        transport.flush(Queue::Both)?;
 
         Ok(Uart {
             inner: UartInner {
-                device,
-                fd,
+                transport: RecordingTransport::new(transport),
                 rtscts_mode,
                 rtscts_pins: None,
                 blocking_read: false,
@@ -532,7 +604,7 @@ impl Uart {
     ///
     /// Support for some values may be device-dependent.
     pub fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
-        termios::set_line_speed(self.inner.fd, baud_rate)?;
+        self.inner.transport.set_line_speed(baud_rate)?;
 
         self.inner.baud_rate = baud_rate;
 
@@ -550,7 +622,7 @@ impl Uart {
     ///
     /// Support for some modes may be device-dependent.
     pub fn set_parity(&mut self, parity: Parity) -> Result<()> {
-        termios::set_parity(self.inner.fd, parity)?;
+        self.inner.transport.set_parity(parity)?;
 
         self.inner.parity = parity;
 
@@ -572,7 +644,7 @@ impl Uart {
     ///
     /// [`None`]: enum.ParityCheck.html#variant.None
     pub fn set_parity_check(&mut self, parity_check: ParityCheck) -> Result<()> {
-        termios::set_parity_check(self.inner.fd, parity_check)?;
+        self.inner.transport.set_parity_check(parity_check)?;
 
         self.inner.parity_check = parity_check;
 
@@ -590,7 +662,7 @@ impl Uart {
     ///
     /// Support for some values may be device-dependent.
     pub fn set_data_bits(&mut self, data_bits: u8) -> Result<()> {
-        termios::set_data_bits(self.inner.fd, data_bits)?;
+        self.inner.transport.set_data_bits(data_bits)?;
 
         self.inner.data_bits = data_bits;
 
@@ -608,7 +680,7 @@ impl Uart {
     ///
     /// Support for some values may be device-dependent.
     pub fn set_stop_bits(&mut self, stop_bits: u8) -> Result<()> {
-        termios::set_stop_bits(self.inner.fd, stop_bits)?;
+        self.inner.transport.set_stop_bits(stop_bits)?;
 
         self.inner.stop_bits = stop_bits;
 
@@ -617,7 +689,7 @@ impl Uart {
 
     /// Returns the status of the control signals.
     pub fn status(&self) -> Result<Status> {
-        let tiocm = termios::status(self.inner.fd)?;
+        let tiocm = self.inner.transport.status()?;
 
         Ok(Status { tiocm })
     }
@@ -627,12 +699,12 @@ impl Uart {
     /// DTR is not supported by the Raspberry Pi's UART peripherals,
     /// but may be available on some USB to serial adapters.
     pub fn set_dtr(&mut self, dtr: bool) -> Result<()> {
-        termios::set_dtr(self.inner.fd, dtr)
+        self.inner.transport.set_dtr(dtr)
     }
 
     /// Sets RTS to active (`true`) or inactive (`false`).
     pub fn set_rts(&mut self, rts: bool) -> Result<()> {
-        termios::set_rts(self.inner.fd, rts)
+        self.inner.transport.set_rts(rts)
     }
 
     /// Returns `true` if XON/XOFF software flow control is enabled.
@@ -664,11 +736,9 @@ impl Uart {
     /// [`read`]: #method.read
     /// [`write`]: #method.write
     pub fn set_software_flow_control(&mut self, software_flow_control: bool) -> Result<()> {
-        termios::set_software_flow_control(
-            self.inner.fd,
-            software_flow_control,
-            software_flow_control,
-        )?;
+        self.inner
+            .transport
+            .set_software_flow_control(software_flow_control)?;
 
         self.inner.software_flow_control = software_flow_control;
 
@@ -733,7 +803,9 @@ impl Uart {
             self.inner.rtscts_pins = None;
         }
 
-        termios::set_hardware_flow_control(self.inner.fd, hardware_flow_control)?;
+        self.inner
+            .transport
+            .set_hardware_flow_control(hardware_flow_control)?;
 
         self.inner.hardware_flow_control = hardware_flow_control;
 
@@ -749,11 +821,11 @@ impl Uart {
     /// inactive state.
     pub fn send_stop(&self) -> Result<()> {
         if self.inner.software_flow_control {
-            termios::send_stop(self.inner.fd)?;
+            self.inner.transport.send_stop()?;
         }
 
         if self.inner.hardware_flow_control {
-            termios::set_rts(self.inner.fd, false)?;
+            self.inner.transport.set_rts(false)?;
         }
 
         Ok(())
@@ -768,11 +840,11 @@ impl Uart {
     /// active state.
     pub fn send_start(&self) -> Result<()> {
         if self.inner.software_flow_control {
-            termios::send_start(self.inner.fd)?;
+            self.inner.transport.send_start()?;
         }
 
         if self.inner.hardware_flow_control {
-            termios::set_rts(self.inner.fd, true)?;
+            self.inner.transport.set_rts(true)?;
         }
 
         Ok(())
@@ -825,7 +897,7 @@ impl Uart {
     ///
     /// [`read`]: #method.read
     pub fn set_read_mode(&mut self, min_length: u8, timeout: Duration) -> Result<()> {
-        termios::set_read_mode(self.inner.fd, min_length, timeout)?;
+        self.inner.transport.set_read_mode(min_length, timeout)?;
 
         self.inner.blocking_read = min_length > 0 || timeout.as_millis() > 0;
 
@@ -834,13 +906,9 @@ impl Uart {
         // leave it set when read() should block, because it ignores the
         // VMIN and VTIME settings.
         if self.inner.blocking_read || self.inner.blocking_write {
-            unsafe {
-                libc::fcntl(self.inner.fd, libc::F_SETFL, 0);
-            }
+            self.inner.transport.set_nonblocking(false);
         } else {
-            unsafe {
-                libc::fcntl(self.inner.fd, libc::F_SETFL, libc::O_NONBLOCK);
-            }
+            self.inner.transport.set_nonblocking(true);
         }
 
         Ok(())
@@ -870,13 +938,9 @@ impl Uart {
         // leave it set when read() should block, because it ignores the
         // VMIN and VTIME settings.
         if self.inner.blocking_read || self.inner.blocking_write {
-            unsafe {
-                libc::fcntl(self.inner.fd, libc::F_SETFL, 0);
-            }
+            self.inner.transport.set_nonblocking(false);
         } else {
-            unsafe {
-                libc::fcntl(self.inner.fd, libc::F_SETFL, libc::O_NONBLOCK);
-            }
+            self.inner.transport.set_nonblocking(true);
         }
 
         Ok(())
@@ -884,12 +948,12 @@ impl Uart {
 
     /// Returns the number of bytes waiting in the input queue.
     pub fn input_len(&self) -> Result<usize> {
-        termios::input_len(self.inner.fd)
+        self.inner.transport.input_len()
     }
 
     /// Returns the number of bytes waiting in the output queue.
     pub fn output_len(&self) -> Result<usize> {
-        termios::output_len(self.inner.fd)
+        self.inner.transport.output_len()
     }
 
     /// Receives incoming data from the external device and stores it in
@@ -903,13 +967,7 @@ impl Uart {
     ///
     /// [`set_read_mode`]: #method.set_read_mode
     pub fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
-        self.inner.device.read(buffer).or_else(|e| {
-            if e.kind() == io::ErrorKind::WouldBlock {
-                Ok(0)
-            } else {
-                Err(Error::Io(e))
-            }
-        })
+        self.inner.transport.read(buffer)
     }
 
     /// Sends the contents of `buffer` to the external device.
@@ -926,23 +984,13 @@ impl Uart {
         // blocking. If read() is non-blocking, either with_path() or
         // set_read_mode() will have already enabled O_NONBLOCK.
         if self.inner.blocking_read && !self.inner.blocking_write {
-            unsafe {
-                libc::fcntl(self.inner.fd, libc::F_SETFL, libc::O_NONBLOCK);
-            }
+            self.inner.transport.set_nonblocking(true);
         }
 
-        let result = self.inner.device.write(buffer).or_else(|e| {
-            if e.kind() == io::ErrorKind::WouldBlock {
-                Ok(0)
-            } else {
-                Err(Error::Io(e))
-            }
-        });
+        let result = self.inner.transport.write(buffer);
 
         if self.inner.blocking_read && !self.inner.blocking_write {
-            unsafe {
-                libc::fcntl(self.inner.fd, libc::F_SETFL, 0);
-            }
+            self.inner.transport.set_nonblocking(false);
         }
 
         result
@@ -950,11 +998,30 @@ impl Uart {
 
     /// Blocks until all data in the output queue has been transmitted.
     pub fn drain(&self) -> Result<()> {
-        termios::drain(self.inner.fd)
+        self.inner.transport.drain()
     }
 
     /// Discards all data in the input and/or output queue.
     pub fn flush(&self, queue_type: Queue) -> Result<()> {
-        termios::flush(self.inner.fd, queue_type)
+        self.inner.transport.flush(queue_type)
+    }
+
+    /// Starts recording all incoming and outgoing data to the specified file.
+    ///
+    /// If the file already exists, it's overwritten. Any active recording is
+    /// stopped first. The recording can be replayed using [`Mock::from_recording`].
+    /// More information on the file format can be found [here].
+    ///
+    /// [`Mock::from_recording`]: struct.Mock.html#method.from_recording
+    /// [here]: index.html#recording
+    pub fn start_recording<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        Ok(self.inner.transport.start(path.as_ref())?)
+    }
+
+    /// Stops the active recording, if any.
+    ///
+    /// Returns an error if any read or write couldn't be written to the file.
+    pub fn stop_recording(&mut self) -> Result<()> {
+        Ok(self.inner.transport.stop()?)
     }
 }
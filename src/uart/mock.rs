@@ -0,0 +1,268 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use libc::c_int;
+
+use crate::mock::Script;
+use crate::uart::record;
+use crate::uart::transport::Transport;
+use crate::uart::{Error, Parity, ParityCheck, Queue, Result};
+
+/// A UART transaction expected by a [`Mock`].
+///
+/// Because UART transfers a stream of bytes, a single transaction can be
+/// completed by multiple calls to [`Uart::read`] or [`Uart::write`].
+///
+/// [`Mock`]: struct.Mock.html
+/// [`Uart::read`]: struct.Uart.html#method.read
+/// [`Uart::write`]: struct.Uart.html#method.write
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transaction {
+    /// Incoming data received by [`Uart::read`].
+    ///
+    /// Each call to `read` returns as much of the remaining data as can fit in
+    /// its buffer.
+    ///
+    /// [`Uart::read`]: struct.Uart.html#method.read
+    Read { data: Vec<u8> },
+    /// Outgoing data sent by [`Uart::write`].
+    ///
+    /// Each call to `write` has to match the start of the remaining data.
+    ///
+    /// [`Uart::write`]: struct.Uart.html#method.write
+    Write { data: Vec<u8> },
+}
+
+/// A scripted serial device, used to test code that depends on [`Uart`]
+/// without accessing any hardware.
+///
+/// `Mock` contains a sequence of expected [`Transaction`]s. A `Uart` instance
+/// constructed by [`Uart::with_mock`] compares every write to the next expected
+/// transaction in the script. If a write doesn't match, or the script has already
+/// been completed, [`Uart::write`] returns `Err(`[`Error::Mismatch`]`)`, which
+/// describes both the expected and the actual transaction.
+///
+/// Similar to a serial device that hasn't received any data, a non-blocking
+/// [`Uart::read`] or a read with a timeout returns `0` unless the next expected
+/// transaction is a [`Read`]. A blocking read, configured with a `min_length` above
+/// `0` through [`Uart::set_read_mode`], would never return on a real device, and
+/// returns `Err(`[`Error::Mismatch`]`)` instead.
+///
+/// Configuration methods such as [`Uart::set_baud_rate`] and [`Uart::set_read_mode`]
+/// aren't part of the script, and any value is accepted. [`Uart::input_len`] returns
+/// the remaining length of the next expected [`Read`]. [`Uart::output_len`] returns
+/// `0`, and [`Uart::status`] reports all control signals as inactive.
+///
+/// `Mock` can be cloned to keep a handle to the script after it's been passed to
+/// `Uart`. Call [`done`] at the end of a test to verify every expected transaction
+/// was completed.
+///
+/// [`Uart`]: struct.Uart.html
+/// [`Transaction`]: enum.Transaction.html
+/// [`Read`]: enum.Transaction.html#variant.Read
+/// [`Uart::with_mock`]: struct.Uart.html#method.with_mock
+/// [`Uart::write`]: struct.Uart.html#method.write
+/// [`Uart::read`]: struct.Uart.html#method.read
+/// [`Error::Mismatch`]: enum.Error.html#variant.Mismatch
+/// [`Uart::set_baud_rate`]: struct.Uart.html#method.set_baud_rate
+/// [`Uart::set_read_mode`]: struct.Uart.html#method.set_read_mode
+/// [`Uart::input_len`]: struct.Uart.html#method.input_len
+/// [`Uart::output_len`]: struct.Uart.html#method.output_len
+/// [`Uart::status`]: struct.Uart.html#method.status
+/// [`done`]: #method.done
+#[derive(Clone, Debug)]
+pub struct Mock {
+    script: Arc<Mutex<Script<Transaction>>>,
+}
+
+impl Mock {
+    /// Constructs a new `Mock` that expects the specified sequence of transactions.
+    pub fn new<I>(transactions: I) -> Mock
+    where
+        I: IntoIterator<Item = Transaction>,
+    {
+        Mock {
+            script: Arc::new(Mutex::new(Script::new(transactions))),
+        }
+    }
+
+    /// Constructs a new `Mock` that expects the transactions stored in a recording.
+    ///
+    /// Recordings are created by [`Uart::start_recording`]. Timestamps and failed
+    /// reads or writes are ignored.
+    ///
+    /// Returns `Err(`[`Error::Io`]`)` if the file can't be read or isn't a valid
+    /// UART recording.
+    ///
+    /// [`Uart::start_recording`]: struct.Uart.html#method.start_recording
+    /// [`Error::Io`]: enum.Error.html#variant.Io
+    pub fn from_recording<P: AsRef<Path>>(path: P) -> Result<Mock> {
+        Ok(Mock::new(record::parse(path.as_ref())?))
+    }
+
+    /// Appends a transaction to the end of the script.
+    pub fn expect(&self, transaction: Transaction) {
+        self.script.lock().unwrap().push(transaction);
+    }
+
+    /// Verifies all expected transactions have been completed.
+    ///
+    /// Returns `Err(`[`Error::Mismatch`]`)` if any read or write didn't match the
+    /// script, or if any expected transactions remain.
+    ///
+    /// [`Error::Mismatch`]: enum.Error.html#variant.Mismatch
+    pub fn done(&self) -> Result<()> {
+        self.script.lock().unwrap().done().map_err(Error::Mismatch)
+    }
+}
+
+// Transport used by a Uart instance constructed with Uart::with_mock
+#[derive(Debug)]
+pub(crate) struct MockTransport {
+    mock: Mock,
+    // Minimum number of bytes configured for a blocking read
+    min_length: AtomicU8,
+}
+
+impl MockTransport {
+    pub(crate) fn new(mock: Mock) -> MockTransport {
+        MockTransport {
+            mock,
+            min_length: AtomicU8::new(0),
+        }
+    }
+}
+
+impl Transport for MockTransport {
+    fn set_line_speed(&self, _line_speed: u32) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_parity(&self, _parity: Parity) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_parity_check(&self, _parity_check: ParityCheck) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_data_bits(&self, _data_bits: u8) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_stop_bits(&self, _stop_bits: u8) -> Result<()> {
+        Ok(())
+    }
+
+    fn status(&self) -> Result<c_int> {
+        Ok(0)
+    }
+
+    fn set_dtr(&self, _dtr: bool) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_rts(&self, _rts: bool) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_software_flow_control(&self, _enabled: bool) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_hardware_flow_control(&self, _enabled: bool) -> Result<()> {
+        Ok(())
+    }
+
+    fn send_stop(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn send_start(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_read_mode(&self, min_length: u8, _timeout: Duration) -> Result<()> {
+        self.min_length.store(min_length, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    fn set_nonblocking(&self, _nonblocking: bool) {}
+
+    fn input_len(&self) -> Result<usize> {
+        match self.mock.script.lock().unwrap().expected() {
+            Some(Transaction::Read { data }) => Ok(data.len()),
+            _ => Ok(0),
+        }
+    }
+
+    fn output_len(&self) -> Result<usize> {
+        Ok(0)
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        if buffer.is_empty() {
+            return Ok(0);
+        }
+
+        let mut script = self.mock.script.lock().unwrap();
+
+        // Similar to a serial device, no incoming data is available until the
+        // preceding transactions have been completed
+        match script.expected() {
+            Some(Transaction::Read { data }) => {
+                let len = data.len().min(buffer.len());
+                buffer[..len].copy_from_slice(&data[..len]);
+                data.drain(..len);
+
+                if data.is_empty() {
+                    script.complete();
+                }
+
+                Ok(len)
+            }
+            // A blocking read would wait indefinitely for data that never arrives
+            _ if self.min_length.load(Ordering::SeqCst) > 0 => {
+                Err(Error::Mismatch(script.mismatch(format_args!(
+                    "blocking read of up to {} bytes",
+                    buffer.len()
+                ))))
+            }
+            _ => Ok(0),
+        }
+    }
+
+    fn write(&mut self, buffer: &[u8]) -> Result<usize> {
+        if buffer.is_empty() {
+            return Ok(0);
+        }
+
+        let mut script = self.mock.script.lock().unwrap();
+
+        match script.expected() {
+            Some(Transaction::Write { data }) if data.starts_with(buffer) => {
+                data.drain(..buffer.len());
+
+                if data.is_empty() {
+                    script.complete();
+                }
+
+                Ok(buffer.len())
+            }
+            _ => Err(Error::Mismatch(
+                script.mismatch(format_args!("Write {{ data: {:?} }}", buffer)),
+            )),
+        }
+    }
+
+    fn drain(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn flush(&self, _queue_type: Queue) -> Result<()> {
+        Ok(())
+    }
+}
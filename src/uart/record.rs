@@ -0,0 +1,177 @@
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use libc::c_int;
+
+use crate::record::{self, Hex, Recording};
+use crate::uart::mock::Transaction;
+use crate::uart::transport::Transport;
+use crate::uart::{Parity, ParityCheck, Queue, Result};
+
+const BUS: &str = "uart";
+
+// Forwards all operations to the underlying transport, and adds every read and
+// write to the active recording, if any. Reads and writes that don't transfer
+// any data aren't recorded.
+#[derive(Debug)]
+pub(crate) struct RecordingTransport {
+    transport: Box<dyn Transport + Send>,
+    recording: Option<Recording>,
+}
+
+impl RecordingTransport {
+    pub(crate) fn new(transport: Box<dyn Transport + Send>) -> RecordingTransport {
+        RecordingTransport {
+            transport,
+            recording: None,
+        }
+    }
+
+    pub(crate) fn start(&mut self, path: &Path) -> io::Result<()> {
+        self.stop()?;
+        self.recording = Some(Recording::create(path, BUS)?);
+
+        Ok(())
+    }
+
+    pub(crate) fn stop(&mut self) -> io::Result<()> {
+        match self.recording.take() {
+            Some(recording) => recording.finish(),
+            None => Ok(()),
+        }
+    }
+
+    fn record<F>(&mut self, name: &str, result: &Result<usize>, transaction: F)
+    where
+        F: FnOnce(usize) -> Transaction,
+    {
+        if let Some(recording) = self.recording.as_mut() {
+            match *result {
+                Ok(0) => (),
+                Ok(len) => recording.record(&Line(&transaction(len))),
+                Err(ref e) => recording.record_error(name, e),
+            }
+        }
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn set_line_speed(&self, line_speed: u32) -> Result<()> {
+        self.transport.set_line_speed(line_speed)
+    }
+
+    fn set_parity(&self, parity: Parity) -> Result<()> {
+        self.transport.set_parity(parity)
+    }
+
+    fn set_parity_check(&self, parity_check: ParityCheck) -> Result<()> {
+        self.transport.set_parity_check(parity_check)
+    }
+
+    fn set_data_bits(&self, data_bits: u8) -> Result<()> {
+        self.transport.set_data_bits(data_bits)
+    }
+
+    fn set_stop_bits(&self, stop_bits: u8) -> Result<()> {
+        self.transport.set_stop_bits(stop_bits)
+    }
+
+    fn status(&self) -> Result<c_int> {
+        self.transport.status()
+    }
+
+    fn set_dtr(&self, dtr: bool) -> Result<()> {
+        self.transport.set_dtr(dtr)
+    }
+
+    fn set_rts(&self, rts: bool) -> Result<()> {
+        self.transport.set_rts(rts)
+    }
+
+    fn set_software_flow_control(&self, enabled: bool) -> Result<()> {
+        self.transport.set_software_flow_control(enabled)
+    }
+
+    fn set_hardware_flow_control(&self, enabled: bool) -> Result<()> {
+        self.transport.set_hardware_flow_control(enabled)
+    }
+
+    fn send_stop(&self) -> Result<()> {
+        self.transport.send_stop()
+    }
+
+    fn send_start(&self) -> Result<()> {
+        self.transport.send_start()
+    }
+
+    fn set_read_mode(&self, min_length: u8, timeout: Duration) -> Result<()> {
+        self.transport.set_read_mode(min_length, timeout)
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) {
+        self.transport.set_nonblocking(nonblocking)
+    }
+
+    fn input_len(&self) -> Result<usize> {
+        self.transport.input_len()
+    }
+
+    fn output_len(&self) -> Result<usize> {
+        self.transport.output_len()
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        let result = self.transport.read(buffer);
+        self.record("read", &result, |len| Transaction::Read {
+            data: buffer[..len].to_vec(),
+        });
+
+        result
+    }
+
+    fn write(&mut self, buffer: &[u8]) -> Result<usize> {
+        let result = self.transport.write(buffer);
+        self.record("write", &result, |len| Transaction::Write {
+            data: buffer[..len].to_vec(),
+        });
+
+        result
+    }
+
+    fn drain(&self) -> Result<()> {
+        self.transport.drain()
+    }
+
+    fn flush(&self, queue_type: Queue) -> Result<()> {
+        self.transport.flush(queue_type)
+    }
+}
+
+// Formats a transaction as a single line of a recording, without the timestamp
+struct Line<'a>(&'a Transaction);
+
+impl fmt::Display for Line<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self.0 {
+            Transaction::Read { ref data } => write!(f, "read {}", Hex(data)),
+            Transaction::Write { ref data } => write!(f, "write {}", Hex(data)),
+        }
+    }
+}
+
+// Reads all transactions from the recording at path
+pub(crate) fn parse(path: &Path) -> io::Result<Vec<Transaction>> {
+    record::parse(path, BUS, |name, fields| {
+        Ok(match name {
+            "read" => Transaction::Read {
+                data: fields.next_data()?,
+            },
+            "write" => Transaction::Write {
+                data: fields.next_data()?,
+            },
+            _ => return Err(record::unknown_transaction(name)),
+        })
+    })
+}
@@ -0,0 +1,173 @@
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::Path;
+use std::time::Duration;
+
+use libc::{c_int, O_NOCTTY, O_NONBLOCK};
+
+use crate::uart::termios;
+use crate::uart::{Error, Parity, ParityCheck, Queue, Result};
+
+// Operations performed by Uart on the underlying device. UartDev forwards them
+// to the serial character device, while Mock checks them against a script of
+// expected transactions.
+pub(crate) trait Transport: fmt::Debug {
+    fn set_line_speed(&self, line_speed: u32) -> Result<()>;
+    fn set_parity(&self, parity: Parity) -> Result<()>;
+    fn set_parity_check(&self, parity_check: ParityCheck) -> Result<()>;
+    fn set_data_bits(&self, data_bits: u8) -> Result<()>;
+    fn set_stop_bits(&self, stop_bits: u8) -> Result<()>;
+    fn status(&self) -> Result<c_int>;
+    fn set_dtr(&self, dtr: bool) -> Result<()>;
+    fn set_rts(&self, rts: bool) -> Result<()>;
+    fn set_software_flow_control(&self, enabled: bool) -> Result<()>;
+    fn set_hardware_flow_control(&self, enabled: bool) -> Result<()>;
+    fn send_stop(&self) -> Result<()>;
+    fn send_start(&self) -> Result<()>;
+    fn set_read_mode(&self, min_length: u8, timeout: Duration) -> Result<()>;
+    fn set_nonblocking(&self, nonblocking: bool);
+    fn input_len(&self) -> Result<usize>;
+    fn output_len(&self) -> Result<usize>;
+    // Returns Ok(0) instead of WouldBlock when no data is available
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize>;
+    // Returns Ok(0) instead of WouldBlock when the output queue is full
+    fn write(&mut self, buffer: &[u8]) -> Result<usize>;
+    fn drain(&self) -> Result<()>;
+    fn flush(&self, queue_type: Queue) -> Result<()>;
+}
+
+#[derive(Debug)]
+pub(crate) struct UartDev {
+    device: File,
+    fd: RawFd,
+}
+
+impl UartDev {
+    pub(crate) fn open(path: &Path) -> Result<UartDev> {
+        let device = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(O_NOCTTY | O_NONBLOCK)
+            .open(path)?;
+
+        let fd = device.as_raw_fd();
+
+        // Enables character input mode, disables echoing and any special
+        // processing
+        termios::set_raw_mode(fd)?;
+
+        // Non-blocking reads
+        termios::set_read_mode(fd, 0, Duration::default())?;
+
+        // Ignore modem control lines (CLOCAL)
+        termios::ignore_carrier_detect(fd)?;
+
+        // Enable receiver (CREAD)
+        termios::enable_read(fd)?;
+
+        Ok(UartDev { device, fd })
+    }
+}
+
+impl Transport for UartDev {
+    fn set_line_speed(&self, line_speed: u32) -> Result<()> {
+        termios::set_line_speed(self.fd, line_speed)
+    }
+
+    fn set_parity(&self, parity: Parity) -> Result<()> {
+        termios::set_parity(self.fd, parity)
+    }
+
+    fn set_parity_check(&self, parity_check: ParityCheck) -> Result<()> {
+        termios::set_parity_check(self.fd, parity_check)
+    }
+
+    fn set_data_bits(&self, data_bits: u8) -> Result<()> {
+        termios::set_data_bits(self.fd, data_bits)
+    }
+
+    fn set_stop_bits(&self, stop_bits: u8) -> Result<()> {
+        termios::set_stop_bits(self.fd, stop_bits)
+    }
+
+    fn status(&self) -> Result<c_int> {
+        termios::status(self.fd)
+    }
+
+    fn set_dtr(&self, dtr: bool) -> Result<()> {
+        termios::set_dtr(self.fd, dtr)
+    }
+
+    fn set_rts(&self, rts: bool) -> Result<()> {
+        termios::set_rts(self.fd, rts)
+    }
+
+    fn set_software_flow_control(&self, enabled: bool) -> Result<()> {
+        termios::set_software_flow_control(self.fd, enabled, enabled)
+    }
+
+    fn set_hardware_flow_control(&self, enabled: bool) -> Result<()> {
+        termios::set_hardware_flow_control(self.fd, enabled)
+    }
+
+    fn send_stop(&self) -> Result<()> {
+        termios::send_stop(self.fd)
+    }
+
+    fn send_start(&self) -> Result<()> {
+        termios::send_start(self.fd)
+    }
+
+    fn set_read_mode(&self, min_length: u8, timeout: Duration) -> Result<()> {
+        termios::set_read_mode(self.fd, min_length, timeout)
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) {
+        unsafe {
+            libc::fcntl(
+                self.fd,
+                libc::F_SETFL,
+                if nonblocking { libc::O_NONBLOCK } else { 0 },
+            );
+        }
+    }
+
+    fn input_len(&self) -> Result<usize> {
+        termios::input_len(self.fd)
+    }
+
+    fn output_len(&self) -> Result<usize> {
+        termios::output_len(self.fd)
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        self.device.read(buffer).or_else(|e| {
+            if e.kind() == io::ErrorKind::WouldBlock {
+                Ok(0)
+            } else {
+                Err(Error::Io(e))
+            }
+        })
+    }
+
+    fn write(&mut self, buffer: &[u8]) -> Result<usize> {
+        self.device.write(buffer).or_else(|e| {
+            if e.kind() == io::ErrorKind::WouldBlock {
+                Ok(0)
+            } else {
+                Err(Error::Io(e))
+            }
+        })
+    }
+
+    fn drain(&self) -> Result<()> {
+        termios::drain(self.fd)
+    }
+
+    fn flush(&self, queue_type: Queue) -> Result<()> {
+        termios::flush(self.fd, queue_type)
+    }
+}
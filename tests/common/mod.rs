@@ -0,0 +1,45 @@
+use std::fmt;
+
+use rppal::{i2c, spi, uart};
+
+// Errors returned by the mock transports
+pub trait MockError: fmt::Debug {
+    // Returns the description if the error is a mismatch
+    fn mismatch(&self) -> Option<&str>;
+}
+
+impl MockError for i2c::Error {
+    fn mismatch(&self) -> Option<&str> {
+        match self {
+            i2c::Error::Mismatch(description) => Some(description),
+            _ => None,
+        }
+    }
+}
+
+impl MockError for spi::Error {
+    fn mismatch(&self) -> Option<&str> {
+        match self {
+            spi::Error::Mismatch(description) => Some(description),
+            _ => None,
+        }
+    }
+}
+
+impl MockError for uart::Error {
+    fn mismatch(&self) -> Option<&str> {
+        match self {
+            uart::Error::Mismatch(description) => Some(description),
+            _ => None,
+        }
+    }
+}
+
+// Returns the description of the mismatch reported by a mock transport
+pub fn mismatch<T: fmt::Debug, E: MockError>(result: Result<T, E>) -> String {
+    if let Err(Some(description)) = result.as_ref().map_err(MockError::mismatch) {
+        return description.to_owned();
+    }
+
+    panic!("expected mismatch, got {:?}", result);
+}
@@ -0,0 +1,123 @@
+use std::sync::{Arc, Mutex};
+
+use rppal::gpio::{Backend, Bias, Error, Gpio, Level, Mode, Registers, Result, Trigger};
+use rppal::system::Model;
+
+#[derive(Debug, Clone, Copy)]
+struct Line {
+    level: Level,
+    mode: Mode,
+    bias: Bias,
+}
+
+// Stores the state of each pin, which is shared with the test
+#[derive(Debug, Clone)]
+struct MemoryRegisters {
+    lines: Arc<Mutex<Vec<Line>>>,
+}
+
+impl MemoryRegisters {
+    fn new() -> MemoryRegisters {
+        let line = Line {
+            level: Level::Low,
+            mode: Mode::Input,
+            bias: Bias::Off,
+        };
+
+        MemoryRegisters {
+            lines: Arc::new(Mutex::new(vec![line; 64])),
+        }
+    }
+
+    fn line(&self, pin: u8) -> Line {
+        self.lines.lock().unwrap()[pin as usize]
+    }
+}
+
+impl Registers for MemoryRegisters {
+    fn level(&self, pin: u8) -> Level {
+        self.line(pin).level
+    }
+
+    fn set_level(&self, pin: u8, level: Level) -> Result<()> {
+        self.lines.lock().unwrap()[pin as usize].level = level;
+
+        Ok(())
+    }
+
+    fn mode(&self, pin: u8) -> Mode {
+        self.line(pin).mode
+    }
+
+    fn set_mode(&self, pin: u8, mode: Mode) -> Result<()> {
+        self.lines.lock().unwrap()[pin as usize].mode = mode;
+
+        Ok(())
+    }
+
+    fn bias(&self, pin: u8) -> Result<Bias> {
+        Ok(self.line(pin).bias)
+    }
+
+    fn set_bias(&self, pin: u8, bias: Bias) -> Result<()> {
+        self.lines.lock().unwrap()[pin as usize].bias = bias;
+
+        Ok(())
+    }
+}
+
+fn custom() -> (Gpio, MemoryRegisters) {
+    let registers = MemoryRegisters::new();
+    let gpio = Gpio::with_registers(Model::RaspberryPi4B, registers.clone()).unwrap();
+
+    (gpio, registers)
+}
+
+#[test]
+fn backend() {
+    let (gpio, _registers) = custom();
+
+    assert_eq!(gpio.backend(), Backend::Custom);
+}
+
+#[test]
+fn output() {
+    let (gpio, registers) = custom();
+    let mut pin = gpio.get(17).unwrap().into_output_low();
+
+    assert_eq!(registers.line(17).mode, Mode::Output);
+    assert_eq!(registers.line(17).level, Level::Low);
+
+    pin.set_high().unwrap();
+    assert_eq!(registers.line(17).level, Level::High);
+
+    drop(pin);
+    assert_eq!(registers.line(17).mode, Mode::Input);
+}
+
+#[test]
+fn input() {
+    let (gpio, registers) = custom();
+    let pin = gpio.get(17).unwrap().into_input_pullup();
+
+    assert_eq!(registers.line(17).mode, Mode::Input);
+    assert_eq!(registers.line(17).bias, Bias::PullUp);
+
+    registers.set_level(17, Level::High).unwrap();
+    assert_eq!(pin.read(), Level::High);
+
+    // The original bias is restored on drop
+    drop(pin);
+    assert_eq!(registers.line(17).bias, Bias::Off);
+}
+
+#[test]
+fn interrupt_not_supported() {
+    let (gpio, _registers) = custom();
+    let mut pin = gpio.get(17).unwrap().into_input();
+
+    assert!(matches!(
+        pin.set_interrupt(Trigger::Both, None),
+        Err(Error::NotSupported)
+    ));
+}
@@ -0,0 +1,317 @@
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use rppal::gpio::{Bias, Gpio, Level, Mode, Simulator, Trigger};
+use rppal::system::Model;
+
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+fn simulated() -> (Gpio, Simulator) {
+    let gpio = Gpio::simulated(Model::RaspberryPi4B).unwrap();
+    let sim = gpio.simulator().unwrap();
+
+    (gpio, sim)
+}
+
+#[test]
+fn input_level() {
+    let (gpio, sim) = simulated();
+    let pin = gpio.get(17).unwrap().into_input();
+
+    assert_eq!(pin.read(), Level::Low);
+
+    sim.set_level(17, Level::High).unwrap();
+    assert_eq!(pin.read(), Level::High);
+
+    sim.set_level(17, Level::Low).unwrap();
+    assert_eq!(pin.read(), Level::Low);
+}
+
+#[test]
+fn input_bias() {
+    let (gpio, sim) = simulated();
+    let pin = gpio.get(17).unwrap().into_input_pullup();
+
+    assert_eq!(sim.mode(17).unwrap(), Mode::Input);
+    assert_eq!(sim.bias(17).unwrap(), Bias::PullUp);
+    assert_eq!(pin.read(), Level::High);
+
+    sim.set_level(17, Level::Low).unwrap();
+    assert_eq!(pin.read(), Level::Low);
+
+    sim.clear_level(17).unwrap();
+    assert_eq!(pin.read(), Level::High);
+}
+
+#[test]
+fn output_level() {
+    let (gpio, sim) = simulated();
+    let mut pin = gpio.get(17).unwrap().into_output_low();
+
+    assert_eq!(sim.mode(17).unwrap(), Mode::Output);
+    assert_eq!(sim.level(17).unwrap(), Level::Low);
+
+    pin.set_high().unwrap();
+    assert_eq!(sim.level(17).unwrap(), Level::High);
+
+    // Outputs take precedence over external devices
+    sim.set_level(17, Level::Low).unwrap();
+    assert_eq!(sim.level(17).unwrap(), Level::High);
+
+    pin.toggle().unwrap();
+    assert_eq!(sim.level(17).unwrap(), Level::Low);
+}
+
+#[test]
+fn io_mode_and_bias() {
+    let (gpio, sim) = simulated();
+    let mut pin = gpio.get(17).unwrap().into_io(Mode::Input);
+
+    assert_eq!(sim.mode(17).unwrap(), Mode::Input);
+
+    pin.set_bias(Bias::PullDown).unwrap();
+    assert_eq!(sim.bias(17).unwrap(), Bias::PullDown);
+
+    pin.set_mode(Mode::Alt0).unwrap();
+    assert_eq!(sim.mode(17).unwrap(), Mode::Alt0);
+
+    pin.set_mode(Mode::Output).unwrap();
+    pin.set_high().unwrap();
+    assert_eq!(sim.mode(17).unwrap(), Mode::Output);
+    assert_eq!(sim.level(17).unwrap(), Level::High);
+}
+
+#[test]
+fn active_low() {
+    let (gpio, sim) = simulated();
+    let mut pin = gpio.get(17).unwrap().into_input();
+
+    // The simulated backend inverts active-low pins in software
+    pin.set_active_low(true).unwrap();
+    assert!(pin.is_active_low());
+
+    sim.set_level(17, Level::High).unwrap();
+    assert_eq!(pin.read(), Level::Low);
+
+    sim.set_level(17, Level::Low).unwrap();
+    assert_eq!(pin.read(), Level::High);
+}
+
+#[test]
+fn reset_on_drop() {
+    let (gpio, sim) = simulated();
+    let pin = gpio.get(17).unwrap().into_output();
+
+    assert_eq!(sim.mode(17).unwrap(), Mode::Output);
+
+    drop(pin);
+    assert_eq!(sim.mode(17).unwrap(), Mode::Input);
+}
+
+#[test]
+fn open_drain() {
+    let (gpio, sim) = simulated();
+    let mut pin = gpio.get(17).unwrap().into_output_open_drain().unwrap();
+
+    // An external pull-up resistor pulls the line high while it's released
+    sim.set_level(17, Level::High).unwrap();
+
+    pin.set_low().unwrap();
+    assert_eq!(sim.mode(17).unwrap(), Mode::Output);
+    assert_eq!(sim.level(17).unwrap(), Level::Low);
+
+    pin.set_high().unwrap();
+    assert_eq!(sim.mode(17).unwrap(), Mode::Input);
+    assert_eq!(sim.level(17).unwrap(), Level::High);
+
+    // Another device on the bus can pull the released line low
+    sim.set_level(17, Level::Low).unwrap();
+    assert_eq!(sim.level(17).unwrap(), Level::Low);
+}
+
+#[test]
+fn poll_interrupt() {
+    let (gpio, sim) = simulated();
+    let mut pin = gpio.get(17).unwrap().into_input();
+
+    pin.set_interrupt(Trigger::Both, None).unwrap();
+
+    sim.set_level(17, Level::High).unwrap();
+    let event = pin.poll_interrupt(false, Some(TIMEOUT)).unwrap().unwrap();
+    assert_eq!(event.trigger, Trigger::RisingEdge);
+
+    sim.set_level(17, Level::Low).unwrap();
+    let next_event = pin.poll_interrupt(false, Some(TIMEOUT)).unwrap().unwrap();
+    assert_eq!(next_event.trigger, Trigger::FallingEdge);
+    assert!(next_event.timestamp >= event.timestamp);
+    assert_eq!(next_event.line_seqno, event.line_seqno + 1);
+    assert_eq!(next_event.missed, 0);
+
+    // No level change, no edge
+    sim.set_level(17, Level::Low).unwrap();
+    assert!(pin
+        .poll_interrupt(false, Some(Duration::from_millis(10)))
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn poll_interrupt_trigger() {
+    let (gpio, sim) = simulated();
+    let mut pin = gpio.get(17).unwrap().into_input();
+
+    pin.set_interrupt(Trigger::FallingEdge, None).unwrap();
+
+    sim.set_level(17, Level::High).unwrap();
+    sim.set_level(17, Level::Low).unwrap();
+
+    let event = pin.poll_interrupt(false, Some(TIMEOUT)).unwrap().unwrap();
+    assert_eq!(event.trigger, Trigger::FallingEdge);
+    assert!(pin
+        .poll_interrupt(false, Some(Duration::from_millis(10)))
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn poll_interrupts_batch() {
+    let (gpio, sim) = simulated();
+    let mut pin17 = gpio.get(17).unwrap().into_input();
+    let mut pin27 = gpio.get(27).unwrap().into_input();
+
+    pin17.set_interrupt(Trigger::Both, None).unwrap();
+    pin27.set_interrupt(Trigger::Both, None).unwrap();
+
+    sim.set_level(17, Level::High).unwrap();
+    sim.set_level(17, Level::Low).unwrap();
+    sim.set_level(27, Level::High).unwrap();
+
+    let events = gpio
+        .poll_interrupts_batch(&[&pin17, &pin27], false, Some(TIMEOUT))
+        .unwrap();
+    let triggers: Vec<(u8, Trigger)> = events
+        .iter()
+        .map(|(pin, event)| (pin.pin(), event.trigger))
+        .collect();
+
+    assert_eq!(
+        triggers,
+        [
+            (17, Trigger::RisingEdge),
+            (17, Trigger::FallingEdge),
+            (27, Trigger::RisingEdge)
+        ]
+    );
+}
+
+#[test]
+fn debounce() {
+    let (gpio, sim) = simulated();
+    let mut pin = gpio.get(17).unwrap().into_input();
+
+    pin.set_interrupt(Trigger::Both, Some(Duration::from_millis(100)))
+        .unwrap();
+
+    // Edges within the debounce period after the first edge are dropped
+    sim.set_level(17, Level::High).unwrap();
+    sim.set_level(17, Level::Low).unwrap();
+    sim.set_level(17, Level::High).unwrap();
+
+    let event = pin.poll_interrupt(false, Some(TIMEOUT)).unwrap().unwrap();
+    assert_eq!(event.trigger, Trigger::RisingEdge);
+    assert!(pin
+        .poll_interrupt(false, Some(Duration::from_millis(10)))
+        .unwrap()
+        .is_none());
+
+    thread::sleep(Duration::from_millis(150));
+
+    sim.set_level(17, Level::Low).unwrap();
+    let event = pin.poll_interrupt(false, Some(TIMEOUT)).unwrap().unwrap();
+    assert_eq!(event.trigger, Trigger::FallingEdge);
+    assert_eq!(event.missed, 0);
+}
+
+#[test]
+fn missed_events() {
+    let (gpio, sim) = simulated();
+    let mut pin = gpio.get(17).unwrap().into_input();
+
+    pin.set_interrupt(Trigger::Both, None).unwrap();
+
+    // Overflows the event buffer, so the most recent edges are dropped
+    let mut level = Level::Low;
+    for _ in 0..10_000 {
+        level = !level;
+        sim.set_level(17, level).unwrap();
+    }
+
+    let mut queued = 0;
+    while let Some(event) = pin
+        .poll_interrupt(false, Some(Duration::from_millis(10)))
+        .unwrap()
+    {
+        assert_eq!(event.missed, 0);
+        queued += 1;
+    }
+
+    assert!(queued < 10_000);
+
+    sim.set_level(17, !level).unwrap();
+    let event = pin.poll_interrupt(false, Some(TIMEOUT)).unwrap().unwrap();
+    assert_eq!(event.missed, 10_000 - queued);
+}
+
+#[test]
+fn async_interrupt() {
+    let (gpio, sim) = simulated();
+    let mut pin = gpio.get(17).unwrap().into_input();
+    let (tx, rx) = mpsc::channel();
+
+    pin.set_async_interrupt(Trigger::Both, None, move |event| {
+        let _ = tx.send(event.trigger);
+    })
+    .unwrap();
+
+    sim.set_level(17, Level::High).unwrap();
+    assert_eq!(rx.recv_timeout(TIMEOUT).unwrap(), Trigger::RisingEdge);
+
+    sim.set_level(17, Level::Low).unwrap();
+    assert_eq!(rx.recv_timeout(TIMEOUT).unwrap(), Trigger::FallingEdge);
+
+    pin.clear_async_interrupt().unwrap();
+
+    sim.set_level(17, Level::High).unwrap();
+    assert!(rx.recv_timeout(Duration::from_millis(10)).is_err());
+}
+
+#[test]
+fn async_interrupt_debounce() {
+    let (gpio, sim) = simulated();
+    let mut pin = gpio.get(17).unwrap().into_input();
+    let (tx, rx) = mpsc::channel();
+
+    pin.set_async_interrupt(
+        Trigger::Both,
+        Some(Duration::from_millis(100)),
+        move |event| {
+            let _ = tx.send(event.trigger);
+        },
+    )
+    .unwrap();
+
+    sim.set_level(17, Level::High).unwrap();
+    sim.set_level(17, Level::Low).unwrap();
+
+    assert_eq!(rx.recv_timeout(TIMEOUT).unwrap(), Trigger::RisingEdge);
+    assert!(rx.recv_timeout(Duration::from_millis(10)).is_err());
+}
+
+#[test]
+fn pin_not_available() {
+    let (_gpio, sim) = simulated();
+
+    assert!(sim.set_level(200, Level::High).is_err());
+    assert!(sim.level(200).is_err());
+}
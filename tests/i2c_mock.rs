@@ -0,0 +1,250 @@
+use rppal::i2c::{I2c, Mock, Transaction};
+
+mod common;
+
+use common::mismatch;
+
+#[test]
+fn matching_transactions() {
+    let mock = Mock::new([
+        Transaction::Write {
+            address: 0x20,
+            data: vec![0x01, 0x02],
+        },
+        Transaction::Read {
+            address: 0x20,
+            data: vec![0xaa, 0xbb],
+        },
+        Transaction::WriteRead {
+            address: 0x20,
+            write: vec![0x03],
+            read: vec![0xcc],
+        },
+        Transaction::SmbusReadByte {
+            address: 0x21,
+            command: 0x10,
+            value: 0x42,
+        },
+    ]);
+
+    let mut i2c = I2c::with_mock(&mock).unwrap();
+    i2c.set_slave_address(0x20).unwrap();
+
+    assert_eq!(i2c.write(&[0x01, 0x02]).unwrap(), 2);
+
+    let mut buffer = [0u8; 2];
+    assert_eq!(i2c.read(&mut buffer).unwrap(), 2);
+    assert_eq!(buffer, [0xaa, 0xbb]);
+
+    let mut buffer = [0u8; 1];
+    i2c.write_read(&[0x03], &mut buffer).unwrap();
+    assert_eq!(buffer, [0xcc]);
+
+    i2c.set_slave_address(0x21).unwrap();
+    assert_eq!(i2c.smbus_read_byte(0x10).unwrap(), 0x42);
+
+    mock.done().unwrap();
+}
+
+#[test]
+fn expect() {
+    let mock = Mock::new([]);
+    mock.expect(Transaction::SmbusSendByte {
+        address: 0x20,
+        value: 0x01,
+    });
+
+    let mut i2c = I2c::with_mock(&mock).unwrap();
+    i2c.set_slave_address(0x20).unwrap();
+    i2c.smbus_send_byte(0x01).unwrap();
+
+    mock.done().unwrap();
+}
+
+#[test]
+fn mismatch_data() {
+    let mock = Mock::new([Transaction::Write {
+        address: 0x20,
+        data: vec![0x01],
+    }]);
+
+    let mut i2c = I2c::with_mock(&mock).unwrap();
+    i2c.set_slave_address(0x20).unwrap();
+
+    assert_eq!(
+        mismatch(i2c.write(&[0x02])),
+        "transaction 0: expected Write { address: 32, data: [1] }, \
+         got Write { address: 32, data: [2] }"
+    );
+}
+
+#[test]
+fn mismatch_address() {
+    let mock = Mock::new([
+        Transaction::SmbusWriteByte {
+            address: 0x20,
+            command: 0x01,
+            value: 0x02,
+        },
+        Transaction::SmbusReadByte {
+            address: 0x20,
+            command: 0x03,
+            value: 0x04,
+        },
+    ]);
+
+    let mut i2c = I2c::with_mock(&mock).unwrap();
+    i2c.set_slave_address(0x20).unwrap();
+    i2c.smbus_write_byte(0x01, 0x02).unwrap();
+    i2c.set_slave_address(0x21).unwrap();
+
+    assert_eq!(
+        mismatch(i2c.smbus_read_byte(0x03)),
+        "transaction 1: expected SmbusReadByte { address: 32, command: 3, value: 4 }, \
+         got SmbusReadByte { address: 33, command: 3 }"
+    );
+}
+
+#[test]
+fn mismatch_end_of_script() {
+    let mock = Mock::new([]);
+
+    let i2c = I2c::with_mock(&mock).unwrap();
+
+    assert_eq!(
+        mismatch(i2c.smbus_quick_command(true)),
+        "transaction 0: expected end of script, got SmbusQuickCommand { address: 0, command: true }"
+    );
+}
+
+#[test]
+fn done_reports_discarded_mismatch() {
+    let mock = Mock::new([
+        Transaction::SmbusSendByte {
+            address: 0x20,
+            value: 0x01,
+        },
+        Transaction::SmbusSendByte {
+            address: 0x20,
+            value: 0x02,
+        },
+    ]);
+
+    let mut i2c = I2c::with_mock(&mock).unwrap();
+    i2c.set_slave_address(0x20).unwrap();
+
+    // A driver that ignores errors
+    let _ = i2c.smbus_send_byte(0x03);
+    i2c.smbus_send_byte(0x01).unwrap();
+    i2c.smbus_send_byte(0x02).unwrap();
+
+    assert_eq!(
+        mismatch(mock.done()),
+        "transaction 0: expected SmbusSendByte { address: 32, value: 1 }, \
+         got SmbusSendByte { address: 32, value: 3 }"
+    );
+}
+
+#[test]
+fn done_reports_remaining_transactions() {
+    let mock = Mock::new([
+        Transaction::SmbusReceiveByte {
+            address: 0x20,
+            value: 0x01,
+        },
+        Transaction::SmbusReceiveByte {
+            address: 0x20,
+            value: 0x02,
+        },
+    ]);
+
+    let mut i2c = I2c::with_mock(&mock).unwrap();
+    i2c.set_slave_address(0x20).unwrap();
+    assert_eq!(i2c.smbus_receive_byte().unwrap(), 0x01);
+
+    assert_eq!(
+        mismatch(mock.done()),
+        "transaction 1: expected SmbusReceiveByte { address: 32, value: 2 }, got end of script"
+    );
+}
+
+#[test]
+fn smbus_swapped() {
+    // The expected transactions contain the values as they're transferred on the bus
+    let mock = Mock::new([
+        Transaction::SmbusReadWord {
+            address: 0x20,
+            command: 0x01,
+            value: 0x1234,
+        },
+        Transaction::SmbusWriteWord {
+            address: 0x20,
+            command: 0x02,
+            value: 0x3412,
+        },
+        Transaction::SmbusProcessCall {
+            address: 0x20,
+            command: 0x03,
+            value: 0x3412,
+            response: 0xabcd,
+        },
+    ]);
+
+    let mut i2c = I2c::with_mock(&mock).unwrap();
+    i2c.set_slave_address(0x20).unwrap();
+
+    assert_eq!(i2c.smbus_read_word_swapped(0x01).unwrap(), 0x3412);
+    i2c.smbus_write_word_swapped(0x02, 0x1234).unwrap();
+    assert_eq!(
+        i2c.smbus_process_call_swapped(0x03, 0x1234).unwrap(),
+        0xcdab
+    );
+
+    mock.done().unwrap();
+}
+
+#[test]
+fn smbus_block_read() {
+    let mock = Mock::new([
+        Transaction::SmbusBlockRead {
+            address: 0x20,
+            command: 0x01,
+            data: vec![0x01, 0x02, 0x03],
+        },
+        Transaction::SmbusBlockRead {
+            address: 0x20,
+            command: 0x02,
+            data: vec![0x04, 0x05, 0x06],
+        },
+    ]);
+
+    let mut i2c = I2c::with_mock(&mock).unwrap();
+    i2c.set_slave_address(0x20).unwrap();
+
+    let mut buffer = [0u8; 4];
+    assert_eq!(i2c.smbus_block_read(0x01, &mut buffer).unwrap(), 3);
+    assert_eq!(buffer, [0x01, 0x02, 0x03, 0x00]);
+
+    // The returned length is reported by the slave device, and can exceed the buffer
+    let mut buffer = [0u8; 2];
+    assert_eq!(i2c.smbus_block_read(0x02, &mut buffer).unwrap(), 3);
+    assert_eq!(buffer, [0x04, 0x05]);
+
+    mock.done().unwrap();
+}
+
+#[test]
+fn smbus_block_write_truncated() {
+    // Only the first 32 bytes are transferred
+    let mock = Mock::new([Transaction::SmbusBlockWrite {
+        address: 0x20,
+        command: 0x01,
+        data: vec![0xaa; 32],
+    }]);
+
+    let mut i2c = I2c::with_mock(&mock).unwrap();
+    i2c.set_slave_address(0x20).unwrap();
+    i2c.smbus_block_write(0x01, &[0xaa; 40]).unwrap();
+
+    mock.done().unwrap();
+}
@@ -0,0 +1,392 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process;
+
+use rppal::{i2c, spi, uart};
+
+// Temporary recording file, removed when it goes out of scope
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(name: &str) -> TempFile {
+        TempFile(env::temp_dir().join(format!("rppal-{}-{}.rec", process::id(), name)))
+    }
+
+    fn with_contents(name: &str, contents: &str) -> TempFile {
+        let file = TempFile::new(name);
+        fs::write(&file.0, contents).unwrap();
+
+        file
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn invalid_data(error: io::Error) -> String {
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+    error.to_string()
+}
+
+// Parses the contents as a recording for the specified bus, and returns the
+// description of the parse error
+macro_rules! parse_error {
+    ($bus:ident, $contents:expr) => {{
+        let file = TempFile::with_contents(concat!(stringify!($bus), "-parse-error"), $contents);
+
+        match $bus::Mock::from_recording(&file.0) {
+            Err($bus::Error::Io(e)) => invalid_data(e),
+            result => panic!("expected I/O error, got {:?}", result.map(|_| ())),
+        }
+    }};
+}
+
+fn i2c_driver(i2c: &mut i2c::I2c) -> i2c::Result<()> {
+    i2c.set_slave_address(0x20)?;
+    i2c.write(&[0x01, 0x02])?;
+
+    let mut buffer = [0u8; 2];
+    i2c.read(&mut buffer)?;
+    assert_eq!(buffer, [0xaa, 0xbb]);
+
+    i2c.write_read(&[0x03], &mut buffer)?;
+    assert_eq!(buffer, [0xcc, 0xdd]);
+
+    i2c.set_slave_address(0x21)?;
+    i2c.smbus_quick_command(true)?;
+    assert_eq!(i2c.smbus_read_word(0x04)?, 0x1234);
+    assert_eq!(i2c.smbus_process_call(0x05, 0x5678)?, 0x9abc);
+    i2c.smbus_block_write(0x06, &[])?;
+
+    let mut buffer = [0u8; 4];
+    assert_eq!(i2c.smbus_block_read(0x07, &mut buffer)?, 3);
+    assert_eq!(buffer, [0x01, 0x02, 0x03, 0x00]);
+
+    Ok(())
+}
+
+#[test]
+fn i2c_round_trip() {
+    let file = TempFile::new("i2c-round-trip");
+
+    let mock = i2c::Mock::new([
+        i2c::Transaction::Write {
+            address: 0x20,
+            data: vec![0x01, 0x02],
+        },
+        i2c::Transaction::Read {
+            address: 0x20,
+            data: vec![0xaa, 0xbb],
+        },
+        i2c::Transaction::WriteRead {
+            address: 0x20,
+            write: vec![0x03],
+            read: vec![0xcc, 0xdd],
+        },
+        i2c::Transaction::SmbusQuickCommand {
+            address: 0x21,
+            command: true,
+        },
+        i2c::Transaction::SmbusReadWord {
+            address: 0x21,
+            command: 0x04,
+            value: 0x1234,
+        },
+        i2c::Transaction::SmbusProcessCall {
+            address: 0x21,
+            command: 0x05,
+            value: 0x5678,
+            response: 0x9abc,
+        },
+        i2c::Transaction::SmbusBlockWrite {
+            address: 0x21,
+            command: 0x06,
+            data: vec![],
+        },
+        i2c::Transaction::SmbusBlockRead {
+            address: 0x21,
+            command: 0x07,
+            data: vec![0x01, 0x02, 0x03],
+        },
+    ]);
+
+    let mut i2c = i2c::I2c::with_mock(&mock).unwrap();
+    i2c.start_recording(&file.0).unwrap();
+    i2c_driver(&mut i2c).unwrap();
+    i2c.stop_recording().unwrap();
+    mock.done().unwrap();
+
+    let replay = i2c::Mock::from_recording(&file.0).unwrap();
+    let mut i2c = i2c::I2c::with_mock(&replay).unwrap();
+    i2c_driver(&mut i2c).unwrap();
+    replay.done().unwrap();
+}
+
+#[test]
+fn i2c_failed_transactions() {
+    let file = TempFile::new("i2c-failed");
+
+    let mock = i2c::Mock::new([i2c::Transaction::SmbusSendByte {
+        address: 0x20,
+        value: 0x01,
+    }]);
+
+    let mut i2c = i2c::I2c::with_mock(&mock).unwrap();
+    i2c.set_slave_address(0x20).unwrap();
+    i2c.start_recording(&file.0).unwrap();
+    assert!(i2c.smbus_send_byte(0x02).is_err());
+    i2c.smbus_send_byte(0x01).unwrap();
+    i2c.stop_recording().unwrap();
+
+    // Failed transactions are stored as comments, and aren't replayed
+    let contents = fs::read_to_string(&file.0).unwrap();
+    assert!(contents.lines().any(|line| line.starts_with('#')));
+
+    let replay = i2c::Mock::from_recording(&file.0).unwrap();
+    let mut i2c = i2c::I2c::with_mock(&replay).unwrap();
+    i2c.set_slave_address(0x20).unwrap();
+    i2c.smbus_send_byte(0x01).unwrap();
+    replay.done().unwrap();
+}
+
+fn spi_driver(spi: &mut spi::Spi) -> spi::Result<()> {
+    spi.write(&[0x01, 0x02])?;
+
+    let mut buffer = [0u8; 2];
+    spi.read(&mut buffer)?;
+    assert_eq!(buffer, [0xaa, 0xbb]);
+
+    spi.transfer(&mut buffer, &[0x03, 0x04])?;
+    assert_eq!(buffer, [0xcc, 0xdd]);
+
+    let mut read_buffer = [0u8; 3];
+    spi.transfer_segments(&[
+        spi::Segment::with_settings(None, Some(&[0x05]), 500_000, 10, 8, true),
+        spi::Segment::with_read(&mut read_buffer),
+        spi::Segment::with_write(&[]),
+    ])?;
+    assert_eq!(read_buffer, [0x01, 0x02, 0x03]);
+
+    Ok(())
+}
+
+#[test]
+fn spi_round_trip() {
+    let file = TempFile::new("spi-round-trip");
+
+    let mock = spi::Mock::new([
+        spi::Transaction::Write {
+            data: vec![0x01, 0x02],
+        },
+        spi::Transaction::Read {
+            data: vec![0xaa, 0xbb],
+        },
+        spi::Transaction::Transfer {
+            write: vec![0x03, 0x04],
+            read: vec![0xcc, 0xdd],
+        },
+        spi::Transaction::TransferSegments(vec![
+            spi::SegmentTransfer {
+                clock_speed: 500_000,
+                delay: 10,
+                bits_per_word: 8,
+                ss_change: true,
+                ..spi::SegmentTransfer::with_write(vec![0x05])
+            },
+            spi::SegmentTransfer::with_read(vec![0x01, 0x02, 0x03]),
+            spi::SegmentTransfer::with_write(vec![]),
+        ]),
+    ]);
+
+    let mut spi = spi::Spi::with_mock(&mock, 1_000_000, spi::Mode::Mode0).unwrap();
+    spi.start_recording(&file.0).unwrap();
+    spi_driver(&mut spi).unwrap();
+    spi.stop_recording().unwrap();
+    mock.done().unwrap();
+
+    let replay = spi::Mock::from_recording(&file.0).unwrap();
+    let mut spi = spi::Spi::with_mock(&replay, 1_000_000, spi::Mode::Mode0).unwrap();
+    spi_driver(&mut spi).unwrap();
+    replay.done().unwrap();
+}
+
+fn uart_driver(uart: &mut uart::Uart) -> uart::Result<()> {
+    uart.write(b"AT")?;
+    uart.write(b"\r\n")?;
+
+    let mut buffer = [0u8; 3];
+    assert_eq!(uart.read(&mut buffer)?, 3);
+    assert_eq!(&buffer, b"OK\r");
+    assert_eq!(uart.read(&mut buffer)?, 1);
+    assert_eq!(buffer[0], b'\n');
+
+    Ok(())
+}
+
+#[test]
+fn uart_round_trip() {
+    let file = TempFile::new("uart-round-trip");
+
+    let mock = uart::Mock::new([
+        uart::Transaction::Write {
+            data: b"AT\r\n".to_vec(),
+        },
+        uart::Transaction::Read {
+            data: b"OK\r\n".to_vec(),
+        },
+    ]);
+
+    let mut uart = uart::Uart::with_mock(&mock, 115_200, uart::Parity::None, 8, 1).unwrap();
+    uart.start_recording(&file.0).unwrap();
+    uart_driver(&mut uart).unwrap();
+
+    // Reads without any incoming data aren't recorded
+    let mut buffer = [0u8; 1];
+    assert_eq!(uart.read(&mut buffer).unwrap(), 0);
+
+    uart.stop_recording().unwrap();
+    mock.done().unwrap();
+
+    let replay = uart::Mock::from_recording(&file.0).unwrap();
+    let mut uart = uart::Uart::with_mock(&replay, 115_200, uart::Parity::None, 8, 1).unwrap();
+    uart_driver(&mut uart).unwrap();
+    replay.done().unwrap();
+}
+
+#[test]
+fn skip_comments_and_empty_lines() {
+    let file = TempFile::with_contents(
+        "comments",
+        "rppal-recording 1 uart 0\n\
+         # 5 write failed: I/O error\n\
+         \n\
+         10 write 0102\n",
+    );
+
+    let replay = uart::Mock::from_recording(&file.0).unwrap();
+    let mut uart = uart::Uart::with_mock(&replay, 115_200, uart::Parity::None, 8, 1).unwrap();
+    uart.write(&[0x01, 0x02]).unwrap();
+    replay.done().unwrap();
+}
+
+#[test]
+fn short_transfers() {
+    // Short reads and writes are stored with the number of bytes that were transferred
+    let file = TempFile::with_contents(
+        "i2c-short",
+        "rppal-recording 1 i2c 0\n\
+         10 read 32 aa\n\
+         20 write 32 01\n",
+    );
+
+    let replay = i2c::Mock::from_recording(&file.0).unwrap();
+    let mut i2c = i2c::I2c::with_mock(&replay).unwrap();
+    i2c.set_slave_address(0x20).unwrap();
+
+    let mut buffer = [0u8; 2];
+    assert_eq!(i2c.read(&mut buffer).unwrap(), 1);
+    assert_eq!(buffer, [0xaa, 0x00]);
+    assert_eq!(i2c.write(&[0x01, 0x02]).unwrap(), 1);
+    replay.done().unwrap();
+
+    let file = TempFile::with_contents(
+        "spi-short",
+        "rppal-recording 1 spi 0\n\
+         10 read aa\n\
+         20 write 01\n",
+    );
+
+    let replay = spi::Mock::from_recording(&file.0).unwrap();
+    let mut spi = spi::Spi::with_mock(&replay, 1_000_000, spi::Mode::Mode0).unwrap();
+
+    let mut buffer = [0u8; 2];
+    assert_eq!(spi.read(&mut buffer).unwrap(), 1);
+    assert_eq!(buffer, [0xaa, 0x00]);
+    assert_eq!(spi.write(&[0x01, 0x02]).unwrap(), 1);
+    replay.done().unwrap();
+}
+
+#[test]
+fn parse_error_header() {
+    assert_eq!(parse_error!(i2c, ""), "line 1: not a recording");
+    assert_eq!(
+        parse_error!(i2c, "recording 1 i2c 0\n"),
+        "line 1: not a recording"
+    );
+    assert_eq!(
+        parse_error!(i2c, "rppal-recording 2 i2c 0\n"),
+        "line 1: unsupported version 2"
+    );
+    assert_eq!(
+        parse_error!(i2c, "rppal-recording 1\n"),
+        "line 1: missing field"
+    );
+}
+
+#[test]
+fn parse_error_bus() {
+    assert_eq!(
+        parse_error!(i2c, "rppal-recording 1 spi 0\n"),
+        "line 1: expected i2c recording, got spi"
+    );
+    assert_eq!(
+        parse_error!(spi, "rppal-recording 1 uart 0\n"),
+        "line 1: expected spi recording, got uart"
+    );
+    assert_eq!(
+        parse_error!(uart, "rppal-recording 1 i2c 0\n"),
+        "line 1: expected uart recording, got i2c"
+    );
+}
+
+#[test]
+fn parse_error_data() {
+    assert_eq!(
+        parse_error!(spi, "rppal-recording 1 spi 0\n10 write abc\n"),
+        "line 2: invalid data \"abc\""
+    );
+    assert_eq!(
+        parse_error!(spi, "rppal-recording 1 spi 0\n10 write 0g\n"),
+        "line 2: invalid data \"0g\""
+    );
+    assert_eq!(
+        parse_error!(uart, "rppal-recording 1 uart 0\n10 read 01\n20 read 1\n"),
+        "line 3: invalid data \"1\""
+    );
+}
+
+#[test]
+fn parse_error_fields() {
+    assert_eq!(
+        parse_error!(uart, "rppal-recording 1 uart 0\n10 write 01 02\n"),
+        "line 2: unexpected field \"02\""
+    );
+    assert_eq!(
+        parse_error!(i2c, "rppal-recording 1 i2c 0\n10 smbus_read_byte 32 1\n"),
+        "line 2: missing field"
+    );
+    assert_eq!(
+        parse_error!(
+            i2c,
+            "rppal-recording 1 i2c 0\n10 smbus_quick_command 32 2\n"
+        ),
+        "line 2: invalid boolean \"2\""
+    );
+    assert_eq!(
+        parse_error!(
+            i2c,
+            "rppal-recording 1 i2c 0\n10 smbus_read_byte 32 256 1\n"
+        ),
+        "line 2: invalid number \"256\""
+    );
+    assert_eq!(
+        parse_error!(spi, "rppal-recording 1 spi 0\n10 flush\n"),
+        "line 2: unknown transaction \"flush\""
+    );
+}
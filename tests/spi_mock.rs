@@ -0,0 +1,154 @@
+use rppal::spi::{Mock, Mode, Segment, SegmentTransfer, Spi, Transaction};
+
+mod common;
+
+use common::mismatch;
+
+#[test]
+fn matching_transactions() {
+    let mock = Mock::new([
+        Transaction::Write {
+            data: vec![0x01, 0x02],
+        },
+        Transaction::Read {
+            data: vec![0xaa, 0xbb],
+        },
+        Transaction::Transfer {
+            write: vec![0x03, 0x04],
+            read: vec![0xcc, 0xdd],
+        },
+    ]);
+
+    let mut spi = Spi::with_mock(&mock, 1_000_000, Mode::Mode0).unwrap();
+
+    assert_eq!(spi.write(&[0x01, 0x02]).unwrap(), 2);
+
+    let mut buffer = [0u8; 2];
+    assert_eq!(spi.read(&mut buffer).unwrap(), 2);
+    assert_eq!(buffer, [0xaa, 0xbb]);
+
+    let mut buffer = [0u8; 2];
+    assert_eq!(spi.transfer(&mut buffer, &[0x03, 0x04]).unwrap(), 2);
+    assert_eq!(buffer, [0xcc, 0xdd]);
+
+    mock.done().unwrap();
+}
+
+#[test]
+fn configuration() {
+    let mock = Mock::new([]);
+
+    let spi = Spi::with_mock(&mock, 1_000_000, Mode::Mode3).unwrap();
+    assert_eq!(spi.mode().unwrap(), Mode::Mode3);
+    assert_eq!(spi.clock_speed().unwrap(), 1_000_000);
+
+    mock.done().unwrap();
+}
+
+#[test]
+fn transfer_segments() {
+    let mock = Mock::new([Transaction::TransferSegments(vec![
+        SegmentTransfer::with_write(vec![0x01]),
+        SegmentTransfer::with_read(vec![0xaa, 0xbb]),
+        SegmentTransfer::new(vec![0xcc], vec![0x02]),
+    ])]);
+
+    let spi = Spi::with_mock(&mock, 1_000_000, Mode::Mode0).unwrap();
+
+    let mut read_buffer = [0u8; 2];
+    let mut duplex_buffer = [0u8; 1];
+    spi.transfer_segments(&[
+        Segment::with_write(&[0x01]),
+        Segment::with_read(&mut read_buffer),
+        Segment::new(&mut duplex_buffer, &[0x02]),
+    ])
+    .unwrap();
+
+    assert_eq!(read_buffer, [0xaa, 0xbb]);
+    assert_eq!(duplex_buffer, [0xcc]);
+
+    mock.done().unwrap();
+}
+
+#[test]
+fn transfer_segments_settings() {
+    let mock = Mock::new([Transaction::TransferSegments(vec![SegmentTransfer {
+        clock_speed: 500_000,
+        delay: 10,
+        ..SegmentTransfer::with_write(vec![0x01])
+    }])]);
+
+    let spi = Spi::with_mock(&mock, 1_000_000, Mode::Mode0).unwrap();
+
+    // Different settings don't match the script
+    let result = spi.transfer_segments(&[Segment::with_write(&[0x01])]);
+    assert!(mismatch(result).starts_with("transaction 0: expected TransferSegments("));
+
+    let mut segment = Segment::with_write(&[0x01]);
+    segment.set_clock_speed(500_000);
+    segment.set_delay(10);
+    spi.transfer_segments(&[segment]).unwrap();
+
+    // The earlier mismatch is still reported
+    assert!(mock.done().is_err());
+}
+
+#[test]
+fn mismatch_read_len() {
+    let mock = Mock::new([Transaction::Read {
+        data: vec![0x01, 0x02],
+    }]);
+
+    let mut spi = Spi::with_mock(&mock, 1_000_000, Mode::Mode0).unwrap();
+
+    let mut buffer = [0u8; 3];
+    assert_eq!(
+        mismatch(spi.read(&mut buffer)),
+        "transaction 0: expected Read { data: [1, 2] }, got Read { len: 3 }"
+    );
+}
+
+#[test]
+fn mismatch_transaction_type() {
+    let mock = Mock::new([
+        Transaction::Write { data: vec![0x01] },
+        Transaction::Write { data: vec![0x02] },
+    ]);
+
+    let mut spi = Spi::with_mock(&mock, 1_000_000, Mode::Mode0).unwrap();
+    spi.write(&[0x01]).unwrap();
+
+    let mut buffer = [0u8; 1];
+    assert_eq!(
+        mismatch(spi.transfer(&mut buffer, &[0x02])),
+        "transaction 1: expected Write { data: [2] }, got Transfer { write: [2], len: 1 }"
+    );
+}
+
+#[test]
+fn done_reports_discarded_mismatch() {
+    let mock = Mock::new([Transaction::Write { data: vec![0x01] }]);
+
+    let mut spi = Spi::with_mock(&mock, 1_000_000, Mode::Mode0).unwrap();
+
+    // A driver that ignores errors
+    let _ = spi.write(&[0x02]);
+    spi.write(&[0x01]).unwrap();
+
+    assert_eq!(
+        mismatch(mock.done()),
+        "transaction 0: expected Write { data: [1] }, got Write { data: [2] }"
+    );
+}
+
+#[test]
+fn done_reports_remaining_transactions() {
+    let mock = Mock::new([Transaction::Write { data: vec![0x01] }]);
+
+    let _spi = Spi::with_mock(&mock, 1_000_000, Mode::Mode0).unwrap();
+
+    assert_eq!(
+        mismatch(mock.done()),
+        "transaction 0: expected Write { data: [1] }, got end of script"
+    );
+}
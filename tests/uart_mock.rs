@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use rppal::uart::{Mock, Parity, Transaction, Uart};
+
+mod common;
+
+use common::mismatch;
+
+fn uart(mock: &Mock) -> Uart {
+    Uart::with_mock(mock, 115_200, Parity::None, 8, 1).unwrap()
+}
+
+#[test]
+fn matching_transactions() {
+    let mock = Mock::new([
+        Transaction::Write {
+            data: b"AT\r\n".to_vec(),
+        },
+        Transaction::Read {
+            data: b"OK\r\n".to_vec(),
+        },
+    ]);
+
+    let mut uart = uart(&mock);
+
+    assert_eq!(uart.write(b"AT\r\n").unwrap(), 4);
+
+    let mut buffer = [0u8; 4];
+    assert_eq!(uart.read(&mut buffer).unwrap(), 4);
+    assert_eq!(&buffer, b"OK\r\n");
+
+    mock.done().unwrap();
+}
+
+#[test]
+fn partial_read() {
+    let mock = Mock::new([Transaction::Read {
+        data: vec![0x01, 0x02, 0x03, 0x04],
+    }]);
+
+    let mut uart = uart(&mock);
+    assert_eq!(uart.input_len().unwrap(), 4);
+
+    let mut buffer = [0u8; 3];
+    assert_eq!(uart.read(&mut buffer).unwrap(), 3);
+    assert_eq!(buffer, [0x01, 0x02, 0x03]);
+    assert_eq!(uart.input_len().unwrap(), 1);
+
+    assert_eq!(uart.read(&mut buffer).unwrap(), 1);
+    assert_eq!(buffer[0], 0x04);
+
+    // No more incoming data
+    assert_eq!(uart.read(&mut buffer).unwrap(), 0);
+    assert_eq!(uart.input_len().unwrap(), 0);
+
+    mock.done().unwrap();
+}
+
+#[test]
+fn partial_write() {
+    let mock = Mock::new([
+        Transaction::Write {
+            data: vec![0x01, 0x02, 0x03],
+        },
+        Transaction::Read { data: vec![0xaa] },
+    ]);
+
+    let mut uart = uart(&mock);
+
+    // Incoming data isn't available until the write has been completed
+    let mut buffer = [0u8; 1];
+    assert_eq!(uart.write(&[0x01, 0x02]).unwrap(), 2);
+    assert_eq!(uart.read(&mut buffer).unwrap(), 0);
+
+    assert_eq!(uart.write(&[0x03]).unwrap(), 1);
+    assert_eq!(uart.read(&mut buffer).unwrap(), 1);
+    assert_eq!(buffer, [0xaa]);
+
+    mock.done().unwrap();
+}
+
+#[test]
+fn mismatch_partial_write() {
+    let mock = Mock::new([Transaction::Write {
+        data: vec![0x01, 0x02, 0x03],
+    }]);
+
+    let mut uart = uart(&mock);
+    uart.write(&[0x01]).unwrap();
+
+    assert_eq!(
+        mismatch(uart.write(&[0x03])),
+        "transaction 0: expected Write { data: [2, 3] }, got Write { data: [3] }"
+    );
+}
+
+#[test]
+fn mismatch_end_of_script() {
+    let mock = Mock::new([]);
+
+    let mut uart = uart(&mock);
+
+    assert_eq!(
+        mismatch(uart.write(&[0x01])),
+        "transaction 0: expected end of script, got Write { data: [1] }"
+    );
+}
+
+#[test]
+fn blocking_read() {
+    let mock = Mock::new([Transaction::Write { data: vec![0x01] }]);
+
+    let mut uart = uart(&mock);
+    uart.set_read_mode(1, Duration::default()).unwrap();
+
+    let mut buffer = [0u8; 4];
+    assert_eq!(
+        mismatch(uart.read(&mut buffer)),
+        "transaction 0: expected Write { data: [1] }, got blocking read of up to 4 bytes"
+    );
+
+    // A read with a timeout returns without any data
+    uart.set_read_mode(0, Duration::from_millis(100)).unwrap();
+    assert_eq!(uart.read(&mut buffer).unwrap(), 0);
+}
+
+#[test]
+fn done_reports_discarded_mismatch() {
+    let mock = Mock::new([Transaction::Write { data: vec![0x01] }]);
+
+    let mut uart = uart(&mock);
+
+    // A driver that ignores errors
+    let _ = uart.write(&[0x02]);
+    uart.write(&[0x01]).unwrap();
+
+    assert_eq!(
+        mismatch(mock.done()),
+        "transaction 0: expected Write { data: [1] }, got Write { data: [2] }"
+    );
+}
+
+#[test]
+fn done_reports_remaining_transactions() {
+    let mock = Mock::new([Transaction::Read { data: vec![0x01] }]);
+
+    let _uart = uart(&mock);
+
+    assert_eq!(
+        mismatch(mock.done()),
+        "transaction 0: expected Read { data: [1] }, got end of script"
+    );
+}